@@ -0,0 +1,360 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use pueue_lib::state::{GroupStatus, State};
+use pueue_lib::task::{TaskResult, TaskStatus};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use crate::notify::{self, FinishedTask};
+
+/// Maximum number of events kept in memory
+pub const DEFAULT_CAPACITY: usize = 1000;
+
+/// Something that changed between two successive daemon states
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActivityKind {
+    TaskAdded {
+        id: usize,
+        group: String,
+        command: String,
+    },
+    TaskStarted {
+        id: usize,
+    },
+    TaskPaused {
+        id: usize,
+    },
+    TaskResumed {
+        id: usize,
+    },
+    TaskStashed {
+        id: usize,
+    },
+    TaskEnqueued {
+        id: usize,
+    },
+    TaskFinished(FinishedTask),
+    TaskRemoved {
+        id: usize,
+    },
+    GroupAdded {
+        group: String,
+    },
+    GroupRemoved {
+        group: String,
+    },
+    GroupPaused {
+        group: String,
+    },
+    GroupResumed {
+        group: String,
+    },
+    ParallelChanged {
+        group: String,
+        from: usize,
+        to: usize,
+    },
+}
+
+impl ActivityKind {
+    pub fn is_task_event(&self) -> bool {
+        !self.is_group_event()
+    }
+
+    pub fn is_group_event(&self) -> bool {
+        matches!(
+            self,
+            ActivityKind::GroupAdded { .. }
+                | ActivityKind::GroupRemoved { .. }
+                | ActivityKind::GroupPaused { .. }
+                | ActivityKind::GroupResumed { .. }
+                | ActivityKind::ParallelChanged { .. }
+        )
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self, ActivityKind::TaskFinished(task) if task.result != TaskResult::Success)
+    }
+
+    /// Human readable description of the event
+    pub fn describe(&self) -> String {
+        match self {
+            ActivityKind::TaskAdded { id, group, command } => {
+                format!("Task #{} added to '{}': {}", id, group, command)
+            }
+            ActivityKind::TaskStarted { id } => format!("Task #{} started", id),
+            ActivityKind::TaskPaused { id } => format!("Task #{} paused", id),
+            ActivityKind::TaskResumed { id } => format!("Task #{} resumed", id),
+            ActivityKind::TaskStashed { id } => format!("Task #{} stashed", id),
+            ActivityKind::TaskEnqueued { id } => format!("Task #{} enqueued", id),
+            ActivityKind::TaskFinished(task) => format!(
+                "Task #{} finished ({}): {}",
+                task.id,
+                task.result_name(),
+                task.command
+            ),
+            ActivityKind::TaskRemoved { id } => format!("Task #{} removed", id),
+            ActivityKind::GroupAdded { group } => format!("Group '{}' added", group),
+            ActivityKind::GroupRemoved { group } => format!("Group '{}' removed", group),
+            ActivityKind::GroupPaused { group } => format!("Group '{}' paused", group),
+            ActivityKind::GroupResumed { group } => format!("Group '{}' resumed", group),
+            ActivityKind::ParallelChanged { group, from, to } => {
+                format!("Group '{}' parallel limit {} → {}", group, from, to)
+            }
+        }
+    }
+}
+
+/// A timestamped activity event
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityEvent {
+    pub time: DateTime<Local>,
+    pub kind: ActivityKind,
+    /// Name of the daemon it happened on, if that one wasn't shown
+    pub daemon: Option<String>,
+}
+
+impl ActivityEvent {
+    /// Description of the event, prefixed with the daemon it happened on
+    pub fn describe(&self) -> String {
+        match &self.daemon {
+            Some(daemon) => format!("[{}] {}", daemon, self.kind.describe()),
+            None => self.kind.describe(),
+        }
+    }
+}
+
+/// Compute all changes between two successive states, in a stable order:
+/// group changes first, then task changes by ID.
+pub fn diff_states(previous: &State, new: &State) -> Vec<ActivityKind> {
+    let mut changes = Vec::new();
+
+    for (name, group) in &new.groups {
+        match previous.groups.get(name) {
+            None => changes.push(ActivityKind::GroupAdded {
+                group: name.clone(),
+            }),
+            Some(old) => {
+                match (&old.status, &group.status) {
+                    (GroupStatus::Paused, GroupStatus::Running) => {
+                        changes.push(ActivityKind::GroupResumed {
+                            group: name.clone(),
+                        })
+                    }
+                    (GroupStatus::Running, GroupStatus::Paused) => {
+                        changes.push(ActivityKind::GroupPaused {
+                            group: name.clone(),
+                        })
+                    }
+                    _ => {}
+                }
+                if old.parallel_tasks != group.parallel_tasks {
+                    changes.push(ActivityKind::ParallelChanged {
+                        group: name.clone(),
+                        from: old.parallel_tasks,
+                        to: group.parallel_tasks,
+                    });
+                }
+            }
+        }
+    }
+    for name in previous.groups.keys() {
+        if !new.groups.contains_key(name) {
+            changes.push(ActivityKind::GroupRemoved {
+                group: name.clone(),
+            });
+        }
+    }
+
+    let mut finished = notify::finished_tasks(previous, new).into_iter().peekable();
+    for (id, task) in &new.tasks {
+        let id = *id;
+        match previous.tasks.get(&id) {
+            None => changes.push(ActivityKind::TaskAdded {
+                id,
+                group: task.group.clone(),
+                command: task.command.clone(),
+            }),
+            Some(old) => match (&old.status, &task.status) {
+                (
+                    TaskStatus::Queued { .. } | TaskStatus::Stashed { .. },
+                    TaskStatus::Running { .. },
+                ) => changes.push(ActivityKind::TaskStarted { id }),
+                (TaskStatus::Running { .. }, TaskStatus::Paused { .. }) => {
+                    changes.push(ActivityKind::TaskPaused { id })
+                }
+                (TaskStatus::Paused { .. }, TaskStatus::Running { .. }) => {
+                    changes.push(ActivityKind::TaskResumed { id })
+                }
+                (TaskStatus::Queued { .. }, TaskStatus::Stashed { .. }) => {
+                    changes.push(ActivityKind::TaskStashed { id })
+                }
+                (TaskStatus::Stashed { .. }, TaskStatus::Queued { .. }) => {
+                    changes.push(ActivityKind::TaskEnqueued { id })
+                }
+                _ => {}
+            },
+        }
+        if finished.peek().is_some_and(|task| task.id == id) {
+            changes.push(ActivityKind::TaskFinished(finished.next().unwrap()));
+        }
+    }
+    for id in previous.tasks.keys() {
+        if !new.tasks.contains_key(id) {
+            changes.push(ActivityKind::TaskRemoved { id: *id });
+        }
+    }
+
+    changes
+}
+
+/// Which events to show in the activity pane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ActivityCategory {
+    #[default]
+    All,
+    Tasks,
+    Groups,
+    Failures,
+}
+
+impl ActivityCategory {
+    pub fn next(self) -> Self {
+        match self {
+            ActivityCategory::All => ActivityCategory::Tasks,
+            ActivityCategory::Tasks => ActivityCategory::Groups,
+            ActivityCategory::Groups => ActivityCategory::Failures,
+            ActivityCategory::Failures => ActivityCategory::All,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ActivityCategory::All => "all",
+            ActivityCategory::Tasks => "tasks",
+            ActivityCategory::Groups => "groups",
+            ActivityCategory::Failures => "failures",
+        }
+    }
+
+    fn matches(self, kind: &ActivityKind) -> bool {
+        match self {
+            ActivityCategory::All => true,
+            ActivityCategory::Tasks => kind.is_task_event(),
+            ActivityCategory::Groups => kind.is_group_event(),
+            ActivityCategory::Failures => kind.is_failure(),
+        }
+    }
+}
+
+/// Category and text filter for the activity pane
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActivityFilter {
+    pub category: ActivityCategory,
+    /// Case-insensitive substring that the description must contain
+    pub query: String,
+}
+
+impl ActivityFilter {
+    pub fn matches(&self, event: &ActivityEvent) -> bool {
+        if !self.category.matches(&event.kind) {
+            return false;
+        }
+        self.query.is_empty()
+            || event
+                .describe()
+                .to_lowercase()
+                .contains(&self.query.to_lowercase())
+    }
+}
+
+/// Bounded, timestamped record of everything that happened, optionally
+/// mirrored to a file
+#[derive(Debug)]
+pub struct ActivityLog {
+    events: VecDeque<ActivityEvent>,
+    capacity: usize,
+    file: Option<File>,
+}
+
+impl Default for ActivityLog {
+    fn default() -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity: DEFAULT_CAPACITY,
+            file: None,
+        }
+    }
+}
+
+impl ActivityLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// Also append every event to `path`
+    pub fn with_file(mut self, path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| anyhow::anyhow!("Failed to open activity log {:?}: {}", path, e))?;
+        self.file = Some(file);
+        Ok(self)
+    }
+
+    pub fn push(&mut self, kind: ActivityKind) {
+        self.record(kind, None);
+    }
+
+    /// Record an event of a daemon that isn't shown
+    pub fn push_from(&mut self, daemon: &str, kind: ActivityKind) {
+        self.record(kind, Some(daemon.to_string()));
+    }
+
+    fn record(&mut self, kind: ActivityKind, daemon: Option<String>) {
+        let event = ActivityEvent {
+            time: Local::now(),
+            kind,
+            daemon,
+        };
+        if let Some(file) = &mut self.file {
+            // Errors writing the mirror file must never break the UI
+            let _ = writeln!(
+                file,
+                "{} {}",
+                event.time.format("%Y-%m-%d %H:%M:%S"),
+                event.describe()
+            );
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Events matching the filter, newest first
+    pub fn filtered<'a>(
+        &'a self,
+        filter: &'a ActivityFilter,
+    ) -> impl Iterator<Item = &'a ActivityEvent> + 'a {
+        self.events.iter().rev().filter(|e| filter.matches(e))
+    }
+}
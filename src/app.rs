@@ -1,12 +1,35 @@
 use anyhow::Result;
+use pueue_lib::message::request::Signal;
 use pueue_lib::message::EditableTask;
-use pueue_lib::state::State;
-use pueue_lib::task::TaskStatus;
-use std::collections::HashSet;
+use pueue_lib::state::{GroupStatus, State};
+use pueue_lib::task::{Task, TaskStatus};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
 use std::time::Instant;
 
-use crate::pueue_client::PueueClient;
-use crate::ui::TextInput;
+use crate::activity::{self, ActivityFilter, ActivityKind, ActivityLog};
+use crate::compat::{DaemonVersion, RequestKind};
+use crate::complete;
+use crate::config::{self, Config, DEFAULT_DAEMON};
+use crate::daemon::{
+    Connection, DaemonEntry, DaemonView, Effect, Job, JobFuture, JobKind, Outcome, PendingJob,
+};
+use crate::diagnostics::Report;
+use crate::filter::{self, SortMode, TaskFilter};
+use crate::history::{History, HistorySearch};
+use crate::keymap::{Keymap, Mode};
+use crate::layout::{LayoutState, Pane, RESIZE_STEP};
+use crate::messages::Messages;
+use crate::move_tasks;
+use crate::notify::Notifier;
+use crate::palette::{self, PaletteScope};
+use crate::pueue_client::{AddOptions, PueueClient, RestartOptions};
+use crate::queue::{self, QueueMove};
+use crate::templates::{self, Template};
+use crate::theme::Theme;
+use crate::ui::{HitRegions, TextInput};
+use crate::undo::{self, UndoEntry, UndoOp, UndoStack};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Action {
@@ -14,6 +37,9 @@ pub enum Action {
     NavigateDown,
     NavigateTop,
     NavigateBottom,
+    HalfPageDown,
+    HalfPageUp,
+    CenterSelection,
     KillTask,
     TogglePause,
     ToggleTaskPause,
@@ -27,6 +53,8 @@ pub enum Action {
     ScrollLogDown,
     ScrollLogPageUp,
     ScrollLogPageDown,
+    ScrollLogTop,
+    ScrollLogBottom,
     // Input mode actions
     StartAddTask,
     StartEditTask,
@@ -40,27 +68,733 @@ pub enum Action {
     InputRight,
     InputHome,
     InputEnd,
+    InputNewline,
+    InputWordLeft,
+    InputWordRight,
+    InputDeleteWord,
+    InputHistoryPrev,
+    InputHistoryNext,
+    InputHistorySearch,
+    InputComplete,
     // Phase 2: Power features
     StashTask,
     EnqueueTask,
     SwitchUp,
     SwitchDown,
+    MoveToQueueTop,
+    MoveToQueueBottom,
+    RaisePriority,
+    LowerPriority,
     IncreaseParallel,
     DecreaseParallel,
     // Tree navigation
     CollapseGroup,
     ExpandGroup,
+    // Activity pane
+    ToggleActivity,
+    CloseActivity,
+    ScrollActivityUp,
+    ScrollActivityDown,
+    ScrollActivityPageUp,
+    ScrollActivityPageDown,
+    CycleActivityFilter,
+    StartActivitySearch,
+    // Message history
+    ToggleMessages,
+    CloseMessages,
+    ScrollMessagesUp,
+    ScrollMessagesDown,
+    // Help overlay
+    ToggleHelp,
+    CloseHelp,
+    ScrollHelpUp,
+    ScrollHelpDown,
+    ScrollHelpPageUp,
+    ScrollHelpPageDown,
+    StartHelpSearch,
+    // Command palette
+    OpenPalette,
+    ClosePalette,
+    PaletteNext,
+    PalettePrev,
+    ExecutePalette,
+    SetParallel(usize),
+    KillWithSignal(Signal),
+    // Moving tasks between groups
+    ToggleMark,
+    ClearMarks,
+    StartMoveToGroup,
+    MoveToGroup(String),
+    // Task list filter and order
+    StartTaskFilter,
+    CycleSortMode,
+    // Templates
+    AddBlankTask,
+    UseTemplate(String),
+    SaveAsTemplate,
+    // Undo
+    Undo,
+    ShowUndoHistory,
+    UndoAt(usize),
+    // Daemons
+    NextDaemon,
+    PrevDaemon,
+    SwitchDaemon(usize),
+    // Connection diagnostics
+    ToggleDiagnostics,
+    CloseDiagnostics,
+    RunDiagnostics,
+    ScrollDiagnosticsUp,
+    ScrollDiagnosticsDown,
+    // Layout
+    GrowTaskList,
+    ShrinkTaskList,
+    CycleLayout,
+    ToggleZoom,
+    ToggleStatusBar,
+    ToggleHelpBar,
+    // Pane focus
+    FocusNext,
+    FocusPrev,
+    FocusTaskList,
+    Focus(Pane),
+    ToggleLogPane,
+    ScrollDetailsUp,
+    ScrollDetailsDown,
+    ScrollDetailsPageUp,
+    ScrollDetailsPageDown,
+    ScrollDetailsTop,
+    ScrollDetailsBottom,
+    ScrollLogPaneUp,
+    ScrollLogPaneDown,
+    ScrollLogPanePageUp,
+    ScrollLogPanePageDown,
+    ScrollLogPaneTop,
+    ScrollLogPaneBottom,
+    // Mouse actions
+    SelectItem(TreeItem),
+    OpenItem(TreeItem),
     // Confirmation actions
     ConfirmAction,
     CancelConfirm,
     Quit,
 }
 
+/// Name (as used in the config file) and description of every action that can
+/// be bound to a key. Actions carrying data, like `InputChar`, aren't listed.
+const ACTION_INFO: &[(Action, &str, &str)] = &[
+    (Action::NavigateUp, "navigate_up", "Move selection up"),
+    (Action::NavigateDown, "navigate_down", "Move selection down"),
+    (Action::NavigateTop, "navigate_top", "Go to the first item"),
+    (
+        Action::NavigateBottom,
+        "navigate_bottom",
+        "Go to the last item",
+    ),
+    (
+        Action::HalfPageDown,
+        "half_page_down",
+        "Move down half a page",
+    ),
+    (Action::HalfPageUp, "half_page_up", "Move up half a page"),
+    (
+        Action::CenterSelection,
+        "center_selection",
+        "Scroll the selection to the middle",
+    ),
+    (Action::KillTask, "kill_task", "Kill the selected task"),
+    (
+        Action::TogglePause,
+        "toggle_pause",
+        "Pause/resume the group",
+    ),
+    (
+        Action::ToggleTaskPause,
+        "toggle_task_pause",
+        "Pause/resume/start the selected task",
+    ),
+    (Action::Refresh, "refresh", "Refresh the task list"),
+    (Action::ViewLogs, "view_logs", "Open/close the log viewer"),
+    (Action::CloseLogs, "close_logs", "Close the log viewer"),
+    (
+        Action::RestartTask,
+        "restart_task",
+        "Restart the task as a new copy",
+    ),
+    (
+        Action::CleanFinished,
+        "clean_finished",
+        "Clean finished tasks of the group",
+    ),
+    (
+        Action::FollowLogs,
+        "follow_logs",
+        "Follow the task's log output",
+    ),
+    (Action::ScrollLogUp, "scroll_log_up", "Scroll up one line"),
+    (
+        Action::ScrollLogDown,
+        "scroll_log_down",
+        "Scroll down one line",
+    ),
+    (
+        Action::ScrollLogPageUp,
+        "scroll_log_page_up",
+        "Scroll up one page",
+    ),
+    (
+        Action::ScrollLogPageDown,
+        "scroll_log_page_down",
+        "Scroll down one page",
+    ),
+    (Action::ScrollLogTop, "scroll_log_top", "Scroll to the top"),
+    (
+        Action::ScrollLogBottom,
+        "scroll_log_bottom",
+        "Scroll to the bottom",
+    ),
+    (
+        Action::StartAddTask,
+        "start_add_task",
+        "Add a task to the group",
+    ),
+    (
+        Action::StartEditTask,
+        "start_edit_task",
+        "Edit the task's command",
+    ),
+    (Action::RemoveTask, "remove_task", "Remove the task"),
+    (Action::SubmitInput, "submit_input", "Submit"),
+    (Action::CancelInput, "cancel_input", "Cancel"),
+    (
+        Action::InputBackspace,
+        "input_backspace",
+        "Delete the character before the cursor",
+    ),
+    (
+        Action::InputDelete,
+        "input_delete",
+        "Delete the character under the cursor",
+    ),
+    (Action::InputLeft, "input_left", "Move the cursor left"),
+    (Action::InputRight, "input_right", "Move the cursor right"),
+    (
+        Action::InputHome,
+        "input_home",
+        "Move the cursor to the start",
+    ),
+    (Action::InputEnd, "input_end", "Move the cursor to the end"),
+    (Action::InputNewline, "input_newline", "Start a new line"),
+    (
+        Action::InputWordLeft,
+        "input_word_left",
+        "Move the cursor a word left",
+    ),
+    (
+        Action::InputWordRight,
+        "input_word_right",
+        "Move the cursor a word right",
+    ),
+    (
+        Action::InputDeleteWord,
+        "input_delete_word",
+        "Delete the word before the cursor",
+    ),
+    (
+        Action::InputHistoryPrev,
+        "input_history_prev",
+        "Previous line or command from the history",
+    ),
+    (
+        Action::InputHistoryNext,
+        "input_history_next",
+        "Next line or command from the history",
+    ),
+    (
+        Action::InputHistorySearch,
+        "input_history_search",
+        "Search the command history",
+    ),
+    (
+        Action::InputComplete,
+        "input_complete",
+        "Complete a path or executable",
+    ),
+    (Action::StashTask, "stash_task", "Stash the queued task"),
+    (
+        Action::EnqueueTask,
+        "enqueue_task",
+        "Enqueue the stashed task",
+    ),
+    (
+        Action::SwitchUp,
+        "switch_up",
+        "Move the task up in the queue",
+    ),
+    (
+        Action::SwitchDown,
+        "switch_down",
+        "Move the task down in the queue",
+    ),
+    (
+        Action::MoveToQueueTop,
+        "move_to_queue_top",
+        "Move the task to the top of the queue",
+    ),
+    (
+        Action::MoveToQueueBottom,
+        "move_to_queue_bottom",
+        "Move the task to the bottom of the queue",
+    ),
+    (
+        Action::RaisePriority,
+        "raise_priority",
+        "Raise the task's priority",
+    ),
+    (
+        Action::LowerPriority,
+        "lower_priority",
+        "Lower the task's priority",
+    ),
+    (
+        Action::IncreaseParallel,
+        "increase_parallel",
+        "Increase the group's parallel limit",
+    ),
+    (
+        Action::DecreaseParallel,
+        "decrease_parallel",
+        "Decrease the group's parallel limit",
+    ),
+    (Action::ToggleMark, "toggle_mark", "Mark/unmark the task"),
+    (Action::ClearMarks, "clear_marks", "Unmark all tasks"),
+    (
+        Action::StartMoveToGroup,
+        "start_move_to_group",
+        "Move the marked or selected tasks to another group",
+    ),
+    (
+        Action::StartTaskFilter,
+        "start_task_filter",
+        "Filter the task list",
+    ),
+    (
+        Action::CycleSortMode,
+        "cycle_sort_mode",
+        "Change the task order",
+    ),
+    (
+        Action::AddBlankTask,
+        "add_blank_task",
+        "Add a task without a template",
+    ),
+    (
+        Action::SaveAsTemplate,
+        "save_as_template",
+        "Save the task as a template",
+    ),
+    (Action::Undo, "undo", "Undo the last operation"),
+    (
+        Action::ShowUndoHistory,
+        "show_undo_history",
+        "List recent operations to undo",
+    ),
+    (
+        Action::NextDaemon,
+        "next_daemon",
+        "Switch to the next daemon",
+    ),
+    (
+        Action::PrevDaemon,
+        "prev_daemon",
+        "Switch to the previous daemon",
+    ),
+    (
+        Action::GrowTaskList,
+        "grow_task_list",
+        "Make the task list bigger",
+    ),
+    (
+        Action::ShrinkTaskList,
+        "shrink_task_list",
+        "Make the task list smaller",
+    ),
+    (
+        Action::CycleLayout,
+        "cycle_layout",
+        "Switch between the auto, horizontal and vertical layout",
+    ),
+    (
+        Action::ToggleZoom,
+        "toggle_zoom",
+        "Show the focused pane full-screen",
+    ),
+    (
+        Action::ToggleStatusBar,
+        "toggle_status_bar",
+        "Show/hide the status bar",
+    ),
+    (
+        Action::ToggleHelpBar,
+        "toggle_help_bar",
+        "Show/hide the help bar",
+    ),
+    (Action::FocusNext, "focus_next", "Focus the next pane"),
+    (Action::FocusPrev, "focus_prev", "Focus the previous pane"),
+    (
+        Action::FocusTaskList,
+        "focus_task_list",
+        "Focus the task list",
+    ),
+    (
+        Action::ToggleLogPane,
+        "toggle_log_pane",
+        "Show/hide the docked log pane",
+    ),
+    (
+        Action::ScrollDetailsUp,
+        "scroll_details_up",
+        "Scroll up one line",
+    ),
+    (
+        Action::ScrollDetailsDown,
+        "scroll_details_down",
+        "Scroll down one line",
+    ),
+    (
+        Action::ScrollDetailsPageUp,
+        "scroll_details_page_up",
+        "Scroll up one page",
+    ),
+    (
+        Action::ScrollDetailsPageDown,
+        "scroll_details_page_down",
+        "Scroll down one page",
+    ),
+    (
+        Action::ScrollDetailsTop,
+        "scroll_details_top",
+        "Scroll to the top",
+    ),
+    (
+        Action::ScrollDetailsBottom,
+        "scroll_details_bottom",
+        "Scroll to the bottom",
+    ),
+    (
+        Action::ScrollLogPaneUp,
+        "scroll_log_pane_up",
+        "Scroll up one line",
+    ),
+    (
+        Action::ScrollLogPaneDown,
+        "scroll_log_pane_down",
+        "Scroll down one line",
+    ),
+    (
+        Action::ScrollLogPanePageUp,
+        "scroll_log_pane_page_up",
+        "Scroll up one page",
+    ),
+    (
+        Action::ScrollLogPanePageDown,
+        "scroll_log_pane_page_down",
+        "Scroll down one page",
+    ),
+    (
+        Action::ScrollLogPaneTop,
+        "scroll_log_pane_top",
+        "Scroll to the top",
+    ),
+    (
+        Action::ScrollLogPaneBottom,
+        "scroll_log_pane_bottom",
+        "Scroll to the bottom and follow the output",
+    ),
+    (
+        Action::ToggleDiagnostics,
+        "toggle_diagnostics",
+        "Diagnose the connection to the daemon",
+    ),
+    (
+        Action::CloseDiagnostics,
+        "close_diagnostics",
+        "Close the diagnostics",
+    ),
+    (
+        Action::RunDiagnostics,
+        "run_diagnostics",
+        "Run the connection checks again",
+    ),
+    (
+        Action::ScrollDiagnosticsUp,
+        "scroll_diagnostics_up",
+        "Scroll up one line",
+    ),
+    (
+        Action::ScrollDiagnosticsDown,
+        "scroll_diagnostics_down",
+        "Scroll down one line",
+    ),
+    (
+        Action::CollapseGroup,
+        "collapse_group",
+        "Collapse group / go to parent group",
+    ),
+    (
+        Action::ExpandGroup,
+        "expand_group",
+        "Expand group / view task logs",
+    ),
+    (
+        Action::ToggleActivity,
+        "toggle_activity",
+        "Show/hide the activity feed",
+    ),
+    (
+        Action::CloseActivity,
+        "close_activity",
+        "Close the activity feed",
+    ),
+    (
+        Action::ScrollActivityUp,
+        "scroll_activity_up",
+        "Scroll up one line",
+    ),
+    (
+        Action::ScrollActivityDown,
+        "scroll_activity_down",
+        "Scroll down one line",
+    ),
+    (
+        Action::ScrollActivityPageUp,
+        "scroll_activity_page_up",
+        "Scroll up one page",
+    ),
+    (
+        Action::ScrollActivityPageDown,
+        "scroll_activity_page_down",
+        "Scroll down one page",
+    ),
+    (
+        Action::CycleActivityFilter,
+        "cycle_activity_filter",
+        "Cycle the event category filter",
+    ),
+    (
+        Action::StartActivitySearch,
+        "start_activity_search",
+        "Filter events by text",
+    ),
+    (
+        Action::ToggleMessages,
+        "toggle_messages",
+        "Show/hide the message history",
+    ),
+    (
+        Action::CloseMessages,
+        "close_messages",
+        "Close the message history",
+    ),
+    (
+        Action::ScrollMessagesUp,
+        "scroll_messages_up",
+        "Scroll up one line",
+    ),
+    (
+        Action::ScrollMessagesDown,
+        "scroll_messages_down",
+        "Scroll down one line",
+    ),
+    (Action::ToggleHelp, "toggle_help", "Show/hide this help"),
+    (Action::CloseHelp, "close_help", "Close the help"),
+    (Action::ScrollHelpUp, "scroll_help_up", "Scroll up one line"),
+    (
+        Action::ScrollHelpDown,
+        "scroll_help_down",
+        "Scroll down one line",
+    ),
+    (
+        Action::ScrollHelpPageUp,
+        "scroll_help_page_up",
+        "Scroll up one page",
+    ),
+    (
+        Action::ScrollHelpPageDown,
+        "scroll_help_page_down",
+        "Scroll down one page",
+    ),
+    (
+        Action::StartHelpSearch,
+        "start_help_search",
+        "Search key bindings",
+    ),
+    (
+        Action::OpenPalette,
+        "open_palette",
+        "Open the command palette",
+    ),
+    (
+        Action::ClosePalette,
+        "close_palette",
+        "Close the command palette",
+    ),
+    (
+        Action::PaletteNext,
+        "palette_next",
+        "Select the next command",
+    ),
+    (
+        Action::PalettePrev,
+        "palette_prev",
+        "Select the previous command",
+    ),
+    (
+        Action::ExecutePalette,
+        "execute_palette",
+        "Run the selected command",
+    ),
+    (Action::ConfirmAction, "confirm_action", "Confirm"),
+    (Action::CancelConfirm, "cancel_confirm", "Cancel"),
+    (Action::Quit, "quit", "Quit lazypueue"),
+];
+
+impl Action {
+    /// All actions that can be bound to keys
+    pub fn bindable() -> impl Iterator<Item = &'static Action> {
+        ACTION_INFO.iter().map(|(action, _, _)| action)
+    }
+
+    /// Look up a bindable action by its config name, e.g. "navigate_top"
+    pub fn from_name(name: &str) -> Option<Action> {
+        ACTION_INFO
+            .iter()
+            .find(|(_, n, _)| *n == name)
+            .map(|(action, _, _)| action.clone())
+    }
+
+    /// Config name of the action, if it can be bound to keys
+    pub fn name(&self) -> Option<&'static str> {
+        ACTION_INFO
+            .iter()
+            .find(|(action, _, _)| action == self)
+            .map(|(_, name, _)| *name)
+    }
+
+    /// Short human readable description
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::InputChar(_) => "Insert a character",
+            Action::SetParallel(_) => "Set the group's parallel limit",
+            Action::KillWithSignal(_) => "Send a signal to the selected task",
+            Action::MoveToGroup(_) => "Move the marked or selected tasks to a group",
+            Action::UndoAt(_) => "Undo an operation",
+            Action::UseTemplate(_) => "Add a task from a template",
+            Action::SwitchDaemon(_) => "Switch to a daemon",
+            Action::SelectItem(_) => "Select an item / toggle a group",
+            Action::OpenItem(_) => "Open the task's logs",
+            Action::Focus(_) => "Focus a pane",
+            _ => ACTION_INFO
+                .iter()
+                .find(|(action, _, _)| action == self)
+                .map(|(_, _, description)| *description)
+                .unwrap_or(""),
+        }
+    }
+
+    /// The request the action sends to the daemon, to disable it for
+    /// daemons that don't understand it
+    pub fn request(&self) -> Option<RequestKind> {
+        match self {
+            Action::Refresh => Some(RequestKind::Status),
+            Action::ViewLogs | Action::FollowLogs | Action::OpenItem(_) => Some(RequestKind::Log),
+            Action::StartAddTask
+            | Action::AddBlankTask
+            | Action::UseTemplate(_)
+            | Action::RestartTask
+            | Action::StartMoveToGroup
+            | Action::MoveToGroup(_) => Some(RequestKind::Add),
+            Action::RemoveTask => Some(RequestKind::Remove),
+            Action::KillTask | Action::KillWithSignal(_) => Some(RequestKind::Kill),
+            Action::TogglePause | Action::ToggleTaskPause => Some(RequestKind::Pause),
+            Action::StashTask => Some(RequestKind::Stash),
+            Action::EnqueueTask => Some(RequestKind::Enqueue),
+            Action::SwitchUp
+            | Action::SwitchDown
+            | Action::MoveToQueueTop
+            | Action::MoveToQueueBottom => Some(RequestKind::Switch),
+            Action::StartEditTask | Action::RaisePriority | Action::LowerPriority => {
+                Some(RequestKind::Edit)
+            }
+            Action::IncreaseParallel | Action::DecreaseParallel | Action::SetParallel(_) => {
+                Some(RequestKind::Parallel)
+            }
+            Action::CleanFinished => Some(RequestKind::Clean),
+            _ => None,
+        }
+    }
+}
+
 /// Mode for text input dialogs
 #[derive(Debug, Clone)]
 pub enum InputMode {
     AddTask,
+    /// Asking for the placeholder values of a template
+    TemplateValue(TemplateFill),
     EditTask(EditableTask),
+    /// Asking for the name of a template saved from the task
+    SaveTemplate(usize),
+    ActivitySearch,
+    /// Editing the task list filter query
+    TaskFilter,
+    HelpSearch,
+}
+
+/// A dialog or modal view over the panes. They stack: keys go to the
+/// topmost one, and closing it returns to the one below.
+#[derive(Debug, Clone)]
+pub enum Overlay {
+    /// The log viewer
+    Log,
+    Activity,
+    Messages,
+    Diagnostics,
+    Help,
+    Palette,
+    Input(Box<InputMode>),
+    /// Asking whether to remove the task
+    ConfirmDelete(usize),
+}
+
+impl Overlay {
+    pub fn mode(&self) -> Mode {
+        match self {
+            Overlay::Log => Mode::Log,
+            Overlay::Activity => Mode::Activity,
+            Overlay::Messages => Mode::Messages,
+            Overlay::Diagnostics => Mode::Diagnostics,
+            Overlay::Help => Mode::Help,
+            Overlay::Palette => Mode::Palette,
+            Overlay::Input(_) => Mode::Input,
+            Overlay::ConfirmDelete(_) => Mode::Confirm,
+        }
+    }
+}
+
+/// A template whose placeholder values are being asked for, one at a time
+#[derive(Debug, Clone)]
+pub struct TemplateFill {
+    pub name: String,
+    pub template: Template,
+    pub placeholders: Vec<String>,
+    pub values: HashMap<String, String>,
+}
+
+impl TemplateFill {
+    /// The placeholder asked for next
+    pub fn current(&self) -> Option<&str> {
+        self.placeholders.get(self.values.len()).map(String::as_str)
+    }
 }
 
 /// Tree selection - either a group header or a task within a group
@@ -71,45 +805,164 @@ pub enum TreeSelection {
 }
 
 /// Item in the flattened tree view for navigation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum TreeItem {
     Group(String),       // Group header
     Task(String, usize), // (group_name, task_id)
 }
 
 pub struct App {
+    pub config: Config,
+    pub keymap: Keymap,
+    pub theme: Theme,
+    // Configured daemons and the one the task list shows
+    pub daemons: Vec<DaemonEntry>,
+    pub active_daemon: usize,
     pub state: Option<State>,
     pub last_update: Instant,
-    pub show_log_modal: bool,
+    // Dialogs and modal views, topmost last
+    pub overlays: Vec<Overlay>,
     pub log_content: Option<String>,
     pub log_scroll: usize,
+    pub log_max_scroll: Cell<usize>,
     pub follow_mode: bool,
-    pub error_message: Option<String>,
-    // Input mode state
-    pub input_mode: Option<InputMode>,
+    // The daemon can't be reached; replaces the whole UI
+    pub connection_error: Option<String>,
+    // Toasts and message history for action feedback
+    pub messages: Messages,
+    pub messages_scroll: usize,
+    pub messages_max_scroll: Cell<usize>,
+    // Connection diagnostics of the shown daemon, `None` while running
+    pub diagnostics: Option<Report>,
+    pub diagnostics_scroll: usize,
+    pub diagnostics_max_scroll: Cell<usize>,
+    // Text of the input dialog and the palette query
     pub text_input: TextInput,
-    // Confirmation dialog state
-    pub confirm_delete: Option<usize>,
     // Tree view state
     pub selection: TreeSelection,
     pub collapsed_groups: HashSet<String>,
+    // Tasks marked for moving to another group
+    pub marked_tasks: BTreeSet<usize>,
+    // Which tasks the tree shows and in which order
+    pub task_filter: TaskFilter,
+    pub sort_mode: SortMode,
+    // Task list scroll offset and visible rows, updated while rendering
+    pub task_list_offset: Cell<usize>,
+    pub task_list_height: Cell<usize>,
+    // Notifications for finished tasks
+    pub notifier: Notifier,
+    // Activity pane state
+    pub activity: ActivityLog,
+    pub activity_filter: ActivityFilter,
+    pub activity_scroll: usize,
+    pub activity_max_scroll: Cell<usize>,
+    // Help overlay state
+    pub help_query: String,
+    pub help_scroll: usize,
+    pub help_max_scroll: Cell<usize>,
+    // Command palette state; the query is edited in `text_input`
+    pub palette_scope: PaletteScope,
+    pub palette_selected: usize,
+    // Mouse state: regions of the last draw and the last click for double-clicks
+    pub hit_regions: RefCell<HitRegions>,
+    pub last_click: Option<(Instant, TreeItem)>,
+    // Daemon jobs waiting to be sent, and those in flight with their labels
+    pub outbox: Vec<Job>,
+    pub pending: BTreeMap<u64, PendingJob>,
+    next_job_id: u64,
+    pub spinner_frame: usize,
+    // Recent operations that can be undone
+    pub undo: UndoStack,
+    // Config file that saved templates are written to
+    pub config_path: Option<PathBuf>,
+    // Pane that gets the keys, shown full-screen when zoomed
+    pub focus: Pane,
+    pub zoomed: bool,
+    // Scroll offsets of the details and docked log panes, and their largest
+    // offsets at the last draw. usize::MAX keeps the log at its end.
+    pub details_scroll: usize,
+    pub details_max_scroll: Cell<usize>,
+    pub log_pane_scroll: usize,
+    pub log_pane_max_scroll: Cell<usize>,
+    // Log shown in the docked pane and the task it belongs to, and the
+    // task whose log was last requested for it
+    pub log_pane: Option<(usize, String)>,
+    log_pane_task: Option<usize>,
+    // Layout changes made in the TUI, saved to `layout_path`
+    pub layout_state: LayoutState,
+    pub layout_path: Option<PathBuf>,
+    // Previously added commands, browsed with up/down in the input dialog
+    pub history: History,
+    pub history_path: Option<PathBuf>,
+    pub history_search: Option<HistorySearch>,
+    // Shown history entry and the input it replaced
+    history_index: Option<usize>,
+    history_draft: String,
 }
 
 impl Default for App {
     fn default() -> Self {
         Self {
+            config: Config::default(),
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            daemons: vec![DaemonEntry::new(DEFAULT_DAEMON)],
+            active_daemon: 0,
             state: None,
             last_update: Instant::now(),
-            show_log_modal: false,
+            overlays: Vec::new(),
             log_content: None,
             log_scroll: 0,
+            log_max_scroll: Cell::new(0),
             follow_mode: false,
-            error_message: None,
-            input_mode: None,
+            connection_error: None,
+            messages: Messages::new(),
+            messages_scroll: 0,
+            messages_max_scroll: Cell::new(0),
+            diagnostics: None,
+            diagnostics_scroll: 0,
+            diagnostics_max_scroll: Cell::new(0),
             text_input: TextInput::new(),
-            confirm_delete: None,
             selection: TreeSelection::Group("default".to_string()),
             collapsed_groups: HashSet::new(),
+            marked_tasks: BTreeSet::new(),
+            task_filter: TaskFilter::default(),
+            sort_mode: SortMode::default(),
+            task_list_offset: Cell::new(0),
+            task_list_height: Cell::new(0),
+            notifier: Notifier::default(),
+            activity: ActivityLog::new(),
+            activity_filter: ActivityFilter::default(),
+            activity_scroll: 0,
+            activity_max_scroll: Cell::new(0),
+            help_query: String::new(),
+            help_scroll: 0,
+            help_max_scroll: Cell::new(0),
+            palette_scope: PaletteScope::Commands,
+            palette_selected: 0,
+            hit_regions: RefCell::new(HitRegions::default()),
+            last_click: None,
+            outbox: Vec::new(),
+            pending: BTreeMap::new(),
+            next_job_id: 0,
+            spinner_frame: 0,
+            undo: UndoStack::new(),
+            config_path: None,
+            focus: Pane::default(),
+            zoomed: false,
+            details_scroll: 0,
+            details_max_scroll: Cell::new(0),
+            log_pane_scroll: usize::MAX,
+            log_pane_max_scroll: Cell::new(0),
+            log_pane: None,
+            log_pane_task: None,
+            layout_state: LayoutState::default(),
+            layout_path: None,
+            history: History::new(),
+            history_path: None,
+            history_search: None,
+            history_index: None,
+            history_draft: String::new(),
         }
     }
 }
@@ -119,21 +972,349 @@ impl App {
         Self::default()
     }
 
+    /// Create an app using the given user configuration.
+    /// The config is expected to be validated already (see `Config::parse`).
+    pub fn with_config(config: Config) -> Self {
+        Self {
+            keymap: Keymap::from_config(&config.keys).unwrap_or_default(),
+            theme: Theme::from_env(config.general.theme),
+            selection: TreeSelection::Group(config.general.default_group.clone()),
+            notifier: Notifier::new(config.notifications.clone().into()),
+            activity: ActivityLog::with_capacity(config.activity.capacity),
+            daemons: config
+                .daemon_list()
+                .into_iter()
+                .map(|(name, _)| DaemonEntry::new(name))
+                .collect(),
+            config,
+            ..Self::default()
+        }
+    }
+
     pub async fn refresh(&mut self, client: &mut PueueClient) -> Result<()> {
-        match client.get_state().await {
+        self.daemons[self.active_daemon].version =
+            Some(DaemonVersion::new(client.daemon_version()));
+        let state = client.get_state().await;
+        self.apply_state(state);
+        Ok(())
+    }
+
+    /// Queue a job for the shown daemon. Jobs with a label show the pending
+    /// spinner until they are done.
+    fn submit<F>(&mut self, label: Option<String>, run: F)
+    where
+        F: for<'a> FnOnce(&'a mut PueueClient) -> JobFuture<'a> + Send + 'static,
+    {
+        self.submit_to(self.active_daemon, JobKind::Action, label, run);
+    }
+
+    /// Queue a job for the daemon at `daemon`
+    fn submit_to<F>(&mut self, daemon: usize, kind: JobKind, label: Option<String>, run: F)
+    where
+        F: for<'a> FnOnce(&'a mut PueueClient) -> JobFuture<'a> + Send + 'static,
+    {
+        self.next_job_id += 1;
+        let id = self.next_job_id;
+        self.pending.insert(
+            id,
+            PendingJob {
+                daemon,
+                kind,
+                label: label.clone(),
+            },
+        );
+        self.outbox.push(Job::new(id, daemon, label, run));
+    }
+
+    /// Diagnose the connection to the shown daemon in the background
+    fn run_diagnostics(&mut self) {
+        self.diagnostics = None;
+        self.diagnostics_scroll = 0;
+        self.next_job_id += 1;
+        let id = self.next_job_id;
+        let daemon = self.active_daemon;
+        let label = Some(format!("Diagnosing daemon '{}'", self.daemons[daemon].name));
+        self.pending.insert(
+            id,
+            PendingJob {
+                daemon,
+                kind: JobKind::Action,
+                label: label.clone(),
+            },
+        );
+        self.outbox.push(Job::diagnose(id, daemon, label));
+    }
+
+    /// Jobs queued since the last call, to be sent to the daemon task
+    pub fn take_jobs(&mut self) -> Vec<Job> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    /// Labels of the user actions still waiting for the daemon, oldest first
+    pub fn pending_labels(&self) -> impl Iterator<Item = &str> {
+        self.pending.values().filter_map(|job| job.label.as_deref())
+    }
+
+    /// Poll the state of every daemon, and the log when following, skipping
+    /// daemons whose last poll is still in flight
+    pub fn poll(&mut self) {
+        for daemon in 0..self.daemons.len() {
+            let polling = self
+                .pending
+                .values()
+                .any(|job| job.daemon == daemon && job.kind == JobKind::Poll);
+            if polling {
+                continue;
+            }
+            let following = self.follow_mode && self.is_open(Mode::Log);
+            let follow = self
+                .get_selected_task_id()
+                .filter(|_| daemon == self.active_daemon)
+                .filter(|_| following || self.config.layout.show_log_pane);
+            self.submit_to(daemon, JobKind::Poll, None, move |client| {
+                Box::pin(async move {
+                    let mut outcome = Outcome::new().refresh(client).await;
+                    if let Some(task_id) = follow {
+                        // Silently ignore errors during follow refresh
+                        if let Ok(content) = client.get_log(task_id).await {
+                            outcome = outcome.effect(Effect::FollowLogs { task_id, content });
+                        }
+                    }
+                    outcome
+                })
+            });
+        }
+    }
+
+    /// Apply the outcome of a finished job. Only the state of a daemon that
+    /// isn't shown is kept; its other effects belong to a view that's gone.
+    pub fn complete(&mut self, id: u64, outcome: Outcome) {
+        let daemon = self
+            .pending
+            .remove(&id)
+            .map_or(self.active_daemon, |job| job.daemon);
+        let shown = daemon == self.active_daemon;
+        for (level, text) in outcome.messages {
+            if shown {
+                self.messages.push(level, text);
+            } else {
+                let name = &self.daemons[daemon].name;
+                self.messages.push(level, format!("[{}] {}", name, text));
+            }
+        }
+        for effect in outcome.effects {
+            match effect {
+                Effect::Undoable(mut entry) => {
+                    entry.daemon = daemon;
+                    self.undo.push(entry);
+                }
+                Effect::RestoreUndoable { position, entry } => {
+                    self.undo.insert(position, entry);
+                }
+                Effect::Connected(version) => {
+                    self.daemons[daemon].version = Some(DaemonVersion::new(version));
+                }
+                effect if shown => self.apply_effect(effect),
+                Effect::State(state) => self.apply_background_state(daemon, state),
+                _ => {}
+            }
+        }
+    }
+
+    /// Keep the state of a daemon that isn't shown, still recording what
+    /// changed and notifying about its finished tasks
+    fn apply_background_state(&mut self, daemon: usize, state: Result<State>) {
+        let entry = &mut self.daemons[daemon];
+        match state {
             Ok(state) => {
+                if let Some(previous) = &entry.view.state {
+                    for change in activity::diff_states(previous, &state) {
+                        if let ActivityKind::TaskFinished(task) = &change {
+                            self.notifier.notify(task, Some(&entry.name));
+                        }
+                        self.activity.push_from(&entry.name, change);
+                    }
+                }
+                entry
+                    .view
+                    .marked_tasks
+                    .retain(|id| state.tasks.contains_key(id));
+                entry.view.state = Some(state);
+                entry.connection = Connection::Connected;
+            }
+            Err(e) => entry.connection = Connection::Failed(format!("{:#}", e)),
+        }
+    }
+
+    /// Show another daemon. The task list of the current one is kept as it
+    /// is and comes back when switching back to it.
+    pub fn switch_daemon(&mut self, index: usize) {
+        if index == self.active_daemon || index >= self.daemons.len() {
+            return;
+        }
+        let default_selection = TreeSelection::Group(self.config.general.default_group.clone());
+        let current = DaemonView {
+            state: self.state.take(),
+            selection: Some(std::mem::replace(
+                &mut self.selection,
+                default_selection.clone(),
+            )),
+            collapsed_groups: std::mem::take(&mut self.collapsed_groups),
+            marked_tasks: std::mem::take(&mut self.marked_tasks),
+        };
+        let next = std::mem::take(&mut self.daemons[index].view);
+        self.daemons[self.active_daemon].view = current;
+        self.active_daemon = index;
+
+        self.state = next.state;
+        self.selection = next.selection.unwrap_or(default_selection);
+        self.collapsed_groups = next.collapsed_groups;
+        self.marked_tasks = next.marked_tasks;
+        self.connection_error = match &self.daemons[index].connection {
+            Connection::Failed(e) => Some(self.connection_error_message(e)),
+            _ => None,
+        };
+        // The log viewer showed a task of the other daemon
+        self.close(Mode::Log);
+        self.follow_mode = false;
+        self.log_content = None;
+        self.log_pane = None;
+        self.log_pane_task = None;
+        self.task_list_offset.set(0);
+        self.validate_selection();
+        self.poll();
+    }
+
+    /// Error screen text for the shown daemon
+    fn connection_error_message(&self, error: &str) -> String {
+        if self.daemons.len() > 1 {
+            let name = &self.daemons[self.active_daemon].name;
+            format!("Failed to connect to pueue daemon '{}': {}", name, error)
+        } else {
+            format!("Failed to connect to pueue daemon: {}", error)
+        }
+    }
+
+    /// Apply an effect of a job for the shown daemon
+    fn apply_effect(&mut self, effect: Effect) {
+        match effect {
+            Effect::State(state) => self.apply_state(state),
+            Effect::ShowLogs { content, follow } => {
+                self.log_content = Some(content);
+                // Start at the end for follow mode
+                self.log_scroll = if follow { usize::MAX } else { 0 };
+                self.open(Overlay::Log);
+                self.follow_mode = follow;
+            }
+            Effect::FollowLogs { task_id, content } => {
+                // The viewer may have been closed while the log was loading
+                if self.get_selected_task_id() != Some(task_id) {
+                    return;
+                }
+                if self.config.layout.show_log_pane {
+                    self.log_pane = Some((task_id, content.clone()));
+                }
+                if self.follow_mode && self.is_open(Mode::Log) {
+                    self.log_content = Some(content);
+                    // Keep scroll at the end for follow mode
+                    self.log_scroll = usize::MAX;
+                }
+            }
+            Effect::Edit(editable) => {
+                self.text_input = TextInput::with_value(editable.original_command.clone());
+                self.start_command_input(InputMode::EditTask(editable));
+            }
+            Effect::Select(selection) => {
+                self.selection = selection;
+            }
+            Effect::Diagnostics(report) => self.diagnostics = Some(report),
+            Effect::Undoable(_) | Effect::RestoreUndoable { .. } | Effect::Connected(_) => {
+                unreachable!("complete() applies effects for any daemon itself")
+            }
+        }
+    }
+
+    fn apply_state(&mut self, state: Result<State>) {
+        match state {
+            Ok(state) => {
+                // Record what changed since the last refresh
+                if let Some(previous) = &self.state {
+                    for change in activity::diff_states(previous, &state) {
+                        if let ActivityKind::TaskFinished(task) = &change {
+                            self.notifier.notify(task, None);
+                        }
+                        self.activity.push(change);
+                    }
+                }
+                // Forget marks of tasks that are gone
+                self.marked_tasks.retain(|id| state.tasks.contains_key(id));
                 self.state = Some(state);
-                self.error_message = None;
+                self.connection_error = None;
+                self.daemons[self.active_daemon].connection = Connection::Connected;
                 self.last_update = Instant::now();
 
                 // Validate selection is still valid
                 self.validate_selection();
             }
             Err(e) => {
-                self.error_message = Some(format!("Failed to connect to pueue daemon: {}", e));
+                let error = e.to_string();
+                self.connection_error = Some(self.connection_error_message(&error));
+                self.daemons[self.active_daemon].connection = Connection::Failed(error);
             }
         }
-        Ok(())
+    }
+
+    /// Fetch a task's log in the background and open the viewer when it
+    /// arrives
+    fn load_logs(&mut self, task_id: usize, follow: bool) {
+        self.submit(
+            Some(format!("Loading log of task #{}", task_id)),
+            move |client| {
+                Box::pin(async move {
+                    match client.get_log(task_id).await {
+                        Ok(content) => Outcome::new().effect(Effect::ShowLogs { content, follow }),
+                        Err(e) => Outcome::new().error(format!("Failed to get logs: {}", e)),
+                    }
+                })
+            },
+        );
+    }
+
+    fn set_parallel(&mut self, group: String, limit: usize, verb: &'static str) {
+        let previous = self
+            .state
+            .as_ref()
+            .and_then(|state| state.groups.get(&group))
+            .map(|g| g.parallel_tasks);
+        self.submit(
+            Some(format!("Setting parallel limit of '{}'", group)),
+            move |client| {
+                Box::pin(async move {
+                    match client.parallel(&group, limit).await {
+                        Ok(()) => {
+                            let mut outcome = Outcome::new()
+                                .success(format!("Parallel limit of '{}': {}", group, limit));
+                            if let Some(previous) = previous.filter(|p| *p != limit) {
+                                outcome = outcome.undoable(UndoEntry::new(
+                                    format!(
+                                        "Set parallel limit of '{}' from {} to {}",
+                                        group, previous, limit
+                                    ),
+                                    UndoOp::Parallel {
+                                        group: group.clone(),
+                                        limit: previous,
+                                    },
+                                ));
+                            }
+                            outcome.refresh(client).await
+                        }
+                        Err(e) => {
+                            Outcome::new().error(format!("Failed to {} parallel: {}", verb, e))
+                        }
+                    }
+                })
+            },
+        );
     }
 
     /// Ensure current selection is still valid, adjust if needed
@@ -141,7 +1322,7 @@ impl App {
         let tree_items = self.get_tree_items();
         if tree_items.is_empty() {
             // No items - select default group
-            self.selection = TreeSelection::Group("default".to_string());
+            self.selection = TreeSelection::Group(self.config.general.default_group.clone());
             return;
         }
 
@@ -168,11 +1349,33 @@ impl App {
         }
     }
 
-    pub async fn handle_action(
-        &mut self,
-        action: Action,
-        client: &mut PueueClient,
-    ) -> Result<bool> {
+    /// Handle an action and return whether to quit. Daemon requests are
+    /// queued as jobs (see `take_jobs`), their results arrive in `complete`.
+    pub fn handle_action(&mut self, action: Action) -> bool {
+        // Palette commands run like any other action
+        let action = match action {
+            Action::ExecutePalette => match self.take_palette_action() {
+                Some(action) => action,
+                None => return false,
+            },
+            action => action,
+        };
+        if self.history_search.is_some() && self.handle_history_search(&action) {
+            return false;
+        }
+        if let Some(kind) = action.request() {
+            if let Some(version) = &self.daemons[self.active_daemon].version {
+                if !version.supports(kind) {
+                    self.messages.warn(format!(
+                        "Daemon version {} doesn't support the {} request",
+                        version,
+                        kind.name()
+                    ));
+                    return false;
+                }
+            }
+        }
+
         match action {
             Action::NavigateUp => {
                 let tree_items = self.get_tree_items();
@@ -202,10 +1405,58 @@ impl App {
                     self.select_tree_item(last);
                 }
             }
+            Action::HalfPageDown | Action::HalfPageUp => {
+                let tree_items = self.get_tree_items();
+                if let Some(current_pos) = self.get_selection_position(&tree_items) {
+                    let half = (self.task_list_height.get() / 2).max(1);
+                    let offset = self.task_list_offset.get();
+                    // Move the view along with the selection, like vim
+                    let (pos, offset) = if action == Action::HalfPageDown {
+                        (
+                            (current_pos + half).min(tree_items.len() - 1),
+                            offset + half,
+                        )
+                    } else {
+                        (
+                            current_pos.saturating_sub(half),
+                            offset.saturating_sub(half),
+                        )
+                    };
+                    self.select_tree_item(&tree_items[pos]);
+                    self.task_list_offset.set(offset);
+                }
+            }
+            Action::CenterSelection => {
+                let tree_items = self.get_tree_items();
+                if let Some(pos) = self.get_selection_position(&tree_items) {
+                    let half = self.task_list_height.get() / 2;
+                    self.task_list_offset.set(pos.saturating_sub(half));
+                }
+            }
             Action::KillTask => {
                 if let Some(task_id) = self.get_selected_task_id() {
-                    client.kill(vec![task_id]).await?;
-                    self.refresh(client).await?;
+                    // A killed task can't be resumed, but a copy can be added
+                    let task = self.task(task_id).cloned();
+                    self.submit(Some(format!("Killing task #{}", task_id)), move |client| {
+                        Box::pin(async move {
+                            match client.kill(vec![task_id]).await {
+                                Ok(()) => {
+                                    let mut outcome =
+                                        Outcome::new().success(format!("Killed task #{}", task_id));
+                                    if let Some(task) = task {
+                                        outcome = outcome.undoable(UndoEntry::new(
+                                            format!("Kill task #{}", task_id),
+                                            UndoOp::Readd(vec![task]),
+                                        ));
+                                    }
+                                    outcome.refresh(client).await
+                                }
+                                Err(e) => {
+                                    Outcome::new().error(format!("Failed to kill task: {}", e))
+                                }
+                            }
+                        })
+                    });
                 }
             }
             Action::TogglePause => {
@@ -215,71 +1466,89 @@ impl App {
                     TreeSelection::Group(name) => name.clone(),
                     TreeSelection::Task(group, _) => group.clone(),
                 };
-                if let Some(state) = &self.state {
-                    if let Some(group) = state.groups.get(&group_name) {
-                        match group.status {
-                            pueue_lib::state::GroupStatus::Paused => {
-                                client.start_group(&group_name).await?;
-                            }
-                            _ => {
-                                client.pause_group(&group_name).await?;
-                            }
-                        }
-                    }
-                    self.refresh(client).await?;
+                let paused = self
+                    .state
+                    .as_ref()
+                    .and_then(|state| state.groups.get(&group_name))
+                    .map(|group| group.status == GroupStatus::Paused);
+                if let Some(paused) = paused {
+                    let label = if paused { "Resuming" } else { "Pausing" };
+                    self.submit(
+                        Some(format!("{} group '{}'", label, group_name)),
+                        move |client| {
+                            Box::pin(async move {
+                                let result = if paused {
+                                    client
+                                        .start_group(&group_name)
+                                        .await
+                                        .map(|_| format!("Resumed group '{}'", group_name))
+                                } else {
+                                    client
+                                        .pause_group(&group_name)
+                                        .await
+                                        .map(|_| format!("Paused group '{}'", group_name))
+                                };
+                                let outcome = match result {
+                                    Ok(message) => {
+                                        let (what, undo) = if paused {
+                                            ("Resume", UndoOp::PauseGroup(group_name.clone()))
+                                        } else {
+                                            ("Pause", UndoOp::StartGroup(group_name.clone()))
+                                        };
+                                        Outcome::new().success(message).undoable(UndoEntry::new(
+                                            format!("{} group '{}'", what, group_name),
+                                            undo,
+                                        ))
+                                    }
+                                    Err(e) => Outcome::new().error(e.to_string()),
+                                };
+                                outcome.refresh(client).await
+                            })
+                        },
+                    );
                 }
             }
             Action::Refresh => {
-                self.refresh(client).await?;
+                self.submit(Some("Refreshing".to_string()), |client| {
+                    Box::pin(Outcome::new().refresh(client))
+                });
             }
             Action::ViewLogs => {
-                if !self.show_log_modal {
+                if !self.is_open(Mode::Log) {
                     // Opening logs - fetch the content
                     if let Some(task_id) = self.get_selected_task_id() {
-                        match client.get_log(task_id).await {
-                            Ok(content) => {
-                                self.log_content = Some(content);
-                                self.log_scroll = 0;
-                                self.show_log_modal = true;
-                            }
-                            Err(e) => {
-                                self.error_message = Some(format!("Failed to get logs: {}", e));
-                            }
-                        }
+                        self.load_logs(task_id, false);
                     }
                 } else {
                     // Closing logs
-                    self.show_log_modal = false;
+                    self.close(Mode::Log);
                     self.log_content = None;
                     self.follow_mode = false;
                 }
             }
             Action::CloseLogs => {
-                self.show_log_modal = false;
+                self.close(Mode::Log);
                 self.log_content = None;
                 self.log_scroll = 0;
                 self.follow_mode = false;
             }
-            Action::ScrollLogUp => {
-                if self.log_scroll > 0 {
-                    self.log_scroll = self.log_scroll.saturating_sub(1);
-                }
-            }
-            Action::ScrollLogDown => {
-                self.log_scroll = self.log_scroll.saturating_add(1);
-            }
-            Action::ScrollLogPageUp => {
-                self.log_scroll = self.log_scroll.saturating_sub(20);
+            Action::ScrollLogUp => self.scroll_log(-1),
+            Action::ScrollLogDown => self.scroll_log(1),
+            Action::ScrollLogPageUp => self.scroll_log(-20),
+            Action::ScrollLogPageDown => self.scroll_log(20),
+            Action::ScrollLogTop => {
+                self.log_scroll = 0;
+                self.follow_mode = false;
             }
-            Action::ScrollLogPageDown => {
-                self.log_scroll = self.log_scroll.saturating_add(20);
+            Action::ScrollLogBottom => {
+                // Clamped to the last page when rendering
+                self.log_scroll = usize::MAX;
             }
             Action::RestartTask => {
                 if let Some(task_id) = self.get_selected_task_id() {
                     if let Some(state) = &self.state {
                         if let Some(task) = state.tasks.get(&task_id) {
                             // Restart by creating a new task copy at end of queue (default pueue behavior)
-                            use crate::pueue_client::RestartOptions;
                             let opts = RestartOptions {
                                 command: task.command.clone(),
                                 path: task.path.clone(),
@@ -288,45 +1557,78 @@ impl App {
                                 priority: Some(task.priority),
                                 label: task.label.clone(),
                             };
-                            if let Err(e) = client.restart(opts).await {
-                                self.error_message = Some(format!("Failed to restart task: {}", e));
-                            } else {
-                                self.refresh(client).await?;
-                            }
+                            self.submit(
+                                Some(format!("Restarting task #{}", task_id)),
+                                move |client| {
+                                    Box::pin(async move {
+                                        match client.restart(opts).await {
+                                            Ok(new_id) => {
+                                                Outcome::new()
+                                                    .success(format!(
+                                                        "Restarted task #{} as #{}",
+                                                        task_id, new_id
+                                                    ))
+                                                    .refresh(client)
+                                                    .await
+                                            }
+                                            Err(e) => Outcome::new()
+                                                .error(format!("Failed to restart task: {}", e)),
+                                        }
+                                    })
+                                },
+                            );
                         }
                     }
                 }
             }
             Action::CleanFinished => {
                 // Clean currently selected group (or task's group)
-                let group_name = match &self.selection {
-                    TreeSelection::Group(name) => Some(name.as_str()),
-                    TreeSelection::Task(group, _) => Some(group.as_str()),
-                };
-                if let Err(e) = client.clean(false, group_name).await {
-                    self.error_message = Some(format!("Failed to clean tasks: {}", e));
-                } else {
-                    self.refresh(client).await?;
-                }
+                let group_name = self.get_selected_group().to_string();
+                // The tasks the daemon is about to remove, to restore them on undo
+                let finished: Vec<Task> = self
+                    .state
+                    .iter()
+                    .flat_map(|state| state.tasks.values())
+                    .filter(|task| task.group == group_name && task.is_done())
+                    .cloned()
+                    .collect();
+                self.submit(
+                    Some(format!("Cleaning group '{}'", group_name)),
+                    move |client| {
+                        Box::pin(async move {
+                            match client.clean(false, Some(&group_name)).await {
+                                Ok(()) => {
+                                    let mut outcome = Outcome::new().success(format!(
+                                        "Cleaned finished tasks of '{}'",
+                                        group_name
+                                    ));
+                                    if !finished.is_empty() {
+                                        outcome = outcome.undoable(UndoEntry::new(
+                                            format!(
+                                                "Clean {} task(s) of '{}'",
+                                                finished.len(),
+                                                group_name
+                                            ),
+                                            UndoOp::Readd(finished),
+                                        ));
+                                    }
+                                    outcome.refresh(client).await
+                                }
+                                Err(e) => {
+                                    Outcome::new().error(format!("Failed to clean tasks: {}", e))
+                                }
+                            }
+                        })
+                    },
+                );
             }
             Action::FollowLogs => {
                 if let Some(task_id) = self.get_selected_task_id() {
                     // Toggle follow mode or open logs in follow mode
-                    if self.show_log_modal {
+                    if self.is_open(Mode::Log) {
                         self.follow_mode = !self.follow_mode;
                     } else {
-                        match client.get_log(task_id).await {
-                            Ok(content) => {
-                                self.log_content = Some(content);
-                                // Start at the end for follow mode
-                                self.log_scroll = usize::MAX;
-                                self.show_log_modal = true;
-                                self.follow_mode = true;
-                            }
-                            Err(e) => {
-                                self.error_message = Some(format!("Failed to get logs: {}", e));
-                            }
-                        }
+                        self.load_logs(task_id, true);
                     }
                 }
             }
@@ -334,58 +1636,104 @@ impl App {
                 if let Some(task_id) = self.get_selected_task_id() {
                     if let Some(state) = &self.state {
                         if let Some(task) = state.tasks.get(&task_id) {
-                            match &task.status {
-                                TaskStatus::Paused { .. } => {
-                                    if let Err(e) = client.start_tasks(vec![task_id]).await {
-                                        self.error_message =
-                                            Some(format!("Failed to resume task: {}", e));
-                                    }
+                            let (pause, verb, label) = match &task.status {
+                                TaskStatus::Paused { .. } => (false, "resume", "Resuming"),
+                                TaskStatus::Running { .. } => (true, "pause", "Pausing"),
+                                // Start queued tasks immediately and force-start
+                                // stashed ones (like 'pueue start <id>')
+                                TaskStatus::Queued { .. } | TaskStatus::Stashed { .. } => {
+                                    (false, "start", "Starting")
                                 }
+                                // Can't pause/resume completed tasks
+                                _ => return false,
+                            };
+                            // Starting a task can't be undone
+                            let undo = match &task.status {
                                 TaskStatus::Running { .. } => {
-                                    if let Err(e) = client.pause_tasks(vec![task_id]).await {
-                                        self.error_message =
-                                            Some(format!("Failed to pause task: {}", e));
-                                    }
-                                }
-                                TaskStatus::Queued { .. } => {
-                                    // Start queued task immediately
-                                    if let Err(e) = client.start_tasks(vec![task_id]).await {
-                                        self.error_message =
-                                            Some(format!("Failed to start task: {}", e));
-                                    }
-                                }
-                                TaskStatus::Stashed { .. } => {
-                                    // Force-start stashed task (like 'pueue start <id>')
-                                    if let Err(e) = client.start_tasks(vec![task_id]).await {
-                                        self.error_message =
-                                            Some(format!("Failed to start task: {}", e));
-                                    }
+                                    Some(("Pause", UndoOp::StartTasks(vec![task_id])))
                                 }
-                                _ => {
-                                    // Can't pause/resume completed tasks
+                                TaskStatus::Paused { .. } => {
+                                    Some(("Resume", UndoOp::PauseTasks(vec![task_id])))
                                 }
-                            }
-                            self.refresh(client).await?;
+                                _ => None,
+                            };
+                            self.submit(
+                                Some(format!("{} task #{}", label, task_id)),
+                                move |client| {
+                                    Box::pin(async move {
+                                        let result = if pause {
+                                            client.pause_tasks(vec![task_id]).await
+                                        } else {
+                                            client.start_tasks(vec![task_id]).await
+                                        };
+                                        let outcome = match (result, undo) {
+                                            (Ok(()), Some((what, undo))) => Outcome::new()
+                                                .undoable(UndoEntry::new(
+                                                    format!("{} task #{}", what, task_id),
+                                                    undo,
+                                                )),
+                                            (Ok(()), None) => Outcome::new(),
+                                            (Err(e), _) => Outcome::new()
+                                                .error(format!("Failed to {} task: {}", verb, e)),
+                                        };
+                                        outcome.refresh(client).await
+                                    })
+                                },
+                            );
                         }
                     }
                 }
             }
             Action::StartAddTask => {
+                if self.config.templates.is_empty() {
+                    self.text_input.clear();
+                    self.start_command_input(InputMode::AddTask);
+                } else {
+                    // Pick a template or a blank command first
+                    self.text_input.clear();
+                    self.palette_scope = PaletteScope::Templates;
+                    self.palette_selected = 0;
+                    self.open(Overlay::Palette);
+                }
+            }
+            Action::AddBlankTask => {
                 self.text_input.clear();
-                self.input_mode = Some(InputMode::AddTask);
+                self.start_command_input(InputMode::AddTask);
+            }
+            Action::UseTemplate(name) => {
+                let Some(template) = self.config.templates.get(&name).cloned() else {
+                    self.messages.error(format!("No template named '{}'", name));
+                    return false;
+                };
+                let fill = TemplateFill {
+                    name,
+                    placeholders: template.placeholders(),
+                    template,
+                    values: HashMap::new(),
+                };
+                self.continue_template(fill);
+            }
+            Action::SaveAsTemplate => {
+                if let Some(task) = self.get_selected_task_id().and_then(|id| self.task(id)) {
+                    // Suggest the label as the name
+                    let name = task.label.clone().unwrap_or_default();
+                    let task_id = task.id;
+                    self.text_input = TextInput::with_value(name);
+                    self.open_input(InputMode::SaveTemplate(task_id));
+                }
             }
             Action::StartEditTask => {
                 if let Some(task_id) = self.get_selected_task_id() {
-                    match client.edit_request(task_id).await {
-                        Ok(editable) => {
-                            self.text_input =
-                                TextInput::with_value(editable.original_command.clone());
-                            self.input_mode = Some(InputMode::EditTask(editable));
-                        }
-                        Err(e) => {
-                            self.error_message = Some(format!("Failed to edit task: {}", e));
-                        }
-                    }
+                    self.submit(Some(format!("Loading task #{}", task_id)), move |client| {
+                        Box::pin(async move {
+                            match client.edit_request(task_id).await {
+                                Ok(editable) => Outcome::new().effect(Effect::Edit(editable)),
+                                Err(e) => {
+                                    Outcome::new().error(format!("Failed to edit task: {}", e))
+                                }
+                            }
+                        })
+                    });
                 }
             }
             Action::RemoveTask => {
@@ -395,76 +1743,138 @@ impl App {
                             // Only allow removing non-running tasks
                             if !matches!(task.status, TaskStatus::Running { .. }) {
                                 // Set confirmation state instead of immediate delete
-                                self.confirm_delete = Some(task_id);
+                                self.open(Overlay::ConfirmDelete(task_id));
                             }
                         }
                     }
                 }
             }
             Action::ConfirmAction => {
-                if let Some(task_id) = self.confirm_delete.take() {
-                    if let Err(e) = client.remove(vec![task_id]).await {
-                        self.error_message = Some(format!("Failed to remove task: {}", e));
-                    } else {
-                        self.refresh(client).await?;
-                    }
+                if let Some(Overlay::ConfirmDelete(task_id)) = self.close(Mode::Confirm) {
+                    let task = self.task(task_id).cloned();
+                    self.submit(Some(format!("Removing task #{}", task_id)), move |client| {
+                        Box::pin(async move {
+                            match client.remove(vec![task_id]).await {
+                                Ok(()) => {
+                                    let mut outcome = Outcome::new()
+                                        .success(format!("Removed task #{}", task_id));
+                                    if let Some(task) = task {
+                                        outcome = outcome.undoable(UndoEntry::new(
+                                            format!("Remove task #{}", task_id),
+                                            UndoOp::Readd(vec![task]),
+                                        ));
+                                    }
+                                    outcome.refresh(client).await
+                                }
+                                Err(e) => {
+                                    Outcome::new().error(format!("Failed to remove task: {}", e))
+                                }
+                            }
+                        })
+                    });
                 }
             }
             Action::CancelConfirm => {
-                self.confirm_delete = None;
+                self.close(Mode::Confirm);
             }
             Action::SubmitInput => {
-                if let Some(mode) = self.input_mode.take() {
+                self.history_index = None;
+                if let Some(Overlay::Input(mode)) = self.close(Mode::Input) {
+                    let mode = *mode;
                     let command = self.text_input.value.clone();
-                    if !command.trim().is_empty() {
-                        match mode {
-                            InputMode::AddTask => {
-                                // Add to currently selected group (or task's group)
-                                let group = match &self.selection {
-                                    TreeSelection::Group(name) => name.as_str(),
-                                    TreeSelection::Task(group, _) => group.as_str(),
-                                };
-                                match client.add(command, group).await {
-                                    Ok(_task_id) => {
-                                        self.refresh(client).await?;
-                                    }
-                                    Err(e) => {
-                                        self.error_message =
-                                            Some(format!("Failed to add task: {}", e));
-                                    }
-                                }
+                    match mode {
+                        InputMode::AddTask if !command.trim().is_empty() => {
+                            self.remember_command(&command);
+                            // Add to currently selected group (or task's group)
+                            let group = self.get_selected_group().to_string();
+                            self.add_task(AddOptions {
+                                command,
+                                group,
+                                ..AddOptions::default()
+                            });
+                        }
+                        InputMode::TemplateValue(mut fill) => {
+                            if let Some(name) = fill.current().map(str::to_string) {
+                                fill.values.insert(name, command);
                             }
-                            InputMode::EditTask(mut editable) => {
-                                editable.original_command = command;
-                                if let Err(e) = client.edit_submit(editable).await {
-                                    self.error_message =
-                                        Some(format!("Failed to save edit: {}", e));
-                                } else {
-                                    self.refresh(client).await?;
-                                }
+                            self.text_input.clear();
+                            self.continue_template(fill);
+                            return false;
+                        }
+                        InputMode::SaveTemplate(task_id) => {
+                            self.save_template(task_id, command.trim());
+                        }
+                        InputMode::EditTask(mut editable) if !command.trim().is_empty() => {
+                            self.remember_command(&command);
+                            editable.original_command = command;
+                            let task_id = editable.id;
+                            self.submit(Some(format!("Saving task #{}", task_id)), move |client| {
+                                Box::pin(async move {
+                                    match client.edit_submit(editable).await {
+                                        Ok(()) => {
+                                            Outcome::new()
+                                                .success(format!("Saved task #{}", task_id))
+                                                .refresh(client)
+                                                .await
+                                        }
+                                        Err(e) => Outcome::new()
+                                            .error(format!("Failed to save edit: {}", e)),
+                                    }
+                                })
+                            });
+                        }
+                        InputMode::TaskFilter => match TaskFilter::parse(&command) {
+                            Ok(task_filter) => {
+                                self.task_filter = task_filter;
+                                self.validate_selection();
                             }
+                            Err(e) => self.messages.error(format!("Invalid filter: {}", e)),
+                        },
+                        InputMode::ActivitySearch => {
+                            // An empty query clears the filter
+                            self.activity_filter.query = command.trim().to_string();
+                            self.activity_scroll = 0;
                         }
+                        InputMode::HelpSearch => {
+                            self.help_query = command.trim().to_string();
+                            self.help_scroll = 0;
+                        }
+                        _ => {}
                     }
                     self.text_input.clear();
                 }
             }
             Action::CancelInput => {
-                if let Some(mode) = self.input_mode.take() {
+                self.history_index = None;
+                if let Some(Overlay::Input(mode)) = self.close(Mode::Input) {
+                    let mode = *mode;
                     // If editing, restore the original task state
                     if let InputMode::EditTask(editable) = mode {
-                        let _ = client.edit_restore(editable.id).await;
+                        let task_id = editable.id;
+                        self.submit(
+                            Some(format!("Restoring task #{}", task_id)),
+                            move |client| {
+                                Box::pin(async move {
+                                    let _ = client.edit_restore(task_id).await;
+                                    Outcome::new()
+                                })
+                            },
+                        );
                     }
                     self.text_input.clear();
                 }
             }
             Action::InputChar(c) => {
                 self.text_input.insert(c);
+                self.palette_selected = 0;
             }
             Action::InputBackspace => {
                 self.text_input.delete_char();
+                self.palette_selected = 0;
             }
             Action::InputDelete => {
                 self.text_input.delete_forward();
+                self.palette_selected = 0;
             }
             Action::InputLeft => {
                 self.text_input.move_left();
@@ -478,18 +1888,103 @@ impl App {
             Action::InputEnd => {
                 self.text_input.move_end();
             }
+            Action::InputWordLeft => {
+                self.text_input.move_word_left();
+            }
+            Action::InputWordRight => {
+                self.text_input.move_word_right();
+            }
+            Action::InputDeleteWord => {
+                self.text_input.delete_word();
+                self.palette_selected = 0;
+            }
+            Action::InputNewline => {
+                self.text_input.insert_newline();
+            }
+            Action::InputHistoryPrev if self.text_input.move_up() => {}
+            Action::InputHistoryNext if self.text_input.move_down() => {}
+            Action::InputHistoryPrev if self.is_command_input() => {
+                let index = self.history_index.map_or(0, |i| i + 1);
+                if let Some(command) = self.history.get(index) {
+                    if self.history_index.is_none() {
+                        self.history_draft = self.text_input.value.clone();
+                    }
+                    self.text_input.set_value(command);
+                    self.history_index = Some(index);
+                }
+            }
+            Action::InputHistoryNext if self.is_command_input() => match self.history_index {
+                Some(0) => {
+                    self.text_input
+                        .set_value(std::mem::take(&mut self.history_draft));
+                    self.history_index = None;
+                }
+                Some(index) => {
+                    if let Some(command) = self.history.get(index - 1) {
+                        self.text_input.set_value(command);
+                    }
+                    self.history_index = Some(index - 1);
+                }
+                None => {}
+            },
+            Action::InputHistorySearch if self.is_command_input() => {
+                self.history_search = Some(HistorySearch {
+                    original: self.text_input.value.clone(),
+                    ..HistorySearch::default()
+                });
+            }
+            Action::InputComplete if self.is_command_input() => {
+                let cwd = std::env::current_dir().unwrap_or_default();
+                let path = std::env::var_os("PATH");
+                if let Some(completion) = complete::complete(
+                    &self.text_input.value,
+                    self.text_input.cursor,
+                    path.as_deref(),
+                    &cwd,
+                ) {
+                    self.text_input
+                        .replace_before_cursor(completion.start, &completion.replacement);
+                    if !completion.candidates.is_empty() {
+                        self.messages.info(completion.candidates.join("  "));
+                    }
+                }
+            }
+            Action::InputHistoryPrev
+            | Action::InputHistoryNext
+            | Action::InputHistorySearch
+            | Action::InputComplete => {}
             Action::StashTask => {
                 if let Some(task_id) = self.get_selected_task_id() {
                     if let Some(state) = &self.state {
                         if let Some(task) = state.tasks.get(&task_id) {
                             // Can only stash queued tasks
                             if matches!(task.status, TaskStatus::Queued { .. }) {
-                                if let Err(e) = client.stash(vec![task_id]).await {
-                                    self.error_message =
-                                        Some(format!("Failed to stash task: {}", e));
-                                } else {
-                                    self.refresh(client).await?;
-                                }
+                                self.submit(
+                                    Some(format!("Stashing task #{}", task_id)),
+                                    move |client| {
+                                        Box::pin(async move {
+                                            match client.stash(vec![task_id]).await {
+                                                Ok(()) => {
+                                                    Outcome::new()
+                                                        .success(format!(
+                                                            "Stashed task #{}",
+                                                            task_id
+                                                        ))
+                                                        .undoable(UndoEntry::new(
+                                                            format!("Stash task #{}", task_id),
+                                                            UndoOp::Enqueue(vec![task_id]),
+                                                        ))
+                                                        .refresh(client)
+                                                        .await
+                                                }
+                                                Err(e) => Outcome::new()
+                                                    .error(format!("Failed to stash task: {}", e)),
+                                            }
+                                        })
+                                    },
+                                );
+                            } else {
+                                self.messages.warn("Only queued tasks can be stashed");
                             }
                         }
                     }
@@ -501,115 +1996,154 @@ impl App {
                         if let Some(task) = state.tasks.get(&task_id) {
                             // Can only enqueue stashed tasks
                             if matches!(task.status, TaskStatus::Stashed { .. }) {
-                                if let Err(e) = client.enqueue(vec![task_id]).await {
-                                    self.error_message =
-                                        Some(format!("Failed to enqueue task: {}", e));
-                                } else {
-                                    self.refresh(client).await?;
-                                }
+                                self.submit(
+                                    Some(format!("Enqueueing task #{}", task_id)),
+                                    move |client| {
+                                        Box::pin(async move {
+                                            match client.enqueue(vec![task_id]).await {
+                                                Ok(()) => {
+                                                    Outcome::new()
+                                                        .success(format!(
+                                                            "Enqueued task #{}",
+                                                            task_id
+                                                        ))
+                                                        .undoable(UndoEntry::new(
+                                                            format!("Enqueue task #{}", task_id),
+                                                            UndoOp::Stash(vec![task_id]),
+                                                        ))
+                                                        .refresh(client)
+                                                        .await
+                                                }
+                                                Err(e) => Outcome::new().error(format!(
+                                                    "Failed to enqueue task: {}",
+                                                    e
+                                                )),
+                                            }
+                                        })
+                                    },
+                                );
+                            } else {
+                                self.messages.warn("Only stashed tasks can be enqueued");
                             }
                         }
                     }
                 }
             }
-            Action::SwitchUp => {
-                if let Some(task_id) = self.get_selected_task_id() {
-                    if let Some(state) = &self.state {
-                        let tasks = self.get_task_list();
-                        // Find the task before this one that can be switched
-                        if let Some(pos) = tasks.iter().position(|(id, _)| *id == task_id) {
-                            if pos > 0 {
-                                let other_id = tasks[pos - 1].0;
-                                // Validate both tasks can be switched (queued or stashed only)
-                                let task1 = state.tasks.get(&task_id);
-                                let task2 = state.tasks.get(&other_id);
-                                if let (Some(t1), Some(t2)) = (task1, task2) {
-                                    let can_switch = |s: &TaskStatus| {
-                                        matches!(
-                                            s,
-                                            TaskStatus::Queued { .. } | TaskStatus::Stashed { .. }
-                                        )
-                                    };
-                                    if can_switch(&t1.status) && can_switch(&t2.status) {
-                                        if let Err(e) = client.switch(task_id, other_id).await {
-                                            self.error_message =
-                                                Some(format!("Failed to switch tasks: {}", e));
-                                        } else {
-                                            self.refresh(client).await?;
+            Action::SwitchUp
+            | Action::SwitchDown
+            | Action::MoveToQueueTop
+            | Action::MoveToQueueBottom => {
+                let direction = match action {
+                    Action::SwitchUp => QueueMove::Up,
+                    Action::SwitchDown => QueueMove::Down,
+                    Action::MoveToQueueTop => QueueMove::Top,
+                    _ => QueueMove::Bottom,
+                };
+                if let (Some(task_id), Some(state)) = (self.get_selected_task_id(), &self.state) {
+                    match queue::plan_switches(state, task_id, direction) {
+                        Ok(switches) => {
+                            let group = self.get_selected_group().to_string();
+                            self.submit(Some(format!("Moving task #{}", task_id)), move |client| {
+                                Box::pin(async move {
+                                    let mut outcome = Outcome::new();
+                                    let mut current = task_id;
+                                    for (a, b) in switches {
+                                        if let Err(e) = client.switch(a, b).await {
+                                            outcome = outcome
+                                                .error(format!("Failed to switch tasks: {}", e));
+                                            break;
                                         }
+                                        current = b;
                                     }
-                                }
-                            }
+                                    // The task now has the other id; keep it selected
+                                    outcome
+                                        .effect(Effect::Select(TreeSelection::Task(group, current)))
+                                        .refresh(client)
+                                        .await
+                                })
+                            });
+                        }
+                        Err(e) => {
+                            self.messages
+                                .warn(format!("Can't move task #{}: {}", task_id, e));
                         }
                     }
                 }
             }
-            Action::SwitchDown => {
-                if let Some(task_id) = self.get_selected_task_id() {
-                    if let Some(state) = &self.state {
-                        let tasks = self.get_task_list();
-                        // Find the task after this one that can be switched
-                        if let Some(pos) = tasks.iter().position(|(id, _)| *id == task_id) {
-                            if pos < tasks.len() - 1 {
-                                let other_id = tasks[pos + 1].0;
-                                // Validate both tasks can be switched (queued or stashed only)
-                                let task1 = state.tasks.get(&task_id);
-                                let task2 = state.tasks.get(&other_id);
-                                if let (Some(t1), Some(t2)) = (task1, task2) {
-                                    let can_switch = |s: &TaskStatus| {
-                                        matches!(
-                                            s,
-                                            TaskStatus::Queued { .. } | TaskStatus::Stashed { .. }
-                                        )
-                                    };
-                                    if can_switch(&t1.status) && can_switch(&t2.status) {
-                                        if let Err(e) = client.switch(task_id, other_id).await {
-                                            self.error_message =
-                                                Some(format!("Failed to switch tasks: {}", e));
-                                        } else {
-                                            self.refresh(client).await?;
-                                        }
+            Action::RaisePriority | Action::LowerPriority => {
+                if let (Some(task_id), Some(state)) = (self.get_selected_task_id(), &self.state) {
+                    let waiting = state.tasks.get(&task_id).is_some_and(|task| {
+                        matches!(
+                            task.status,
+                            TaskStatus::Queued { .. } | TaskStatus::Stashed { .. }
+                        )
+                    });
+                    if !waiting {
+                        self.messages.warn(format!(
+                            "Can't change the priority of task #{}: {}",
+                            task_id,
+                            queue::QueueError::NotQueued
+                        ));
+                        return false;
+                    }
+
+                    // Priorities are changed through the edit flow
+                    let delta = if action == Action::RaisePriority {
+                        1
+                    } else {
+                        -1
+                    };
+                    self.submit(
+                        Some(format!("Changing priority of task #{}", task_id)),
+                        move |client| {
+                            Box::pin(async move {
+                                let mut editable = match client.edit_request(task_id).await {
+                                    Ok(editable) => editable,
+                                    Err(e) => {
+                                        return Outcome::new()
+                                            .error(format!("Failed to change priority: {}", e))
                                     }
+                                };
+                                editable.priority += delta;
+                                let priority = editable.priority;
+                                if let Err(e) = client.edit_submit(editable).await {
+                                    let _ = client.edit_restore(task_id).await;
+                                    Outcome::new()
+                                        .error(format!("Failed to change priority: {}", e))
+                                } else {
+                                    Outcome::new()
+                                        .success(format!(
+                                            "Task #{} priority: {}",
+                                            task_id, priority
+                                        ))
+                                        .refresh(client)
+                                        .await
                                 }
-                            }
-                        }
-                    }
+                            })
+                        },
+                    );
                 }
             }
             Action::IncreaseParallel => {
-                let group_name = match &self.selection {
-                    TreeSelection::Group(name) => name.clone(),
-                    TreeSelection::Task(group, _) => group.clone(),
-                };
-                if let Some(state) = &self.state {
-                    if let Some(group) = state.groups.get(&group_name) {
-                        let new_limit = group.parallel_tasks + 1;
-                        if let Err(e) = client.parallel(&group_name, new_limit).await {
-                            self.error_message =
-                                Some(format!("Failed to increase parallel: {}", e));
-                        } else {
-                            self.refresh(client).await?;
-                        }
-                    }
+                let group_name = self.get_selected_group().to_string();
+                let limit = self
+                    .state
+                    .as_ref()
+                    .and_then(|state| state.groups.get(&group_name))
+                    .map(|group| group.parallel_tasks);
+                if let Some(limit) = limit {
+                    self.set_parallel(group_name, limit + 1, "increase");
                 }
             }
             Action::DecreaseParallel => {
-                let group_name = match &self.selection {
-                    TreeSelection::Group(name) => name.clone(),
-                    TreeSelection::Task(group, _) => group.clone(),
-                };
-                if let Some(state) = &self.state {
-                    if let Some(group) = state.groups.get(&group_name) {
-                        if group.parallel_tasks > 1 {
-                            let new_limit = group.parallel_tasks - 1;
-                            if let Err(e) = client.parallel(&group_name, new_limit).await {
-                                self.error_message =
-                                    Some(format!("Failed to decrease parallel: {}", e));
-                            } else {
-                                self.refresh(client).await?;
-                            }
-                        }
-                    }
+                let group_name = self.get_selected_group().to_string();
+                let limit = self
+                    .state
+                    .as_ref()
+                    .and_then(|state| state.groups.get(&group_name))
+                    .map(|group| group.parallel_tasks);
+                if let Some(limit) = limit.filter(|limit| *limit > 1) {
+                    self.set_parallel(group_name, limit - 1, "decrease");
                 }
             }
             Action::CollapseGroup => {
@@ -635,42 +2169,663 @@ impl App {
                             // Expand the group
                             self.collapsed_groups.remove(name);
                         } else {
-                            // Already expanded - select first task if any
-                            if let Some(state) = &self.state {
-                                let mut tasks_in_group: Vec<_> = state
-                                    .tasks
-                                    .iter()
-                                    .filter(|(_, t)| &t.group == name)
-                                    .map(|(id, _)| *id)
-                                    .collect();
-                                tasks_in_group.sort();
-                                if let Some(first_task_id) = tasks_in_group.first() {
-                                    self.selection =
-                                        TreeSelection::Task(name.clone(), *first_task_id);
-                                }
+                            // Already expanded - select the first task shown, if any
+                            let first_task = self.get_tree_items().into_iter().find(
+                                |item| matches!(item, TreeItem::Task(group, _) if group == name),
+                            );
+                            if let Some(item) = first_task {
+                                self.select_tree_item(&item);
                             }
                         }
                     }
                     TreeSelection::Task(_, task_id) => {
                         // Task selected - view logs
-                        match client.get_log(*task_id).await {
-                            Ok(content) => {
-                                self.log_content = Some(content);
-                                self.log_scroll = 0;
-                                self.show_log_modal = true;
-                            }
-                            Err(e) => {
-                                self.error_message = Some(format!("Failed to get logs: {}", e));
+                        self.load_logs(*task_id, false);
+                    }
+                }
+            }
+            Action::ToggleActivity => {
+                if self.close(Mode::Activity).is_none() {
+                    self.open(Overlay::Activity);
+                }
+                self.activity_scroll = 0;
+            }
+            Action::CloseActivity => {
+                self.close(Mode::Activity);
+                self.activity_scroll = 0;
+            }
+            Action::ScrollActivityUp => self.scroll_activity(-1),
+            Action::ScrollActivityDown => self.scroll_activity(1),
+            Action::ScrollActivityPageUp => self.scroll_activity(-20),
+            Action::ScrollActivityPageDown => self.scroll_activity(20),
+            Action::StartTaskFilter => {
+                self.text_input = TextInput::with_value(self.task_filter.to_string());
+                self.open_input(InputMode::TaskFilter);
+            }
+            Action::CycleSortMode => {
+                self.sort_mode = self.sort_mode.next();
+                self.messages
+                    .info(format!("Sorting tasks by {}", self.sort_mode.name()));
+            }
+            Action::CycleActivityFilter => {
+                self.activity_filter.category = self.activity_filter.category.next();
+                self.activity_scroll = 0;
+            }
+            Action::StartActivitySearch => {
+                self.text_input = TextInput::with_value(self.activity_filter.query.clone());
+                self.open_input(InputMode::ActivitySearch);
+            }
+            Action::ToggleMessages => {
+                if self.close(Mode::Messages).is_none() {
+                    self.open(Overlay::Messages);
+                }
+                self.messages_scroll = 0;
+            }
+            Action::CloseMessages => {
+                self.close(Mode::Messages);
+                self.messages_scroll = 0;
+            }
+            Action::ScrollMessagesUp => self.scroll_messages(-1),
+            Action::ScrollMessagesDown => self.scroll_messages(1),
+            Action::ToggleHelp => {
+                if self.close(Mode::Help).is_none() {
+                    self.open(Overlay::Help);
+                }
+                self.help_query.clear();
+                self.help_scroll = 0;
+            }
+            Action::CloseHelp => {
+                self.close(Mode::Help);
+                self.help_query.clear();
+                self.help_scroll = 0;
+            }
+            Action::ScrollHelpUp => self.scroll_help(-1),
+            Action::ScrollHelpDown => self.scroll_help(1),
+            Action::ScrollHelpPageUp => self.scroll_help(-20),
+            Action::ScrollHelpPageDown => self.scroll_help(20),
+            Action::StartHelpSearch => {
+                self.text_input = TextInput::with_value(self.help_query.clone());
+                self.open_input(InputMode::HelpSearch);
+            }
+            Action::OpenPalette => {
+                self.text_input.clear();
+                self.palette_scope = PaletteScope::Commands;
+                self.palette_selected = 0;
+                self.open(Overlay::Palette);
+            }
+            Action::ClosePalette => {
+                self.text_input.clear();
+                self.close(Mode::Palette);
+            }
+            Action::PaletteNext => {
+                let count = palette::palette_matches(self).len();
+                if self.palette_selected + 1 < count {
+                    self.palette_selected += 1;
+                }
+            }
+            Action::PalettePrev => {
+                self.palette_selected = self.palette_selected.saturating_sub(1);
+            }
+            // Resolved to the selected command above
+            Action::ExecutePalette => {}
+            Action::SetParallel(limit) => {
+                let group_name = self.get_selected_group().to_string();
+                self.set_parallel(group_name, limit, "set");
+            }
+            Action::KillWithSignal(signal) => {
+                if let Some(task_id) = self.get_selected_task_id() {
+                    let name = palette::signal_name(&signal);
+                    self.submit(
+                        Some(format!("Sending {} to task #{}", name, task_id)),
+                        move |client| {
+                            Box::pin(async move {
+                                match client.send_signal(vec![task_id], signal).await {
+                                    Ok(()) => {
+                                        Outcome::new()
+                                            .success(format!("Sent {} to task #{}", name, task_id))
+                                            .refresh(client)
+                                            .await
+                                    }
+                                    Err(e) => Outcome::new()
+                                        .error(format!("Failed to send signal: {}", e)),
+                                }
+                            })
+                        },
+                    );
+                }
+            }
+            Action::ToggleMark => {
+                if let Some(task_id) = self.get_selected_task_id() {
+                    if !self.marked_tasks.remove(&task_id) {
+                        self.marked_tasks.insert(task_id);
+                    }
+                }
+            }
+            Action::ClearMarks => {
+                self.marked_tasks.clear();
+            }
+            Action::StartMoveToGroup => {
+                // The palette doubles as the group picker
+                if !self.move_targets().is_empty() {
+                    self.text_input.clear();
+                    self.palette_scope = PaletteScope::MoveToGroup;
+                    self.palette_selected = 0;
+                    self.open(Overlay::Palette);
+                }
+            }
+            Action::MoveToGroup(group) => {
+                let Some(state) = &self.state else {
+                    return false;
+                };
+                let plan = move_tasks::plan_move(state, &self.move_targets(), &group);
+                let tasks: Vec<_> = plan
+                    .tasks
+                    .iter()
+                    .filter_map(|id| state.tasks.get(id).cloned())
+                    .collect();
+
+                for (id, reason) in &plan.skipped {
+                    self.messages
+                        .warn(format!("Can't move task #{}: {}", id, reason));
+                }
+                self.marked_tasks.clear();
+                if tasks.is_empty() {
+                    return false;
+                }
+
+                self.submit(
+                    Some(format!("Moving {} task(s) to '{}'", tasks.len(), group)),
+                    move |client| {
+                        Box::pin(async move {
+                            let moved = move_tasks::move_tasks(client, &tasks, &group).await;
+                            let new_ids = moved.new_ids;
+                            let mut outcome = match moved.error {
+                                None => Outcome::new().success(format!(
+                                    "Moved {} task(s) to group '{}'",
+                                    new_ids.len(),
+                                    group
+                                )),
+                                Some(e) if new_ids.is_empty() => {
+                                    Outcome::new().error(format!("Failed to move tasks: {}", e))
+                                }
+                                Some(e) => Outcome::new().warn(format!(
+                                    "Moved {} of {} task(s) to group '{}': {}",
+                                    new_ids.len(),
+                                    tasks.len(),
+                                    group,
+                                    e
+                                )),
+                            };
+
+                            if let Some(new_id) = tasks.first().and_then(|t| new_ids.get(&t.id)) {
+                                outcome = outcome
+                                    .effect(Effect::Select(TreeSelection::Task(group, *new_id)));
                             }
-                        }
+                            outcome.refresh(client).await
+                        })
+                    },
+                );
+            }
+            Action::Undo => self.undo_at(0),
+            Action::UndoAt(index) => self.undo_at(index),
+            Action::NextDaemon | Action::PrevDaemon => {
+                let count = self.daemons.len();
+                if count == 1 {
+                    self.messages
+                        .info("Only one daemon is configured, see [daemons] in the config");
+                } else if action == Action::NextDaemon {
+                    self.switch_daemon((self.active_daemon + 1) % count);
+                } else {
+                    self.switch_daemon((self.active_daemon + count - 1) % count);
+                }
+            }
+            Action::SwitchDaemon(index) => self.switch_daemon(index),
+            Action::GrowTaskList => self.resize_task_list(RESIZE_STEP as i16),
+            Action::ShrinkTaskList => self.resize_task_list(-(RESIZE_STEP as i16)),
+            Action::CycleLayout => {
+                let preset = self.config.layout.preset.next();
+                self.config.layout.preset = preset;
+                self.layout_state.preset = Some(preset);
+                self.messages.info(format!("Layout: {}", preset.name()));
+                self.save_layout();
+            }
+            Action::ToggleZoom => self.zoomed = !self.zoomed,
+            Action::ToggleStatusBar => {
+                let show = !self.config.layout.show_status_bar;
+                self.config.layout.show_status_bar = show;
+                self.layout_state.show_status_bar = Some(show);
+                self.save_layout();
+            }
+            Action::ToggleHelpBar => {
+                let show = !self.config.layout.show_help_bar;
+                self.config.layout.show_help_bar = show;
+                self.layout_state.show_help_bar = Some(show);
+                self.save_layout();
+            }
+            Action::FocusNext => self.cycle_focus(1),
+            Action::FocusPrev => self.cycle_focus(-1),
+            Action::FocusTaskList => self.focus = Pane::TaskList,
+            Action::Focus(pane) => self.focus = pane,
+            Action::ToggleLogPane => {
+                let show = !self.config.layout.show_log_pane;
+                self.config.layout.show_log_pane = show;
+                self.layout_state.show_log_pane = Some(show);
+                if show {
+                    self.focus = Pane::Log;
+                } else {
+                    if self.focus == Pane::Log {
+                        self.focus = Pane::TaskList;
+                    }
+                    self.log_pane = None;
+                    self.log_pane_task = None;
+                }
+                self.save_layout();
+            }
+            Action::ScrollDetailsUp => self.scroll_details(-1),
+            Action::ScrollDetailsDown => self.scroll_details(1),
+            Action::ScrollDetailsPageUp => self.scroll_details(-20),
+            Action::ScrollDetailsPageDown => self.scroll_details(20),
+            Action::ScrollDetailsTop => self.details_scroll = 0,
+            Action::ScrollDetailsBottom => self.details_scroll = self.details_max_scroll.get(),
+            Action::ScrollLogPaneUp => self.scroll_log_pane(-1),
+            Action::ScrollLogPaneDown => self.scroll_log_pane(1),
+            Action::ScrollLogPanePageUp => self.scroll_log_pane(-20),
+            Action::ScrollLogPanePageDown => self.scroll_log_pane(20),
+            Action::ScrollLogPaneTop => self.log_pane_scroll = 0,
+            Action::ScrollLogPaneBottom => self.log_pane_scroll = usize::MAX,
+            Action::ToggleDiagnostics => {
+                if self.close(Mode::Diagnostics).is_none() {
+                    self.open(Overlay::Diagnostics);
+                    self.run_diagnostics();
+                }
+            }
+            Action::CloseDiagnostics => {
+                self.close(Mode::Diagnostics);
+            }
+            Action::RunDiagnostics => self.run_diagnostics(),
+            Action::ScrollDiagnosticsUp => self.scroll_diagnostics(-1),
+            Action::ScrollDiagnosticsDown => self.scroll_diagnostics(1),
+            Action::ShowUndoHistory => {
+                // Listed in the palette, newest first
+                self.text_input.clear();
+                self.palette_scope = PaletteScope::Undo;
+                self.palette_selected = 0;
+                self.open(Overlay::Palette);
+            }
+            Action::SelectItem(item) => {
+                // Clicking a group header toggles it
+                if let TreeItem::Group(name) = &item {
+                    if !self.collapsed_groups.remove(name) {
+                        self.collapsed_groups.insert(name.clone());
                     }
                 }
+                self.select_tree_item(&item);
+                self.focus = Pane::TaskList;
+            }
+            Action::OpenItem(item) => {
+                self.select_tree_item(&item);
+                if let TreeItem::Task(_, task_id) = item {
+                    self.load_logs(task_id, false);
+                }
             }
             Action::Quit => {
-                return Ok(true);
+                return true;
+            }
+        }
+        self.load_log_pane();
+        false
+    }
+
+    /// Scroll the details pane by `delta` lines, within its content
+    fn scroll_details(&mut self, delta: isize) {
+        self.details_scroll = self
+            .details_scroll
+            .saturating_add_signed(delta)
+            .min(self.details_max_scroll.get());
+    }
+
+    fn scroll_activity(&mut self, delta: isize) {
+        self.activity_scroll =
+            scrolled_by(self.activity_scroll, delta, self.activity_max_scroll.get());
+    }
+
+    fn scroll_messages(&mut self, delta: isize) {
+        self.messages_scroll =
+            scrolled_by(self.messages_scroll, delta, self.messages_max_scroll.get());
+    }
+
+    fn scroll_diagnostics(&mut self, delta: isize) {
+        self.diagnostics_scroll = scrolled_by(
+            self.diagnostics_scroll,
+            delta,
+            self.diagnostics_max_scroll.get(),
+        );
+    }
+
+    fn scroll_help(&mut self, delta: isize) {
+        self.help_scroll = scrolled_by(self.help_scroll, delta, self.help_max_scroll.get());
+    }
+
+    fn scroll_log(&mut self, delta: isize) {
+        self.log_scroll = log_scrolled_by(self.log_scroll, delta, self.log_max_scroll.get());
+    }
+
+    /// Scroll the docked log by `delta` lines. Reaching the end follows the
+    /// output again.
+    fn scroll_log_pane(&mut self, delta: isize) {
+        self.log_pane_scroll =
+            log_scrolled_by(self.log_pane_scroll, delta, self.log_pane_max_scroll.get());
+    }
+
+    /// Fetch the log of the selected task for the docked pane, unless it's
+    /// hidden or has been requested already. Polls keep it up to date.
+    fn load_log_pane(&mut self) {
+        if !self.config.layout.show_log_pane {
+            return;
+        }
+        let Some(task_id) = self.get_selected_task_id() else {
+            return;
+        };
+        if self.log_pane_task == Some(task_id) {
+            return;
+        }
+        self.log_pane_task = Some(task_id);
+        self.log_pane_scroll = usize::MAX;
+        self.submit(None, move |client| {
+            Box::pin(async move {
+                match client.get_log(task_id).await {
+                    Ok(content) => Outcome::new().effect(Effect::FollowLogs { task_id, content }),
+                    Err(_) => Outcome::new(),
+                }
+            })
+        });
+    }
+
+    /// Open the input dialog for a command, with a fresh history position.
+    /// The commands of the daemon's tasks are offered as older history.
+    fn start_command_input(&mut self, mode: InputMode) {
+        if let Some(state) = &self.state {
+            self.history.seed(
+                state
+                    .tasks
+                    .values()
+                    .map(|task| task.original_command.as_str()),
+            );
+        }
+        self.history_index = None;
+        self.history_search = None;
+        self.text_input.multiline = true;
+        self.open_input(mode);
+    }
+
+    fn is_command_input(&self) -> bool {
+        matches!(
+            self.input_mode(),
+            Some(InputMode::AddTask | InputMode::EditTask(_))
+        )
+    }
+
+    /// Change the size of the task list by `delta` percent, within 10 to 90
+    fn resize_task_list(&mut self, delta: i16) {
+        let percent = (self.config.layout.task_list_percent as i16 + delta).clamp(10, 90) as u16;
+        self.config.layout.task_list_percent = percent;
+        self.layout_state.task_list_percent = Some(percent);
+        self.zoomed = false;
+        self.save_layout();
+    }
+
+    fn save_layout(&mut self) {
+        if let Some(path) = &self.layout_path {
+            if let Err(e) = self.layout_state.save(path) {
+                self.messages
+                    .warn(format!("Failed to save the layout: {:#}", e));
+            }
+        }
+    }
+
+    /// Add a submitted command to the history and save it
+    fn remember_command(&mut self, command: &str) {
+        self.history.push(command);
+        if let Some(path) = &self.history_path {
+            if let Err(e) = self.history.save(path) {
+                self.messages
+                    .warn(format!("Failed to save command history: {:#}", e));
+            }
+        }
+    }
+
+    /// Handle an action during a reverse history search. Returns false for
+    /// actions that end the search and then run as usual.
+    fn handle_history_search(&mut self, action: &Action) -> bool {
+        let Some(search) = &mut self.history_search else {
+            return false;
+        };
+        let from = match action {
+            Action::InputChar(c) => {
+                search.query.push(*c);
+                0
+            }
+            Action::InputBackspace => {
+                search.query.pop();
+                0
+            }
+            Action::InputHistorySearch => search.found.map_or(0, |i| i + 1),
+            Action::SubmitInput => {
+                self.history_search = None;
+                return true;
             }
+            Action::CancelInput => {
+                let original = std::mem::take(&mut search.original);
+                self.text_input.set_value(original);
+                self.history_search = None;
+                return true;
+            }
+            _ => {
+                self.history_search = None;
+                return false;
+            }
+        };
+
+        search.found = self.history.search(&search.query, from);
+        if let Some(command) = search.found.and_then(|i| self.history.get(i)) {
+            self.text_input.set_value(command);
         }
-        Ok(false)
+        true
+    }
+
+    fn add_task(&mut self, options: AddOptions) {
+        self.submit(Some("Adding task".to_string()), move |client| {
+            Box::pin(async move {
+                match client.add_with(options).await {
+                    Ok(task_id) => {
+                        Outcome::new()
+                            .success(format!("Added task #{}", task_id))
+                            .refresh(client)
+                            .await
+                    }
+                    Err(e) => Outcome::new().error(format!("Failed to add task: {}", e)),
+                }
+            })
+        });
+    }
+
+    /// Ask for the next placeholder value, or add the task once all are known
+    fn continue_template(&mut self, fill: TemplateFill) {
+        if fill.current().is_some() {
+            self.text_input.clear();
+            self.open_input(InputMode::TemplateValue(fill));
+            return;
+        }
+
+        let template = fill.template;
+        let group = template
+            .group
+            .clone()
+            .unwrap_or_else(|| self.get_selected_group().to_string());
+        self.add_task(AddOptions {
+            command: templates::fill(&template.command, &fill.values),
+            group,
+            path: template.path.as_deref().map(config::expand_tilde),
+            envs: template.env.into_iter().collect(),
+            label: template.label,
+            priority: template.priority,
+        });
+    }
+
+    /// Save a task as a new template, both in the config file and in memory
+    fn save_template(&mut self, task_id: usize, name: &str) {
+        if name.is_empty() {
+            return;
+        }
+        if self.config.templates.contains_key(name) {
+            self.messages
+                .warn(format!("A template named '{}' already exists", name));
+            return;
+        }
+        let Some(task) = self.task(task_id) else {
+            return;
+        };
+        let template = Template::from_task(task);
+
+        if let Some(path) = &self.config_path {
+            if let Err(e) = templates::append_to_config(path, name, &template) {
+                self.messages
+                    .error(format!("Failed to save template: {:#}", e));
+                return;
+            }
+        }
+        self.config.templates.insert(name.to_string(), template);
+        self.messages.success(format!("Saved template '{}'", name));
+    }
+
+    /// Revert the operation at `index` of the undo stack, newest first. It
+    /// goes back on the stack if reverting fails.
+    fn undo_at(&mut self, index: usize) {
+        let position = self.undo.len().saturating_sub(index + 1);
+        let Some(mut entry) = self.undo.take(index) else {
+            self.messages.info("Nothing to undo");
+            return;
+        };
+        let stashed = self.config.undo.restore_stashed;
+        self.submit_to(
+            entry.daemon,
+            JobKind::Action,
+            Some(format!("Undoing: {}", entry.description)),
+            move |client| {
+                Box::pin(async move {
+                    match undo::revert(&mut entry.op, client, stashed).await {
+                        Ok(()) => {
+                            Outcome::new()
+                                .success(format!("Undone: {}", entry.description))
+                                .refresh(client)
+                                .await
+                        }
+                        // Tasks added again before the error are on the daemon now
+                        Err(e) => {
+                            Outcome::new()
+                                .error(format!("Failed to undo '{}': {}", entry.description, e))
+                                .effect(Effect::RestoreUndoable { position, entry })
+                                .refresh(client)
+                                .await
+                        }
+                    }
+                })
+            },
+        );
+    }
+
+    fn task(&self, task_id: usize) -> Option<&Task> {
+        self.state.as_ref()?.tasks.get(&task_id)
+    }
+
+    /// Tasks a move applies to: the marked tasks, or else the selected one
+    pub fn move_targets(&self) -> Vec<usize> {
+        if self.marked_tasks.is_empty() {
+            self.get_selected_task_id().into_iter().collect()
+        } else {
+            self.marked_tasks.iter().copied().collect()
+        }
+    }
+
+    /// Close the palette and return the selected command, if any
+    fn take_palette_action(&mut self) -> Option<Action> {
+        let action = palette::palette_matches(self)
+            .into_iter()
+            .nth(self.palette_selected)
+            .and_then(|m| m.entry.action);
+        self.close(Mode::Palette);
+        self.text_input.clear();
+        self.palette_selected = 0;
+        action
+    }
+
+    /// Key handling mode for the topmost dialog, or else the focused pane
+    pub fn mode(&self) -> Mode {
+        match self.overlays.last() {
+            Some(overlay) => overlay.mode(),
+            // The error screen replaces the panes
+            None if self.connection_error.is_some() => Mode::Normal,
+            None => self.focus.mode(),
+        }
+    }
+
+    /// Show an overlay on top, moving it there if it's open already
+    pub fn open(&mut self, overlay: Overlay) {
+        self.close(overlay.mode());
+        self.overlays.push(overlay);
+    }
+
+    /// Close the overlay of the given mode, wherever it is in the stack
+    pub fn close(&mut self, mode: Mode) -> Option<Overlay> {
+        let index = self.overlays.iter().rposition(|o| o.mode() == mode)?;
+        Some(self.overlays.remove(index))
+    }
+
+    pub fn open_input(&mut self, mode: InputMode) {
+        self.open(Overlay::Input(Box::new(mode)));
+    }
+
+    pub fn is_open(&self, mode: Mode) -> bool {
+        self.overlays.iter().any(|overlay| overlay.mode() == mode)
+    }
+
+    /// The open input dialog
+    pub fn input_mode(&self) -> Option<&InputMode> {
+        self.overlays
+            .iter()
+            .rev()
+            .find_map(|overlay| match overlay {
+                Overlay::Input(mode) => Some(mode.as_ref()),
+                _ => None,
+            })
+    }
+
+    /// The task the open confirmation dialog asks about removing
+    pub fn confirm_delete(&self) -> Option<usize> {
+        self.overlays
+            .iter()
+            .rev()
+            .find_map(|overlay| match overlay {
+                Overlay::ConfirmDelete(task_id) => Some(*task_id),
+                _ => None,
+            })
+    }
+
+    /// Panes on screen, in focus order
+    pub fn panes(&self) -> Vec<Pane> {
+        let mut panes = vec![Pane::TaskList, Pane::Details];
+        if self.config.layout.show_log_pane {
+            panes.push(Pane::Log);
+        }
+        panes
+    }
+
+    /// Move the focus `step` panes forward or backward, wrapping around
+    fn cycle_focus(&mut self, step: isize) {
+        let panes = self.panes();
+        let current = panes.iter().position(|p| *p == self.focus).unwrap_or(0) as isize;
+        let next = (current + step).rem_euclid(panes.len() as isize) as usize;
+        self.focus = panes[next];
     }
 
     pub fn get_selected_task_id(&self) -> Option<usize> {
@@ -691,26 +2846,28 @@ impl App {
     /// Build the flattened tree of visible items for navigation
     pub fn get_tree_items(&self) -> Vec<TreeItem> {
         let mut items = Vec::new();
-        let groups = self.get_group_list();
+        let Some(state) = &self.state else {
+            return items;
+        };
 
-        if let Some(state) = &self.state {
-            for group_name in groups {
-                // Add the group header
-                items.push(TreeItem::Group(group_name.clone()));
-
-                // If not collapsed, add tasks in this group
-                if !self.collapsed_groups.contains(&group_name) {
-                    let mut tasks_in_group: Vec<_> = state
-                        .tasks
-                        .iter()
-                        .filter(|(_, t)| t.group == group_name)
-                        .map(|(id, _)| *id)
-                        .collect();
-                    tasks_in_group.sort();
-                    for task_id in tasks_in_group {
-                        items.push(TreeItem::Task(group_name.clone(), task_id));
-                    }
-                }
+        // Bucket task ids by group in a single pass, keeping the sort order
+        let mut tasks_by_group: HashMap<&str, Vec<usize>> = HashMap::new();
+        for task in filter::select_tasks(state, &self.task_filter, self.sort_mode) {
+            tasks_by_group.entry(&task.group).or_default().push(task.id);
+        }
+
+        for group_name in self.get_group_list() {
+            // If not collapsed, add tasks in this group
+            let task_ids = if self.collapsed_groups.contains(&group_name) {
+                None
+            } else {
+                tasks_by_group.remove(group_name.as_str())
+            };
+
+            // Add the group header
+            items.push(TreeItem::Group(group_name.clone()));
+            for task_id in task_ids.into_iter().flatten() {
+                items.push(TreeItem::Task(group_name.clone(), task_id));
             }
         }
 
@@ -732,6 +2889,7 @@ impl App {
             TreeItem::Group(name) => TreeSelection::Group(name.clone()),
             TreeItem::Task(group, task_id) => TreeSelection::Task(group.clone(), *task_id),
         };
+        self.details_scroll = 0;
     }
 
     pub fn get_task_list(&self) -> Vec<(usize, &pueue_lib::task::Task)> {
@@ -759,22 +2917,20 @@ impl App {
             Vec::new()
         }
     }
+}
 
-    pub async fn refresh_logs(&mut self, client: &mut PueueClient) -> Result<()> {
-        if self.follow_mode {
-            if let Some(task_id) = self.get_selected_task_id() {
-                match client.get_log(task_id).await {
-                    Ok(content) => {
-                        self.log_content = Some(content);
-                        // Keep scroll at the end for follow mode
-                        self.log_scroll = usize::MAX;
-                    }
-                    Err(_) => {
-                        // Silently ignore errors during follow refresh
-                    }
-                }
-            }
-        }
-        Ok(())
+/// `offset` moved by `delta` lines, kept within `0..=max`
+fn scrolled_by(offset: usize, delta: isize, max: usize) -> usize {
+    offset.min(max).saturating_add_signed(delta).min(max)
+}
+
+/// Log scroll `offset` moved by `delta` lines. `usize::MAX` stands for the
+/// end of the log, which reaching `max` returns to.
+fn log_scrolled_by(offset: usize, delta: isize, max: usize) -> usize {
+    let scroll = offset.min(max).saturating_add_signed(delta);
+    if scroll >= max {
+        usize::MAX
+    } else {
+        scroll
     }
 }
@@ -0,0 +1,363 @@
+use anyhow::{bail, Result};
+use clap::Subcommand;
+use pueue_lib::state::State;
+use pueue_lib::task::{Task, TaskResult, TaskStatus};
+use std::io::Write;
+use std::time::Duration;
+
+use crate::app::{App, Overlay, TreeSelection};
+use crate::compat::DaemonVersion;
+use crate::config::Config;
+use crate::diagnostics;
+use crate::filter::{self, SortMode, TaskFilter};
+use crate::notify::result_name;
+use crate::pueue_client::PueueClient;
+use crate::snapshot::{self, SnapshotFormat, SnapshotView};
+
+/// Commands for scripts and CI that run without the TUI
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Print the tasks, filtered and sorted like in the TUI
+    Status {
+        /// Print the groups and tasks as JSON
+        #[arg(long)]
+        json: bool,
+        /// Filter query, e.g. "status:failed group:ci deploy"
+        #[arg(short, long, value_name = "QUERY")]
+        filter: Option<String>,
+        /// Order of the tasks
+        #[arg(short, long, value_enum, default_value_t)]
+        sort: SortMode,
+    },
+    /// Print a task's status changes until it finishes, then exit with its
+    /// exit code
+    Watch { id: usize },
+    /// Wait until tasks are done; exits with 1 if any of them failed.
+    /// Stashed tasks aren't waited for.
+    Wait {
+        /// Tasks to wait for [default: all matching tasks]
+        ids: Vec<usize>,
+        /// Only wait for tasks in this group
+        #[arg(short, long)]
+        group: Option<String>,
+        /// Only wait for tasks matching this filter query
+        #[arg(short, long, value_name = "QUERY")]
+        filter: Option<String>,
+    },
+    /// Print a task's output
+    Tail {
+        id: usize,
+        /// Keep printing new output until the task finishes, then exit with
+        /// its exit code
+        #[arg(short, long)]
+        follow: bool,
+        /// Only print the last lines
+        #[arg(short = 'n', long, value_name = "N")]
+        lines: Option<usize>,
+    },
+    /// Render one frame of the TUI and print it, e.g. for bug reports or as
+    /// a golden file
+    Snapshot {
+        #[arg(long, default_value_t = 120)]
+        width: u16,
+        #[arg(long, default_value_t = 40)]
+        height: u16,
+        /// Select this task
+        #[arg(long, value_name = "ID")]
+        select: Option<usize>,
+        /// Open a view on top of the task list
+        #[arg(long, value_enum)]
+        view: Option<SnapshotView>,
+        /// Filter query, e.g. "status:failed group:ci deploy"
+        #[arg(long, value_name = "QUERY")]
+        filter: Option<String>,
+        /// Order of the tasks
+        #[arg(long, value_enum, default_value_t)]
+        sort: SortMode,
+        #[arg(short = 'o', long, value_enum, default_value_t)]
+        format: SnapshotFormat,
+    },
+    /// Check the connection to every daemon, or the one given with
+    /// --daemon, and explain failures; exits with 1 if any check failed
+    Doctor,
+}
+
+/// Run a command against the daemon at index `daemon` of the config, the
+/// first one if unset, and return the process exit code
+pub async fn run(command: Command, config: &Config, daemon: Option<usize>) -> Result<i32> {
+    if let Command::Doctor = command {
+        return doctor(config, daemon).await;
+    }
+    let daemon = daemon.unwrap_or(0);
+    let (_, daemon_config) = &config.daemon_list()[daemon];
+    let endpoint = daemon_config.endpoint().map_err(anyhow::Error::msg)?;
+    let mut client = PueueClient::open(&endpoint).await?;
+    if let Some(warning) = DaemonVersion::new(client.daemon_version()).warning() {
+        eprintln!("Warning: {}", warning);
+    }
+    let client = &mut client;
+    let interval = Duration::from_millis(config.refresh.interval_ms);
+    let follow_interval = Duration::from_millis(config.refresh.follow_interval_ms);
+    let mut out = std::io::stdout();
+
+    match command {
+        Command::Status { json, filter, sort } => {
+            let filter = parse_filter(filter.as_deref())?;
+            let state = client.get_state().await?;
+            if json {
+                writeln!(out, "{}", status_json(&state, &filter, sort))?;
+            } else {
+                write!(out, "{}", status_text(&state, &filter, sort))?;
+            }
+            Ok(0)
+        }
+        Command::Watch { id } => {
+            let mut last = None;
+            loop {
+                let state = client.get_state().await?;
+                let task = find_task(&state, id)?;
+                let status = status_name(task);
+                if last != Some(status) {
+                    let time = chrono::Local::now().format("%H:%M:%S");
+                    writeln!(out, "{} #{} {}", time, id, status)?;
+                    out.flush()?;
+                    last = Some(status);
+                }
+                if let Some(code) = exit_code(task) {
+                    return Ok(code);
+                }
+                tokio::time::sleep(interval).await;
+            }
+        }
+        Command::Wait { ids, group, filter } => {
+            let mut filter = parse_filter(filter.as_deref())?;
+            filter.groups.extend(group);
+            let mut reported = Vec::new();
+            loop {
+                let state = client.get_state().await?;
+                let tasks = if ids.is_empty() {
+                    filter::select_tasks(&state, &filter, SortMode::Id)
+                } else {
+                    ids.iter()
+                        .map(|id| find_task(&state, *id))
+                        .collect::<Result<_>>()?
+                };
+                // Report tasks as they finish
+                for task in &tasks {
+                    if exit_code(task).is_some() && !reported.contains(&task.id) {
+                        writeln!(out, "#{} {}", task.id, status_name(task))?;
+                        out.flush()?;
+                        reported.push(task.id);
+                    }
+                }
+                if let Some(code) = wait_result(&tasks) {
+                    return Ok(code);
+                }
+                tokio::time::sleep(interval).await;
+            }
+        }
+        Command::Tail { id, follow, lines } => {
+            let output = client.get_output(id).await?.unwrap_or_default();
+            write!(out, "{}", last_lines(&output, lines))?;
+            out.flush()?;
+            if !follow {
+                return Ok(0);
+            }
+
+            let mut printed = output;
+            loop {
+                // Check the status first, so output written just before the
+                // task finished is still printed
+                let state = client.get_state().await?;
+                let code = exit_code(find_task(&state, id)?);
+                let output = client.get_output(id).await?.unwrap_or_default();
+                write!(out, "{}", new_output(&printed, &output))?;
+                out.flush()?;
+                printed = output;
+                if let Some(code) = code {
+                    return Ok(code);
+                }
+                tokio::time::sleep(follow_interval).await;
+            }
+        }
+        Command::Snapshot {
+            width,
+            height,
+            select,
+            view,
+            filter,
+            sort,
+            format,
+        } => {
+            let mut app = App::with_config(config.clone());
+            app.active_daemon = daemon;
+            app.refresh(client).await?;
+            app.task_filter = parse_filter(filter.as_deref())?;
+            app.sort_mode = sort;
+            if let Some(id) = select {
+                let group = match &app.state {
+                    Some(state) => find_task(state, id)?.group.clone(),
+                    None => bail!("Task #{} doesn't exist", id),
+                };
+                app.selection = TreeSelection::Task(group, id);
+            }
+            match view {
+                Some(SnapshotView::Logs) => {
+                    let Some(id) = select else {
+                        bail!("--view logs needs a task to --select");
+                    };
+                    app.log_content = Some(client.get_log(id).await?);
+                    app.log_scroll = usize::MAX;
+                    app.open(Overlay::Log);
+                }
+                Some(SnapshotView::Activity) => app.open(Overlay::Activity),
+                Some(SnapshotView::Help) => app.open(Overlay::Help),
+                None => {}
+            }
+
+            let buffer = snapshot::render_frame(&app, width, height)?;
+            write!(out, "{}", snapshot::render(&buffer, format))?;
+            Ok(0)
+        }
+        Command::Doctor => unreachable!("doctor runs before connecting"),
+    }
+}
+
+fn parse_filter(query: Option<&str>) -> Result<TaskFilter> {
+    match TaskFilter::parse(query.unwrap_or_default()) {
+        Ok(filter) => Ok(filter),
+        Err(e) => bail!("Invalid filter: {}", e),
+    }
+}
+
+fn find_task(state: &State, id: usize) -> Result<&Task> {
+    match state.tasks.get(&id) {
+        Some(task) => Ok(task),
+        None => bail!("Task #{} doesn't exist", id),
+    }
+}
+
+/// Lowercase status, with the result for finished tasks, e.g. "running" or
+/// "failed"
+pub fn status_name(task: &Task) -> &'static str {
+    match &task.status {
+        TaskStatus::Queued { .. } => "queued",
+        TaskStatus::Stashed { .. } => "stashed",
+        TaskStatus::Running { .. } => "running",
+        TaskStatus::Paused { .. } => "paused",
+        TaskStatus::Locked { .. } => "locked",
+        TaskStatus::Done { result, .. } => result_name(result),
+    }
+}
+
+/// Exit code for a finished task: its own for a normal exit, 1 otherwise.
+/// `None` while it hasn't finished.
+pub fn exit_code(task: &Task) -> Option<i32> {
+    match &task.status {
+        TaskStatus::Done { result, .. } => Some(match result {
+            TaskResult::Success => 0,
+            TaskResult::Failed(code) => *code,
+            _ => 1,
+        }),
+        _ => None,
+    }
+}
+
+/// Exit code once all tasks are finished or stashed: 0 if all succeeded,
+/// 1 otherwise
+pub fn wait_result(tasks: &[&Task]) -> Option<i32> {
+    let mut code = 0;
+    for task in tasks {
+        match exit_code(task) {
+            Some(0) => {}
+            Some(_) => code = 1,
+            None if task.is_stashed() => {}
+            None => return None,
+        }
+    }
+    Some(code)
+}
+
+/// One line per task, grouped like the TUI's tree
+pub fn status_text(state: &State, filter: &TaskFilter, sort: SortMode) -> String {
+    let tasks = filter::select_tasks(state, filter, sort);
+    let mut groups: Vec<&String> = state.groups.keys().collect();
+    groups.sort_by_key(|name| (*name != "default", *name));
+
+    let mut text = String::new();
+    for name in groups {
+        let group = &state.groups[name];
+        let group_tasks: Vec<_> = tasks.iter().filter(|t| &t.group == name).collect();
+        if group_tasks.is_empty() && !filter.is_empty() {
+            continue;
+        }
+        text.push_str(&format!(
+            "{} ({}, {} parallel)\n",
+            name,
+            format!("{:?}", group.status).to_lowercase(),
+            group.parallel_tasks
+        ));
+        for task in group_tasks {
+            let label = task
+                .label
+                .as_ref()
+                .map(|label| format!(" [{}]", label))
+                .unwrap_or_default();
+            text.push_str(&format!(
+                "  #{:<4} {:<17} {}{}\n",
+                task.id,
+                status_name(task),
+                task.command,
+                label
+            ));
+        }
+    }
+    text
+}
+
+/// Groups and the selected tasks in sort order, as pueue serializes them
+pub fn status_json(state: &State, filter: &TaskFilter, sort: SortMode) -> serde_json::Value {
+    serde_json::json!({
+        "groups": state.groups,
+        "tasks": filter::select_tasks(state, filter, sort),
+    })
+}
+
+/// The last `lines` lines of the output, or all of it
+pub fn last_lines(output: &str, lines: Option<usize>) -> &str {
+    let Some(lines) = lines else {
+        return output;
+    };
+    if lines == 0 {
+        return "";
+    }
+    let trimmed = output.strip_suffix('\n').unwrap_or(output);
+    match trimmed.rmatch_indices('\n').nth(lines - 1) {
+        Some((i, _)) => &output[i + 1..],
+        None => output,
+    }
+}
+
+/// Output that's new since `printed`. If the log was replaced, e.g. by a
+/// restart, all of it.
+pub fn new_output<'a>(printed: &str, output: &'a str) -> &'a str {
+    output.strip_prefix(printed).unwrap_or(output)
+}
+
+/// Diagnose the daemon at index `daemon` of the config, or all of them
+async fn doctor(config: &Config, daemon: Option<usize>) -> Result<i32> {
+    let mut reports = Vec::new();
+    for (index, (name, daemon_config)) in config.daemon_list().into_iter().enumerate() {
+        if daemon.is_some_and(|daemon| daemon != index) {
+            continue;
+        }
+        let endpoint = daemon_config.endpoint().map_err(anyhow::Error::msg)?;
+        reports.push(diagnostics::diagnose(&name, &endpoint).await);
+    }
+    print!("{}", diagnostics::to_text(&reports));
+    Ok(if reports.iter().all(|r| r.is_ok()) {
+        0
+    } else {
+        1
+    })
+}
@@ -0,0 +1,179 @@
+use std::fmt;
+use std::ops::Range;
+
+/// Protocol versions lazypueue was built and tested against, matching the
+/// pinned pueue-lib. Pueue daemons report their pueue-lib version in the
+/// handshake.
+pub const SUPPORTED: Range<Version> = Version::new(0, 29, 0)..Version::new(0, 31, 0);
+
+/// A `major.minor.patch` version; pre-release and build suffixes are ignored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let core = text.trim().split(['-', '+']).next()?;
+        let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+        let major = parts.next()??;
+        let minor = parts.next().unwrap_or(Some(0))?;
+        let patch = parts.next().unwrap_or(Some(0))?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The requests lazypueue sends, by their `Request` variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestKind {
+    Status,
+    Log,
+    Add,
+    Remove,
+    Kill,
+    Pause,
+    Start,
+    Stash,
+    Enqueue,
+    Switch,
+    Edit,
+    Parallel,
+    Clean,
+}
+
+impl RequestKind {
+    /// First protocol version with the request in its current wire format.
+    /// pueue-lib 0.28 split messages into requests and responses, and 0.29
+    /// renamed the variants of all requests that carry a payload.
+    pub fn since(self) -> Version {
+        match self {
+            RequestKind::Status | RequestKind::Log => Version::new(0, 28, 0),
+            _ => Version::new(0, 29, 0),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RequestKind::Status => "status",
+            RequestKind::Log => "log",
+            RequestKind::Add => "add",
+            RequestKind::Remove => "remove",
+            RequestKind::Kill => "kill",
+            RequestKind::Pause => "pause",
+            RequestKind::Start => "start",
+            RequestKind::Stash => "stash",
+            RequestKind::Enqueue => "enqueue",
+            RequestKind::Switch => "switch",
+            RequestKind::Edit => "edit",
+            RequestKind::Parallel => "parallel",
+            RequestKind::Clean => "clean",
+        }
+    }
+}
+
+/// How a daemon's version relates to the supported range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    Supported,
+    /// Older requests are disabled where their format changed since
+    Older,
+    /// Everything is tried, but may fail
+    Newer,
+    /// The version couldn't be parsed
+    Unknown,
+}
+
+/// The version a daemon reported in the handshake
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaemonVersion {
+    pub text: String,
+    /// `None` if unparsable
+    protocol: Option<Version>,
+}
+
+impl DaemonVersion {
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let protocol = Version::parse(&text).map(|version| {
+            // Daemons before pueue 4 sent pueue's own version, e.g. 3.4.1,
+            // and speak an older protocol than any listed
+            if (1..4).contains(&version.major) {
+                Version::new(0, 0, 0)
+            } else {
+                version
+            }
+        });
+        Self { text, protocol }
+    }
+
+    pub fn compatibility(&self) -> Compatibility {
+        match self.protocol {
+            None => Compatibility::Unknown,
+            Some(version) if version < SUPPORTED.start => Compatibility::Older,
+            Some(version) if version >= SUPPORTED.end => Compatibility::Newer,
+            Some(_) => Compatibility::Supported,
+        }
+    }
+
+    /// Whether the daemon understands the request. Only older daemons are
+    /// known not to; newer ones get the benefit of the doubt.
+    pub fn supports(&self, kind: RequestKind) -> bool {
+        match (self.compatibility(), self.protocol) {
+            (Compatibility::Older, Some(version)) => version >= kind.since(),
+            _ => true,
+        }
+    }
+
+    /// Explanation for the banner, unless the version is supported
+    pub fn warning(&self) -> Option<String> {
+        let range = format!(
+            "{}.{} to {}.{}",
+            SUPPORTED.start.major,
+            SUPPORTED.start.minor,
+            SUPPORTED.end.major,
+            SUPPORTED.end.minor - 1
+        );
+        match self.compatibility() {
+            Compatibility::Supported => None,
+            Compatibility::Older => Some(format!(
+                "Daemon version {} is older than supported ({}); actions it doesn't \
+                 understand are disabled. Update pueue and restart the daemon.",
+                self.text, range
+            )),
+            Compatibility::Newer => Some(format!(
+                "Daemon version {} is newer than supported ({}); some actions may fail. \
+                 Update lazypueue.",
+                self.text, range
+            )),
+            Compatibility::Unknown => Some(format!(
+                "Daemon reported an unknown version '{}'; some actions may fail.",
+                self.text
+            )),
+        }
+    }
+}
+
+impl fmt::Display for DaemonVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
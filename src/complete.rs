@@ -0,0 +1,168 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::config::expand_tilde;
+
+/// The result of completing the word before the cursor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    /// Byte offset where the completed word starts
+    pub start: usize,
+    /// Text replacing the word, as far as all candidates agree
+    pub replacement: String,
+    /// All matching names when more than one matches
+    pub candidates: Vec<String>,
+}
+
+/// Complete the word before `cursor`: the first word as an executable on
+/// `path_var`, every other word (or one containing a `/`) as a file path
+/// relative to `cwd`. Spaces and shell metacharacters in the completed word
+/// are escaped with backslashes, which the word before the cursor may use too.
+pub fn complete(
+    line: &str,
+    cursor: usize,
+    path_var: Option<&OsStr>,
+    cwd: &Path,
+) -> Option<Completion> {
+    let before = line.get(..cursor)?;
+    let start = word_start(before);
+    let word = unescape(&before[start..]);
+    let is_command = before[..start].trim().is_empty();
+
+    let (dir, mut candidates) = if is_command && !word.contains('/') {
+        let dirs: Vec<PathBuf> = path_var
+            .map(|p| std::env::split_paths(p).collect())
+            .unwrap_or_default();
+        ("", executables(&dirs, &word))
+    } else {
+        let (dir, prefix) = match word.rfind('/') {
+            Some(i) => word.split_at(i + 1),
+            None => ("", word.as_str()),
+        };
+        let listed = if dir.is_empty() {
+            cwd.to_path_buf()
+        } else {
+            cwd.join(expand_tilde(Path::new(dir)))
+        };
+        (dir, files(&listed, prefix))
+    };
+
+    candidates.sort();
+    candidates.dedup();
+    let common = common_prefix(&candidates)?;
+    let replacement = match candidates.as_slice() {
+        // A single file or executable is done, a directory may go deeper
+        [only] if !only.ends_with('/') => format!("{} ", escape(&format!("{}{}", dir, only))),
+        _ => escape(&format!("{}{}", dir, common)),
+    };
+    if candidates.len() == 1 {
+        candidates.clear();
+    }
+    Some(Completion {
+        start,
+        replacement,
+        candidates,
+    })
+}
+
+/// Byte offset of the last word in `before`, skipping whitespace escaped
+/// with a backslash
+fn word_start(before: &str) -> usize {
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in before.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c.is_whitespace() {
+            start = i + c.len_utf8();
+        }
+    }
+    start
+}
+
+/// `word` as the shell reads it, without the backslashes of escapes
+fn unescape(word: &str) -> String {
+    let mut unescaped = String::with_capacity(word.len());
+    let mut chars = word.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            unescaped.extend(chars.next());
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
+
+/// `word` with a backslash before whitespace and shell metacharacters. A `~`
+/// is left alone so a leading one still expands.
+fn escape(word: &str) -> String {
+    let mut escaped = String::with_capacity(word.len());
+    for c in word.chars() {
+        if c.is_whitespace() || "\\'\"$`&|;<>()[]{}*?!#".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Longest prefix shared by all candidates, `None` without candidates
+fn common_prefix(candidates: &[String]) -> Option<&str> {
+    let first = candidates.first()?;
+    let mut len = first.len();
+    for candidate in &candidates[1..] {
+        len = first
+            .char_indices()
+            .zip(candidate.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, a), _)| i + a.len_utf8())
+            .min(len);
+    }
+    Some(&first[..len])
+}
+
+/// Names in `dir` starting with `prefix`, directories with a trailing `/`.
+/// Hidden files are only offered when the prefix starts with a dot.
+fn files(dir: &Path, prefix: &str) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            let is_dir = entry.path().is_dir();
+            Some(if is_dir { format!("{}/", name) } else { name })
+        })
+        .collect()
+}
+
+/// Executable names starting with `prefix` in any of `dirs`
+fn executables(dirs: &[PathBuf], prefix: &str) -> Vec<String> {
+    dirs.iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            (name.starts_with(prefix) && is_executable(&entry.path())).then_some(name)
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
@@ -0,0 +1,440 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::activity;
+use crate::keymap::{Keymap, KeymapError, KeysConfig};
+use crate::layout::LayoutPreset;
+use crate::notify::{NotifyConfig, NotifyMethod, ResultFilter};
+use crate::pueue_client::Endpoint;
+use crate::ssh::SshTarget;
+use crate::templates::Template;
+use crate::theme::ThemeName;
+
+/// Commented template printed by `lazypueue --print-default-config`.
+/// Parsing it must yield `Config::default()`.
+pub const DEFAULT_CONFIG: &str = r#"# lazypueue configuration
+#
+# Location: $XDG_CONFIG_HOME/lazypueue/config.toml (usually ~/.config/lazypueue/config.toml)
+# Override with `lazypueue --config <PATH>`. Every key is optional.
+
+[general]
+# Group selected on startup and used when the task list is empty
+default_group = "default"
+# Colors: "dark", "light", "high-contrast" or "no-color". Unset, it's
+# "no-color" if the NO_COLOR environment variable is set, else "dark".
+# theme = "light"
+
+[refresh]
+# How often the daemon state is polled, in milliseconds
+interval_ms = 500
+# Poll interval while following logs, in milliseconds
+follow_interval_ms = 200
+
+[layout]
+# "horizontal" (side by side), "vertical" (stacked) or "auto": vertical in
+# terminals narrower than vertical_below columns
+preset = "auto"
+vertical_below = 80
+# Size of the task list in percent; the details pane gets the rest
+task_list_percent = 40
+show_status_bar = true
+show_help_bar = true
+# Dock the log of the selected task below the other panes
+show_log_pane = false
+# Changes made with the layout keys are kept in
+# ~/.local/state/lazypueue/layout.toml and override these settings
+
+[notifications]
+# Notify when a task finishes: "bell", "osc9", "osc777", "tmux"
+methods = []
+# Shell command to run when a task finishes. Placeholders:
+# {id} {group} {label} {command} {result} {exit_code}
+# command = 'notify-send "pueue #{id}" {command}'
+# Only notify for tasks in these groups (empty = all)
+groups = []
+# Only notify for tasks with these labels (empty = any)
+labels = []
+# Only notify for these results: "all", "success", "failure"
+result = "all"
+
+[activity]
+# Number of events kept in the activity pane
+capacity = 1000
+# Also append every event to this file
+# log_file = "~/.local/state/lazypueue/activity.log"
+
+[undo]
+# Restore removed tasks as stashed, so they don't start right away. Finished
+# tasks are always restored stashed.
+restore_stashed = true
+
+# Daemons to monitor, in the order of their names. Switch between them with
+# g t and g T. Without any, lazypueue connects to the daemon of pueue's default
+# config.
+#
+# [daemons.local]
+# [daemons.project]
+# pueue_config = "~/src/app/.pueue/pueue.yml"  # pueue.yml of the daemon
+# [daemons.build1]
+# # Forward a remote daemon's socket with ssh; the path defaults to pueue's
+# url = "ssh://me@build1:/run/user/1000/pueue_me.socket"
+# ssh_command = ["ssh", "-i", "~/.ssh/build"]  # default: ["ssh"]
+
+# Saved commands offered when adding a task. Placeholders like {branch} are
+# asked for; shell syntax like ${VAR} is left alone.
+#
+# [templates.deploy]
+# command = "./deploy.sh --env {env} --branch {branch}"
+# group = "deploy"                 # default: the selected group
+# path = "~/src/app"               # default: lazypueue's working directory
+# label = "deploy"
+# priority = 1
+# env = { RUST_LOG = "info" }      # on top of lazypueue's environment
+
+# Key bindings per mode: normal, details, log_pane, log, activity, messages,
+# diagnostics, input, confirm, help, palette.
+# Map a key sequence to an action name, or to "none" to unbind a default.
+# Keys: "j", "G", "ctrl-d", "alt-enter", "space", "tab", "pageup", "f5", ...
+# Separate the keys of a sequence with spaces, e.g. "g g".
+# A sequence must not be a prefix of another one in the same mode.
+#
+# [keys.normal]
+# "ctrl-r" = "refresh"
+# "d d" = "none"
+# "D" = "remove_task"
+"#;
+
+/// Errors while loading the configuration file
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Failed to read config file {path:?}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid config file {path:?}:\n{message}")]
+    Parse { path: PathBuf, message: String },
+    #[error("Invalid config file {path:?}: {source}")]
+    Keymap {
+        path: PathBuf,
+        source: Box<KeymapError>,
+    },
+    #[error("Invalid config file {path:?}: `{key}` {message}")]
+    Invalid {
+        path: PathBuf,
+        key: String,
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub general: GeneralConfig,
+    pub refresh: RefreshConfig,
+    pub layout: LayoutConfig,
+    pub notifications: NotificationsConfig,
+    pub activity: ActivityConfig,
+    pub undo: UndoConfig,
+    pub templates: BTreeMap<String, Template>,
+    pub daemons: BTreeMap<String, DaemonConfig>,
+    pub keys: KeysConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GeneralConfig {
+    pub default_group: String,
+    /// `None` picks `no-color` if `NO_COLOR` is set, else `dark`
+    pub theme: Option<ThemeName>,
+}
+
+impl Default for GeneralConfig {
+    fn default() -> Self {
+        Self {
+            default_group: "default".to_string(),
+            theme: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RefreshConfig {
+    pub interval_ms: u64,
+    pub follow_interval_ms: u64,
+}
+
+impl Default for RefreshConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 500,
+            follow_interval_ms: 200,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutConfig {
+    pub preset: LayoutPreset,
+    /// Width below which the `auto` preset stacks the panes
+    pub vertical_below: u16,
+    pub task_list_percent: u16,
+    pub show_status_bar: bool,
+    pub show_help_bar: bool,
+    /// Dock the log of the selected task below the other panes
+    pub show_log_pane: bool,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            preset: LayoutPreset::Auto,
+            vertical_below: 80,
+            task_list_percent: 40,
+            show_status_bar: true,
+            show_help_bar: true,
+            show_log_pane: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationsConfig {
+    pub methods: Vec<NotifyMethod>,
+    pub command: Option<String>,
+    pub groups: Vec<String>,
+    pub labels: Vec<String>,
+    pub result: ResultFilter,
+}
+
+impl From<NotificationsConfig> for NotifyConfig {
+    fn from(config: NotificationsConfig) -> Self {
+        NotifyConfig {
+            methods: config.methods,
+            command: config.command,
+            groups: config.groups,
+            labels: config.labels,
+            result: config.result,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ActivityConfig {
+    pub capacity: usize,
+    pub log_file: Option<PathBuf>,
+}
+
+impl Default for ActivityConfig {
+    fn default() -> Self {
+        Self {
+            capacity: activity::DEFAULT_CAPACITY,
+            log_file: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UndoConfig {
+    pub restore_stashed: bool,
+}
+
+impl Default for UndoConfig {
+    fn default() -> Self {
+        Self {
+            restore_stashed: true,
+        }
+    }
+}
+
+/// How to reach a daemon
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DaemonConfig {
+    /// pueue.yml with the daemon's socket or host and its shared secret;
+    /// pueue's default config if unset
+    pub pueue_config: Option<PathBuf>,
+    /// `ssh://user@host[:socket-path]` of a remote daemon
+    pub url: Option<String>,
+    /// Program and arguments used instead of `ssh` for `url`
+    pub ssh_command: Vec<String>,
+}
+
+impl DaemonConfig {
+    /// How to reach the daemon. Fails for an invalid `url`.
+    pub fn endpoint(&self) -> Result<Endpoint, String> {
+        let Some(url) = &self.url else {
+            return Ok(Endpoint::Config(
+                self.pueue_config.as_deref().map(expand_tilde),
+            ));
+        };
+        let command = if self.ssh_command.is_empty() {
+            vec!["ssh".to_string()]
+        } else {
+            self.ssh_command.clone()
+        };
+        Ok(Endpoint::Ssh {
+            target: SshTarget::parse(url)?,
+            command,
+        })
+    }
+}
+
+/// Name of the daemon used when none are configured
+pub const DEFAULT_DAEMON: &str = "local";
+
+impl Config {
+    /// The configured daemons by name, or the default daemon
+    pub fn daemon_list(&self) -> Vec<(String, DaemonConfig)> {
+        if self.daemons.is_empty() {
+            return vec![(DEFAULT_DAEMON.to_string(), DaemonConfig::default())];
+        }
+        self.daemons
+            .iter()
+            .map(|(name, daemon)| (name.clone(), daemon.clone()))
+            .collect()
+    }
+
+    /// Default config file location, `$XDG_CONFIG_HOME/lazypueue/config.toml`
+    /// falling back to `~/.config/lazypueue/config.toml`
+    pub fn default_path() -> Option<PathBuf> {
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_dir.join("lazypueue").join("config.toml"))
+    }
+
+    /// Load the config from `path`, or from the default location if `None`.
+    ///
+    /// An explicitly given file must exist; a missing default file yields the
+    /// default config.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+
+        let content = std::fs::read_to_string(&path).map_err(|source| ConfigError::Read {
+            path: path.clone(),
+            source,
+        })?;
+        Self::parse(&content, &path)
+    }
+
+    /// Parse and validate config file content. `path` is only used for error messages.
+    pub fn parse(content: &str, path: &Path) -> Result<Self, ConfigError> {
+        let config: Config = toml::from_str(content).map_err(|e| ConfigError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        config.validate(path)?;
+        Ok(config)
+    }
+
+    /// Check value ranges that serde can't express
+    fn validate(&self, path: &Path) -> Result<(), ConfigError> {
+        let invalid = |key: &str, message: &str| ConfigError::Invalid {
+            path: path.to_path_buf(),
+            key: key.to_string(),
+            message: message.to_string(),
+        };
+
+        if self.general.default_group.trim().is_empty() {
+            return Err(invalid("general.default_group", "must not be empty"));
+        }
+        if !(50..=60_000).contains(&self.refresh.interval_ms) {
+            return Err(invalid(
+                "refresh.interval_ms",
+                "must be between 50 and 60000",
+            ));
+        }
+        if !(50..=60_000).contains(&self.refresh.follow_interval_ms) {
+            return Err(invalid(
+                "refresh.follow_interval_ms",
+                "must be between 50 and 60000",
+            ));
+        }
+        if !(10..=90).contains(&self.layout.task_list_percent) {
+            return Err(invalid(
+                "layout.task_list_percent",
+                "must be between 10 and 90",
+            ));
+        }
+        if self
+            .notifications
+            .command
+            .as_ref()
+            .is_some_and(|c| c.trim().is_empty())
+        {
+            return Err(invalid("notifications.command", "must not be empty"));
+        }
+        if self.activity.capacity == 0 {
+            return Err(invalid("activity.capacity", "must be at least 1"));
+        }
+        for (name, template) in &self.templates {
+            if template.command.trim().is_empty() {
+                return Err(invalid(
+                    &format!("templates.{}.command", name),
+                    "must not be empty",
+                ));
+            }
+        }
+        for (name, daemon) in &self.daemons {
+            if daemon
+                .pueue_config
+                .as_ref()
+                .is_some_and(|p| p.as_os_str().is_empty())
+            {
+                return Err(invalid(
+                    &format!("daemons.{}.pueue_config", name),
+                    "must not be empty",
+                ));
+            }
+            if daemon.url.is_some() && daemon.pueue_config.is_some() {
+                return Err(invalid(
+                    &format!("daemons.{}.url", name),
+                    "can't be combined with pueue_config",
+                ));
+            }
+            if let Err(e) = daemon.endpoint() {
+                return Err(invalid(&format!("daemons.{}.url", name), &e));
+            }
+        }
+        Keymap::from_config(&self.keys).map_err(|source| ConfigError::Keymap {
+            path: path.to_path_buf(),
+            source: Box::new(source),
+        })?;
+        Ok(())
+    }
+}
+
+/// Expand a leading `~/` to the home directory
+pub fn expand_tilde(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Location of a file lazypueue keeps between runs, in
+/// `$XDG_STATE_HOME/lazypueue` falling back to `~/.local/state/lazypueue`
+pub fn state_path(file: &str) -> Option<PathBuf> {
+    let state_dir = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state"))
+        })?;
+    Some(state_dir.join("lazypueue").join(file))
+}
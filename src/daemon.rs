@@ -0,0 +1,288 @@
+use anyhow::Result;
+use pueue_lib::message::EditableTask;
+use pueue_lib::state::State;
+use std::collections::{BTreeSet, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use crate::app::TreeSelection;
+use crate::compat::DaemonVersion;
+use crate::diagnostics::{self, Report};
+use crate::messages::MessageLevel;
+use crate::pueue_client::{Endpoint, PueueClient};
+use crate::undo::UndoEntry;
+
+/// Boxed future of a job, borrowing the client while it runs
+pub type JobFuture<'a> = Pin<Box<dyn Future<Output = Outcome> + Send + 'a>>;
+
+type JobFn = Box<dyn for<'a> FnOnce(&'a mut PueueClient) -> JobFuture<'a> + Send>;
+
+/// What a job does
+enum Run {
+    /// Talk to the daemon with the worker's connection
+    Client(JobFn),
+    /// Check every step of connecting, with a connection of its own
+    Diagnose,
+}
+
+/// A unit of daemon communication, run on the background task
+pub struct Job {
+    pub id: u64,
+    /// Index of the daemon the job is sent to
+    pub daemon: usize,
+    /// Shown next to the pending spinner; background polling has none
+    pub label: Option<String>,
+    run: Run,
+}
+
+impl Job {
+    pub fn new<F>(id: u64, daemon: usize, label: Option<String>, run: F) -> Self
+    where
+        F: for<'a> FnOnce(&'a mut PueueClient) -> JobFuture<'a> + Send + 'static,
+    {
+        Self {
+            id,
+            daemon,
+            label,
+            run: Run::Client(Box::new(run)),
+        }
+    }
+
+    /// A job diagnosing the connection to the daemon, even if there's none
+    pub fn diagnose(id: u64, daemon: usize, label: Option<String>) -> Self {
+        Self {
+            id,
+            daemon,
+            label,
+            run: Run::Diagnose,
+        }
+    }
+}
+
+impl std::fmt::Debug for Job {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Job")
+            .field("id", &self.id)
+            .field("daemon", &self.daemon)
+            .field("label", &self.label)
+            .finish()
+    }
+}
+
+/// Change to the app once a job is done
+#[derive(Debug)]
+pub enum Effect {
+    /// Fresh daemon state, or why it couldn't be fetched
+    State(Result<State>),
+    /// Open the log viewer, at the end when following
+    ShowLogs {
+        content: String,
+        follow: bool,
+    },
+    /// New output for the followed or docked log
+    FollowLogs {
+        task_id: usize,
+        content: String,
+    },
+    /// Start editing the task's command
+    Edit(EditableTask),
+    Select(TreeSelection),
+    /// Record how to revert a successful operation
+    Undoable(UndoEntry),
+    /// Put back what's left of an entry that couldn't be undone, at its
+    /// position in the undo stack counted from the oldest
+    RestoreUndoable {
+        position: usize,
+        entry: UndoEntry,
+    },
+    /// Show the result of diagnosing the connection
+    Diagnostics(Report),
+    /// A new connection was opened to a daemon of this version
+    Connected(String),
+}
+
+/// Result of a job: feedback messages and effects, applied in order
+#[derive(Debug, Default)]
+pub struct Outcome {
+    pub messages: Vec<(MessageLevel, String)>,
+    pub effects: Vec<Effect>,
+}
+
+impl Outcome {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn message(mut self, level: MessageLevel, text: impl Into<String>) -> Self {
+        self.messages.push((level, text.into()));
+        self
+    }
+
+    pub fn success(self, text: impl Into<String>) -> Self {
+        self.message(MessageLevel::Success, text)
+    }
+
+    pub fn warn(self, text: impl Into<String>) -> Self {
+        self.message(MessageLevel::Warn, text)
+    }
+
+    pub fn error(self, text: impl Into<String>) -> Self {
+        self.message(MessageLevel::Error, text)
+    }
+
+    pub fn effect(mut self, effect: Effect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+
+    pub fn undoable(self, entry: UndoEntry) -> Self {
+        self.effect(Effect::Undoable(entry))
+    }
+
+    /// Fetch the daemon state, so the result of an action shows right away
+    pub async fn refresh(self, client: &mut PueueClient) -> Self {
+        let state = client.get_state().await;
+        self.effect(Effect::State(state))
+    }
+}
+
+/// What a job was sent for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    /// The periodic refresh of a daemon's state
+    Poll,
+    /// Anything else: user actions and views loading their content
+    Action,
+}
+
+/// A job that was sent and whose outcome hasn't arrived yet
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingJob {
+    pub daemon: usize,
+    pub kind: JobKind,
+    pub label: Option<String>,
+}
+
+/// Whether a daemon could be reached at the last refresh
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Connection {
+    #[default]
+    Connecting,
+    Connected,
+    Failed(String),
+}
+
+/// What the task list showed of a daemon, kept while another one is shown
+#[derive(Debug, Clone, Default)]
+pub struct DaemonView {
+    pub state: Option<State>,
+    pub selection: Option<TreeSelection>,
+    pub collapsed_groups: HashSet<String>,
+    pub marked_tasks: BTreeSet<usize>,
+}
+
+/// A configured daemon, by its name in the config
+#[derive(Debug, Clone, Default)]
+pub struct DaemonEntry {
+    pub name: String,
+    pub connection: Connection,
+    /// Reported in the handshake of the last connection
+    pub version: Option<DaemonVersion>,
+    /// Saved view while the daemon isn't shown; empty for the shown one
+    pub view: DaemonView,
+}
+
+impl DaemonEntry {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Channels to the background tasks that own the daemon connections, one
+/// per daemon
+pub struct Daemons {
+    jobs: Vec<UnboundedSender<Job>>,
+    pub done: UnboundedReceiver<(u64, Outcome)>,
+}
+
+impl Daemons {
+    /// Start a background task per daemon, given by name and endpoint. Each
+    /// runs its jobs one at a time, in the order they were sent; the daemon
+    /// handles one request per connection at a time.
+    pub fn spawn(daemons: impl IntoIterator<Item = (String, Endpoint)>) -> Self {
+        let (done_tx, done) = mpsc::unbounded_channel();
+        let jobs = daemons
+            .into_iter()
+            .map(|(name, endpoint)| spawn_worker(name, endpoint, done_tx.clone()))
+            .collect();
+        Self { jobs, done }
+    }
+
+    pub fn send(&self, job: Job) {
+        // The tasks only stop when the UI has dropped `done`
+        if let Some(jobs) = self.jobs.get(job.daemon) {
+            let _ = jobs.send(job);
+        }
+    }
+}
+
+/// Run the jobs of one daemon. The connection is opened on the first job
+/// and again after a failed state request, so a restarted daemon or a
+/// dropped ssh tunnel is picked up by the next poll.
+fn spawn_worker(
+    name: String,
+    endpoint: Endpoint,
+    done: UnboundedSender<(u64, Outcome)>,
+) -> UnboundedSender<Job> {
+    let (jobs, mut job_rx) = mpsc::unbounded_channel::<Job>();
+    tokio::spawn(async move {
+        let mut client: Option<PueueClient> = None;
+        while let Some(job) = job_rx.recv().await {
+            let id = job.id;
+            let run = match job.run {
+                Run::Client(run) => run,
+                Run::Diagnose => {
+                    let report = diagnostics::diagnose(&name, &endpoint).await;
+                    let outcome = Outcome::new().effect(Effect::Diagnostics(report));
+                    if done.send((id, outcome)).is_err() {
+                        break;
+                    }
+                    continue;
+                }
+            };
+            let outcome = match &mut client {
+                Some(client) => run(client).await,
+                None => match PueueClient::open(&endpoint).await {
+                    Ok(connected) => {
+                        let version = connected.daemon_version().to_string();
+                        let mut outcome = run(client.insert(connected)).await;
+                        outcome.effects.insert(0, Effect::Connected(version));
+                        outcome
+                    }
+                    Err(e) => {
+                        let mut outcome = Outcome::new();
+                        if let Some(label) = &job.label {
+                            outcome = outcome.error(format!("{} failed: {:#}", label, e));
+                        }
+                        outcome.effect(Effect::State(Err(e)))
+                    }
+                },
+            };
+            let failed = outcome
+                .effects
+                .iter()
+                .any(|effect| matches!(effect, Effect::State(Err(_))));
+            if failed {
+                client = None;
+            }
+            if done.send((id, outcome)).is_err() {
+                break;
+            }
+        }
+    });
+    jobs
+}
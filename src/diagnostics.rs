@@ -0,0 +1,404 @@
+use pueue_lib::settings::{configuration_directories, Settings, Shared};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::compat::DaemonVersion;
+use crate::pueue_client::{Endpoint, PueueClient};
+use crate::ssh::Tunnel;
+
+/// How long the handshake with the daemon may take
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Result of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    /// Not a problem by itself, e.g. the connection mode
+    Info,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            CheckStatus::Ok => "✓",
+            CheckStatus::Info => "·",
+            CheckStatus::Warn => "!",
+            CheckStatus::Fail => "✗",
+        }
+    }
+}
+
+/// One step of connecting to a daemon, e.g. finding its socket
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub status: CheckStatus,
+    pub name: &'static str,
+    pub detail: String,
+    /// What to do about a warning or failure
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn new(status: CheckStatus, name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            name,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// All checks of one daemon, in the order they were made. Checking stops at
+/// the first failure that makes the later checks pointless.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub daemon: String,
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    pub fn is_ok(&self) -> bool {
+        self.checks.iter().all(|c| c.status != CheckStatus::Fail)
+    }
+}
+
+/// Check every step of connecting to a daemon
+pub async fn diagnose(daemon: &str, endpoint: &Endpoint) -> Report {
+    let checks = match endpoint {
+        Endpoint::Config(pueue_config) => diagnose_config(pueue_config.as_deref()).await,
+        Endpoint::Ssh { target, command } => {
+            let mut checks = vec![Check::new(
+                CheckStatus::Info,
+                "Mode",
+                format!(
+                    "Unix socket {} on {}, forwarded with `{}`",
+                    target.socket.as_deref().unwrap_or("(pueue's default)"),
+                    target.destination,
+                    command.join(" ")
+                ),
+            )];
+            match Tunnel::open(target, command).await {
+                Ok(tunnel) => {
+                    checks.push(Check::new(
+                        CheckStatus::Ok,
+                        "Tunnel",
+                        format!("Forwarded to {}", tunnel.socket.display()),
+                    ));
+                    checks.push(Check::new(
+                        CheckStatus::Ok,
+                        "Shared secret",
+                        format!(
+                            "Read from {} ({} bytes)",
+                            target.destination,
+                            tunnel.secret.len()
+                        ),
+                    ));
+                    let destination = target.destination.clone();
+                    checks
+                        .extend(handshake(PueueClient::through(tunnel, &destination), false).await);
+                }
+                Err(e) => checks.push(
+                    Check::new(CheckStatus::Fail, "Tunnel", format!("{:#}", e)).hint(format!(
+                        "Check that `ssh {}` logs in without a password prompt (keys or an \
+                         agent), and that pueued runs there as that user",
+                        target.destination
+                    )),
+                ),
+            }
+            checks
+        }
+    };
+    Report {
+        daemon: daemon.to_string(),
+        checks,
+    }
+}
+
+async fn diagnose_config(pueue_config: Option<&Path>) -> Vec<Check> {
+    let mut checks = vec![config_file_check(pueue_config)];
+    if checks[0].status == CheckStatus::Fail {
+        return checks;
+    }
+    let shared = match Settings::read(&pueue_config.map(Path::to_path_buf)) {
+        Ok((settings, _)) => settings.shared,
+        Err(e) => {
+            checks.push(
+                Check::new(CheckStatus::Fail, "Settings", e.to_string())
+                    .hint("Fix the pueue config file, see `pueue --help` for its format"),
+            );
+            return checks;
+        }
+    };
+    checks.extend(settings_checks(&shared));
+    if checks.iter().any(|c| c.status == CheckStatus::Fail) {
+        return checks;
+    }
+    checks.extend(
+        handshake(
+            PueueClient::connect(pueue_config),
+            unix_socket(&shared).is_none(),
+        )
+        .await,
+    );
+    checks
+}
+
+/// Which pueue config file is read: the given one, `PUEUE_CONFIG_PATH`, or
+/// the first `pueue.yml` in pueue's config directories
+pub fn config_file_check(pueue_config: Option<&Path>) -> Check {
+    let name = "Config file";
+    let explicit = pueue_config
+        .map(Path::to_path_buf)
+        .map(|p| (p, "pueue_config"))
+        .or_else(|| {
+            std::env::var_os("PUEUE_CONFIG_PATH").map(|p| (PathBuf::from(p), "PUEUE_CONFIG_PATH"))
+        });
+    if let Some((path, source)) = explicit {
+        return if path.is_file() {
+            Check::new(
+                CheckStatus::Ok,
+                name,
+                format!("{} (from {})", path.display(), source),
+            )
+        } else {
+            Check::new(
+                CheckStatus::Fail,
+                name,
+                format!("{} doesn't exist", path.display()),
+            )
+            .hint(format!("Point {} at the pueue.yml the daemon uses", source))
+        };
+    }
+    match configuration_directories()
+        .into_iter()
+        .map(|dir| dir.join("pueue.yml"))
+        .find(|path| path.is_file())
+    {
+        Some(path) => Check::new(CheckStatus::Ok, name, path.display().to_string()),
+        None => Check::new(
+            CheckStatus::Info,
+            name,
+            "None found, using pueue's defaults",
+        ),
+    }
+}
+
+/// Connection mode and the files it needs: the socket, or the TLS
+/// certificate, and the shared secret
+pub fn settings_checks(shared: &Shared) -> Vec<Check> {
+    let mut checks = Vec::new();
+    match unix_socket(shared) {
+        #[cfg(unix)]
+        Some(path) => {
+            checks.push(Check::new(CheckStatus::Info, "Mode", "Unix socket"));
+            checks.push(socket_check(&path));
+        }
+        _ => {
+            checks.push(Check::new(
+                CheckStatus::Info,
+                "Mode",
+                format!("TCP with TLS, {}:{}", shared.host, shared.port),
+            ));
+            let cert = shared.daemon_cert();
+            checks.push(if cert.is_file() {
+                Check::new(
+                    CheckStatus::Ok,
+                    "TLS certificate",
+                    cert.display().to_string(),
+                )
+            } else {
+                Check::new(
+                    CheckStatus::Fail,
+                    "TLS certificate",
+                    format!("{} doesn't exist", cert.display()),
+                )
+                .hint(
+                    "Copy certs/daemon.cert from the daemon's pueue directory, or set daemon_cert",
+                )
+            });
+        }
+    }
+    checks.push(secret_check(&shared.shared_secret_path()));
+    checks
+}
+
+/// The daemon's socket, if pueue connects through one
+#[cfg(unix)]
+fn unix_socket(shared: &Shared) -> Option<PathBuf> {
+    shared.use_unix_socket.then(|| shared.unix_socket_path())
+}
+
+/// pueue only connects over TCP here
+#[cfg(not(unix))]
+fn unix_socket(_shared: &Shared) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(unix)]
+fn socket_check(path: &Path) -> Check {
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+    let name = "Socket";
+    match std::fs::metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => Check::new(
+            CheckStatus::Ok,
+            name,
+            format!(
+                "{} ({}, owned by uid {})",
+                path.display(),
+                permissions(metadata.mode()),
+                metadata.uid()
+            ),
+        ),
+        Ok(_) => Check::new(
+            CheckStatus::Fail,
+            name,
+            format!("{} exists but isn't a socket", path.display()),
+        )
+        .hint("Remove the file and restart the daemon with `pueued -d`"),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Check::new(
+            CheckStatus::Fail,
+            name,
+            format!("{} doesn't exist", path.display()),
+        )
+        .hint(
+            "Start the daemon with `pueued -d`. If it runs with another pueue.yml, e.g. a \
+             project's .pueue/pueue.yml, set pueue_config of this daemon to it",
+        ),
+        Err(e) => Check::new(
+            CheckStatus::Fail,
+            name,
+            format!("{}: {}", path.display(), e),
+        )
+        .hint("Check the permissions of the socket's directory"),
+    }
+}
+
+fn secret_check(path: &Path) -> Check {
+    let name = "Shared secret";
+    match std::fs::metadata(path) {
+        Ok(metadata) if metadata.len() > 0 => Check::new(
+            CheckStatus::Ok,
+            name,
+            format!("{} ({} bytes)", path.display(), metadata.len()),
+        ),
+        Ok(_) => Check::new(
+            CheckStatus::Fail,
+            name,
+            format!("{} is empty", path.display()),
+        )
+        .hint("Restart the daemon to create a new secret"),
+        Err(e) => Check::new(
+            CheckStatus::Fail,
+            name,
+            format!("{}: {}", path.display(), e),
+        )
+        .hint(
+            "pueued creates it on its first start. For a daemon on another machine, copy \
+             it from the daemon's pueue directory, or set shared_secret_path",
+        ),
+    }
+}
+
+/// `ls -l` style permissions, e.g. "srwx------"
+#[cfg(unix)]
+fn permissions(mode: u32) -> String {
+    let mut text = String::from("s");
+    for shift in [6, 3, 0] {
+        let bits = (mode >> shift) & 0o7;
+        text.push(if bits & 4 != 0 { 'r' } else { '-' });
+        text.push(if bits & 2 != 0 { 'w' } else { '-' });
+        text.push(if bits & 1 != 0 { 'x' } else { '-' });
+    }
+    text
+}
+
+/// Connect, report the daemon's version from the handshake and fetch the
+/// state
+async fn handshake(
+    connect: impl std::future::Future<Output = anyhow::Result<PueueClient>>,
+    tcp: bool,
+) -> Vec<Check> {
+    let mut client = match tokio::time::timeout(CONNECT_TIMEOUT, connect).await {
+        Ok(Ok(client)) => client,
+        Ok(Err(e)) => {
+            let error = format!("{:#}", e);
+            let hint = if error.contains("Permission denied") {
+                "The socket belongs to another user; run lazypueue as the daemon's user"
+            } else if error.contains("handshake") {
+                "The daemon rejected the shared secret; make sure it's the daemon's own"
+            } else if tcp {
+                "Check that pueued listens on this host and port and that no firewall blocks it"
+            } else {
+                "Check that pueued is running: `pueued -d`"
+            };
+            return vec![Check::new(CheckStatus::Fail, "Handshake", error).hint(hint)];
+        }
+        Err(_) => {
+            return vec![Check::new(
+                CheckStatus::Fail,
+                "Handshake",
+                format!("No answer within {} seconds", CONNECT_TIMEOUT.as_secs()),
+            )
+            .hint("The daemon may be overloaded, or something else listens there")]
+        }
+    };
+
+    let version = DaemonVersion::new(client.daemon_version());
+    let mut checks = vec![match version.warning() {
+        None => Check::new(
+            CheckStatus::Ok,
+            "Handshake",
+            format!("Daemon version {}", version),
+        ),
+        Some(warning) => Check::new(
+            CheckStatus::Warn,
+            "Handshake",
+            format!("Daemon version {}", version),
+        )
+        .hint(warning),
+    }];
+    checks.push(match client.get_state().await {
+        Ok(state) => Check::new(
+            CheckStatus::Ok,
+            "Status",
+            format!(
+                "{} tasks in {} groups",
+                state.tasks.len(),
+                state.groups.len()
+            ),
+        ),
+        Err(e) => Check::new(CheckStatus::Fail, "Status", format!("{:#}", e)).hint(
+            "The daemon answered but its state couldn't be read; lazypueue and the daemon \
+             may be incompatible versions",
+        ),
+    });
+    checks
+}
+
+/// Plain text report for `lazypueue doctor`
+pub fn to_text(reports: &[Report]) -> String {
+    let mut text = String::new();
+    for report in reports {
+        text.push_str(&format!("Daemon '{}'\n", report.daemon));
+        for check in &report.checks {
+            text.push_str(&format!(
+                "  {} {:<15} {}\n",
+                check.status.symbol(),
+                check.name,
+                check.detail
+            ));
+            if let Some(hint) = &check.hint {
+                text.push_str(&format!("    {:<15} → {}\n", "", hint));
+            }
+        }
+    }
+    text
+}
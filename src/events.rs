@@ -1,122 +1,133 @@
-use crate::app::Action;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-
-/// Handle key events when in text input mode
-pub fn handle_input_mode_key_event(key: KeyEvent) -> Option<Action> {
-    match key.code {
-        // Submit
-        KeyCode::Enter => Some(Action::SubmitInput),
-        // Cancel
-        KeyCode::Esc => Some(Action::CancelInput),
-        // Text editing
-        KeyCode::Backspace => Some(Action::InputBackspace),
-        KeyCode::Delete => Some(Action::InputDelete),
-        KeyCode::Left => Some(Action::InputLeft),
-        KeyCode::Right => Some(Action::InputRight),
-        KeyCode::Home => Some(Action::InputHome),
-        KeyCode::End => Some(Action::InputEnd),
-        // Ctrl shortcuts
-        KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            Some(Action::InputHome)
-        }
-        KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            Some(Action::InputEnd)
+use crate::app::{Action, App};
+use crate::keymap::{KeyResult, Mode};
+use crate::layout::Pane;
+use crate::ui::HitTarget;
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+/// Two clicks on the same row within this time are a double-click
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+/// Read terminal events on a dedicated thread, since crossterm's reader
+/// blocks. The channel closes if reading fails.
+pub fn spawn_reader() -> UnboundedReceiver<Event> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            if tx.send(event).is_err() {
+                break;
+            }
         }
-        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            Some(Action::CancelInput)
-        }
-        // Regular characters
-        KeyCode::Char(c) => Some(Action::InputChar(c)),
-        _ => None,
-    }
+    });
+    rx
 }
 
-/// Handle key events when the log modal is open
-pub fn handle_log_modal_key_event(key: KeyEvent) -> Option<Action> {
-    match key.code {
-        // Close modal
-        KeyCode::Enter | KeyCode::Esc | KeyCode::Char('q') => Some(Action::CloseLogs),
-
-        // Scroll
-        KeyCode::Char('j') | KeyCode::Down => Some(Action::ScrollLogDown),
-        KeyCode::Char('k') | KeyCode::Up => Some(Action::ScrollLogUp),
-        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            Some(Action::ScrollLogPageDown)
-        }
-        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            Some(Action::ScrollLogPageUp)
-        }
-        KeyCode::PageDown => Some(Action::ScrollLogPageDown),
-        KeyCode::PageUp => Some(Action::ScrollLogPageUp),
-        KeyCode::Char('g') => Some(Action::ScrollLogUp), // Go to top (will saturate at 0)
-        KeyCode::Char('G') => Some(Action::ScrollLogDown), // Go to bottom (large value)
-
-        // Toggle follow mode
-        KeyCode::Char('f') => Some(Action::FollowLogs),
-
-        _ => None,
+/// Translate a key press into an action using the app's keymap and the mode
+/// of the topmost dialog or pane
+pub fn handle_key_event(app: &mut App, key: KeyEvent) -> Option<Action> {
+    let mode = app.mode();
+    match app.keymap.handle_key(mode, key) {
+        KeyResult::Action(action) => Some(action),
+        KeyResult::Pending => None,
+        KeyResult::Unbound => unbound_key_action(mode, key),
     }
 }
 
-/// Handle key events when a confirmation dialog is shown
-pub fn handle_confirm_mode_key_event(key: KeyEvent) -> Option<Action> {
-    match key.code {
-        // Confirm with y or Enter
-        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => Some(Action::ConfirmAction),
-        // Cancel with n, Escape, or any other key
-        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => Some(Action::CancelConfirm),
+/// Fallback for keys without a binding
+fn unbound_key_action(mode: Mode, key: KeyEvent) -> Option<Action> {
+    match mode {
+        // Regular characters are typed into the input
+        Mode::Input | Mode::Palette => match key.code {
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Some(Action::InputChar(c))
+            }
+            _ => None,
+        },
         // Any other key cancels
-        _ => Some(Action::CancelConfirm),
+        Mode::Confirm => Some(Action::CancelConfirm),
+        _ => None,
     }
 }
 
-/// Handle key events in the main view
-pub fn handle_key_event(key: KeyEvent) -> Option<Action> {
-    // Check for Ctrl+C first (quit)
-    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
-        return Some(Action::Quit);
+/// Translate a mouse event into an action using the regions recorded during
+/// the last draw. Only the topmost dialog or pane reacts to the mouse.
+pub fn handle_mouse_event(app: &mut App, mouse: MouseEvent) -> Option<Action> {
+    let mode = app.mode();
+    let target = app
+        .hit_regions
+        .borrow()
+        .hits(mouse.column, mouse.row)
+        .find(|target| accepts(mode, target))
+        .cloned()?;
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => click_action(app, target),
+        MouseEventKind::ScrollDown => scroll_action(&target, true),
+        MouseEventKind::ScrollUp => scroll_action(&target, false),
+        _ => None,
     }
+}
 
-    match key.code {
-        // Navigation
-        KeyCode::Char('j') | KeyCode::Down => Some(Action::NavigateDown),
-        KeyCode::Char('k') | KeyCode::Up => Some(Action::NavigateUp),
-        KeyCode::Char('g') => Some(Action::NavigateTop),
-        KeyCode::Char('G') => Some(Action::NavigateBottom),
-
-        // Task management
-        KeyCode::Char('K') => Some(Action::KillTask),
-        KeyCode::Char('p') => Some(Action::TogglePause),
-        KeyCode::Char(' ') => Some(Action::ToggleTaskPause),
-        KeyCode::Char('r') => Some(Action::Refresh),
-        KeyCode::Char('R') => Some(Action::RestartTask),
-        KeyCode::Char('c') => Some(Action::CleanFinished),
-        KeyCode::Char('a') => Some(Action::StartAddTask),
-        KeyCode::Char('e') => Some(Action::StartEditTask),
-        KeyCode::Char('d') | KeyCode::Char('x') => Some(Action::RemoveTask),
-
-        // Stash/Enqueue
-        KeyCode::Char('s') => Some(Action::StashTask),
-        KeyCode::Char('S') => Some(Action::EnqueueTask),
-
-        // Switch task order
-        KeyCode::Char('<') => Some(Action::SwitchUp),
-        KeyCode::Char('>') => Some(Action::SwitchDown),
-
-        // Parallel limit
-        KeyCode::Char('+') | KeyCode::Char('=') => Some(Action::IncreaseParallel),
-        KeyCode::Char('-') | KeyCode::Char('_') => Some(Action::DecreaseParallel),
-
-        // Tree navigation: h collapses / goes to parent, l expands / views logs
-        KeyCode::Char('h') | KeyCode::Left => Some(Action::CollapseGroup),
-        KeyCode::Enter | KeyCode::Char('l') | KeyCode::Right => Some(Action::ExpandGroup),
-
-        // Viewing
-        KeyCode::Char('f') => Some(Action::FollowLogs),
-
-        // Quit
-        KeyCode::Char('q') => Some(Action::Quit),
+/// Whether a target belongs to the dialog or pane of the given mode
+fn accepts(mode: Mode, target: &HitTarget) -> bool {
+    match mode {
+        // The panes share the screen, and any of them can be clicked
+        Mode::Normal | Mode::Details | Mode::LogPane => matches!(
+            target,
+            HitTarget::TaskList
+                | HitTarget::TreeItem(_)
+                | HitTarget::DetailsPane
+                | HitTarget::LogPane
+        ),
+        Mode::Log => *target == HitTarget::LogModal,
+        Mode::Activity => *target == HitTarget::ActivityPane,
+        Mode::Messages => *target == HitTarget::MessagesPane,
+        Mode::Diagnostics => *target == HitTarget::DiagnosticsPane,
+        Mode::Help => *target == HitTarget::HelpOverlay,
+        Mode::Palette => *target == HitTarget::Palette,
+        Mode::Confirm => matches!(target, HitTarget::ConfirmYes | HitTarget::ConfirmNo),
+        Mode::Input => false,
+    }
+}
 
+fn click_action(app: &mut App, target: HitTarget) -> Option<Action> {
+    match target {
+        HitTarget::TreeItem(item) => {
+            let now = Instant::now();
+            let double_click = app.last_click.as_ref().is_some_and(|(time, last)| {
+                *last == item && now.duration_since(*time) <= DOUBLE_CLICK_TIME
+            });
+            if double_click {
+                app.last_click = None;
+                Some(Action::OpenItem(item))
+            } else {
+                app.last_click = Some((now, item.clone()));
+                Some(Action::SelectItem(item))
+            }
+        }
+        HitTarget::DetailsPane => Some(Action::Focus(Pane::Details)),
+        HitTarget::LogPane => Some(Action::Focus(Pane::Log)),
+        HitTarget::ConfirmYes => Some(Action::ConfirmAction),
+        HitTarget::ConfirmNo => Some(Action::CancelConfirm),
         _ => None,
     }
 }
+
+fn scroll_action(target: &HitTarget, down: bool) -> Option<Action> {
+    let (down_action, up_action) = match target {
+        HitTarget::TaskList | HitTarget::TreeItem(_) => (Action::NavigateDown, Action::NavigateUp),
+        HitTarget::DetailsPane => (Action::ScrollDetailsDown, Action::ScrollDetailsUp),
+        HitTarget::LogPane => (Action::ScrollLogPaneDown, Action::ScrollLogPaneUp),
+        HitTarget::LogModal => (Action::ScrollLogDown, Action::ScrollLogUp),
+        HitTarget::ActivityPane => (Action::ScrollActivityDown, Action::ScrollActivityUp),
+        HitTarget::MessagesPane => (Action::ScrollMessagesDown, Action::ScrollMessagesUp),
+        HitTarget::DiagnosticsPane => (Action::ScrollDiagnosticsDown, Action::ScrollDiagnosticsUp),
+        HitTarget::HelpOverlay => (Action::ScrollHelpDown, Action::ScrollHelpUp),
+        HitTarget::Palette => (Action::PaletteNext, Action::PalettePrev),
+        HitTarget::ConfirmYes | HitTarget::ConfirmNo => return None,
+    };
+    Some(if down { down_action } else { up_action })
+}
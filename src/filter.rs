@@ -0,0 +1,205 @@
+use chrono::{DateTime, Local};
+use clap::ValueEnum;
+use pueue_lib::state::State;
+use pueue_lib::task::{Task, TaskResult, TaskStatus};
+use std::fmt;
+
+/// Status categories the task list can be filtered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatusFilter {
+    Queued,
+    Stashed,
+    Running,
+    Paused,
+    Locked,
+    /// Finished with any result
+    Done,
+    Success,
+    /// Finished with any result but success, including killed tasks
+    Failed,
+}
+
+impl StatusFilter {
+    pub fn name(self) -> &'static str {
+        match self {
+            StatusFilter::Queued => "queued",
+            StatusFilter::Stashed => "stashed",
+            StatusFilter::Running => "running",
+            StatusFilter::Paused => "paused",
+            StatusFilter::Locked => "locked",
+            StatusFilter::Done => "done",
+            StatusFilter::Success => "success",
+            StatusFilter::Failed => "failed",
+        }
+    }
+
+    pub fn matches(self, status: &TaskStatus) -> bool {
+        match (self, status) {
+            (StatusFilter::Queued, TaskStatus::Queued { .. })
+            | (StatusFilter::Stashed, TaskStatus::Stashed { .. })
+            | (StatusFilter::Running, TaskStatus::Running { .. })
+            | (StatusFilter::Paused, TaskStatus::Paused { .. })
+            | (StatusFilter::Locked, TaskStatus::Locked { .. })
+            | (StatusFilter::Done, TaskStatus::Done { .. }) => true,
+            (StatusFilter::Success, TaskStatus::Done { result, .. }) => {
+                *result == TaskResult::Success
+            }
+            (StatusFilter::Failed, TaskStatus::Done { result, .. }) => {
+                *result != TaskResult::Success
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Which tasks to show, written as a query like `status:failed group:ci
+/// deploy`. Values of the same key are alternatives; different keys and
+/// plain words must all match.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskFilter {
+    pub statuses: Vec<StatusFilter>,
+    pub groups: Vec<String>,
+    pub labels: Vec<String>,
+    /// Case-insensitive words to find in the command or label
+    pub words: Vec<String>,
+}
+
+impl TaskFilter {
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let mut filter = Self::default();
+        for term in query.split_whitespace() {
+            match term.split_once(':') {
+                Some(("status", value)) => {
+                    let status = StatusFilter::from_str(value, true).map_err(|_| {
+                        let names: Vec<_> = StatusFilter::value_variants()
+                            .iter()
+                            .map(|s| s.name())
+                            .collect();
+                        format!(
+                            "unknown status '{}', expected one of: {}",
+                            value,
+                            names.join(", ")
+                        )
+                    })?;
+                    filter.statuses.push(status);
+                }
+                Some(("group", value)) => filter.groups.push(value.to_string()),
+                Some(("label", value)) => filter.labels.push(value.to_string()),
+                _ => filter.words.push(term.to_lowercase()),
+            }
+        }
+        Ok(filter)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        let label = task.label.as_deref().unwrap_or_default();
+        (self.statuses.is_empty() || self.statuses.iter().any(|s| s.matches(&task.status)))
+            && (self.groups.is_empty() || self.groups.contains(&task.group))
+            && (self.labels.is_empty() || self.labels.iter().any(|l| l == label))
+            && self.words.iter().all(|word| {
+                task.command.to_lowercase().contains(word) || label.to_lowercase().contains(word)
+            })
+    }
+}
+
+impl fmt::Display for TaskFilter {
+    /// The filter as a query that parses back to it
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms = self
+            .statuses
+            .iter()
+            .map(|s| format!("status:{}", s.name()))
+            .chain(self.groups.iter().map(|g| format!("group:{}", g)))
+            .chain(self.labels.iter().map(|l| format!("label:{}", l)))
+            .chain(self.words.iter().cloned());
+        write!(f, "{}", terms.collect::<Vec<_>>().join(" "))
+    }
+}
+
+/// Order of tasks within a group
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SortMode {
+    /// Oldest task first
+    #[default]
+    Id,
+    /// Running, paused, queued, locked, stashed, then finished tasks
+    Status,
+    /// Most recently started first
+    Start,
+    /// Longest running first
+    Runtime,
+    /// Alphabetically by command
+    Command,
+}
+
+impl SortMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            SortMode::Id => "id",
+            SortMode::Status => "status",
+            SortMode::Start => "start",
+            SortMode::Runtime => "runtime",
+            SortMode::Command => "command",
+        }
+    }
+
+    /// The next mode, wrapping around
+    pub fn next(self) -> Self {
+        let modes = Self::value_variants();
+        let index = modes.iter().position(|m| *m == self).unwrap_or(0);
+        modes[(index + 1) % modes.len()]
+    }
+
+    /// Sort tasks by this mode, falling back to the id
+    pub fn sort(self, tasks: &mut [&Task]) {
+        let now = Local::now();
+        match self {
+            SortMode::Id => tasks.sort_by_key(|task| task.id),
+            SortMode::Status => tasks.sort_by_key(|task| (status_rank(&task.status), task.id)),
+            SortMode::Start => tasks.sort_by_key(|task| {
+                let start = task.start_and_end().0;
+                (start.is_none(), std::cmp::Reverse(start), task.id)
+            }),
+            SortMode::Runtime => {
+                tasks.sort_by_key(|task| (std::cmp::Reverse(runtime(task, now)), task.id))
+            }
+            SortMode::Command => {
+                tasks.sort_by(|a, b| a.command.cmp(&b.command).then(a.id.cmp(&b.id)))
+            }
+        }
+    }
+}
+
+fn status_rank(status: &TaskStatus) -> u8 {
+    match status {
+        TaskStatus::Running { .. } => 0,
+        TaskStatus::Paused { .. } => 1,
+        TaskStatus::Queued { .. } => 2,
+        TaskStatus::Locked { .. } => 3,
+        TaskStatus::Stashed { .. } => 4,
+        TaskStatus::Done { .. } => 5,
+    }
+}
+
+/// How long the task ran or has been running, in seconds
+fn runtime(task: &Task, now: DateTime<Local>) -> Option<i64> {
+    match task.start_and_end() {
+        (Some(start), end) => Some((end.unwrap_or(now) - start).num_seconds()),
+        _ => None,
+    }
+}
+
+/// Tasks of the state matching the filter, in sort order
+pub fn select_tasks<'a>(state: &'a State, filter: &TaskFilter, sort: SortMode) -> Vec<&'a Task> {
+    let mut tasks: Vec<&Task> = state
+        .tasks
+        .values()
+        .filter(|task| filter.matches(task))
+        .collect();
+    sort.sort(&mut tasks);
+    tasks
+}
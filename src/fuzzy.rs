@@ -0,0 +1,79 @@
+/// Result of a successful fuzzy match
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Char indices of the candidate that matched the query
+    pub positions: Vec<usize>,
+}
+
+/// Case-insensitive subsequence match of `query` against `candidate`.
+///
+/// Every query character (ignoring whitespace) must appear in order. Matches
+/// at word starts and runs of consecutive characters score higher; gaps and
+/// long candidates score lower. Returns `None` if the query doesn't match.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+
+    let chars: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let n = chars.len();
+
+    // best[j][i]: best score with query[..=j] matched and query[j] at chars[i]
+    let mut best: Vec<Vec<Option<i64>>> = vec![vec![None; n]; query.len()];
+    let mut from: Vec<Vec<usize>> = vec![vec![0; n]; query.len()];
+    for (j, q) in query.iter().enumerate() {
+        for i in 0..n {
+            if lower[i] != *q {
+                continue;
+            }
+            let mut score = 1;
+            if i == 0 || !chars[i - 1].is_alphanumeric() {
+                score += 8;
+            }
+            if j == 0 {
+                best[j][i] = Some(score - i.min(10) as i64);
+                continue;
+            }
+            let previous = (0..i)
+                .filter_map(|k| {
+                    let bonus = if k + 1 == i {
+                        5
+                    } else {
+                        -((i - k - 1).min(5) as i64)
+                    };
+                    best[j - 1][k].map(|s| (s + bonus, k))
+                })
+                .max_by_key(|(s, k)| (*s, std::cmp::Reverse(*k)));
+            if let Some((previous_score, k)) = previous {
+                best[j][i] = Some(score + previous_score);
+                from[j][i] = k;
+            }
+        }
+    }
+
+    let last = query.len() - 1;
+    let (mut score, end) = (0..n)
+        .filter_map(|i| best[last][i].map(|s| (s, i)))
+        .max_by_key(|(s, i)| (*s, std::cmp::Reverse(*i)))?;
+    let mut positions = vec![end; query.len()];
+    for j in (1..query.len()).rev() {
+        positions[j - 1] = from[j][positions[j]];
+    }
+
+    // Prefer shorter candidates among otherwise equal matches
+    score -= (chars.len() / 10) as i64;
+    Some(FuzzyMatch { score, positions })
+}
@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+/// Number of commands kept in the history file
+pub const HISTORY_CAPACITY: usize = 1000;
+
+/// Previously added commands, oldest first and without duplicates
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct History {
+    entries: Vec<String>,
+    /// Commands offered before the entries but never saved, e.g. the
+    /// commands of the daemon's tasks
+    seeded: Vec<String>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Default history file location, `$XDG_STATE_HOME/lazypueue/history`
+    /// falling back to `~/.local/state/lazypueue/history`
+    pub fn default_path() -> Option<PathBuf> {
+        config::state_path("history")
+    }
+
+    /// Read the history file, one JSON string per line so commands may span
+    /// lines. A missing file is an empty history.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let mut history = Self::new();
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let command: String = serde_json::from_str(line)
+                .with_context(|| format!("Invalid history entry in {}", path.display()))?;
+            history.push(command);
+        }
+        Ok(history)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let mut content = String::new();
+        for command in &self.entries {
+            content.push_str(&serde_json::to_string(command)?);
+            content.push('\n');
+        }
+        std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Add a command as the newest entry, dropping an older duplicate
+    pub fn push(&mut self, command: impl Into<String>) {
+        let command = command.into();
+        if command.trim().is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| *entry != command);
+        self.seeded.retain(|entry| *entry != command);
+        self.entries.push(command);
+        if self.entries.len() > HISTORY_CAPACITY {
+            let excess = self.entries.len() - HISTORY_CAPACITY;
+            self.entries.drain(..excess);
+        }
+        let excess = self.len().saturating_sub(HISTORY_CAPACITY);
+        self.seeded.drain(..excess);
+    }
+
+    /// Offer commands that aren't in the history yet as the oldest entries,
+    /// e.g. the commands of the daemon's tasks. They replace the previously
+    /// seeded commands and aren't saved.
+    pub fn seed<'a>(&mut self, commands: impl IntoIterator<Item = &'a str>) {
+        let mut seeded: Vec<String> = Vec::new();
+        for command in commands {
+            if !command.trim().is_empty()
+                && !self.entries.iter().any(|entry| entry == command)
+                && !seeded.iter().any(|entry| entry == command)
+            {
+                seeded.push(command.to_string());
+            }
+        }
+        let room = HISTORY_CAPACITY.saturating_sub(self.entries.len());
+        let skip = seeded.len().saturating_sub(room);
+        self.seeded = seeded.into_iter().skip(skip).collect();
+    }
+
+    /// The entry at `index`, counted from the newest
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.iter().nth(index)
+    }
+
+    /// Index of the newest entry at or after `from` (counted from the
+    /// newest) containing `query`
+    pub fn search(&self, query: &str, from: usize) -> Option<usize> {
+        (from..self.len()).find(|&index| self.get(index).is_some_and(|e| e.contains(query)))
+    }
+
+    /// All entries, newest first, followed by the seeded commands
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .rev()
+            .chain(self.seeded.iter().rev())
+            .map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len() + self.seeded.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A reverse search (Ctrl-R) through the history
+#[derive(Debug, Clone, Default)]
+pub struct HistorySearch {
+    pub query: String,
+    /// Index of the shown match, counted from the newest entry
+    pub found: Option<usize>,
+    /// Input before the search, restored when it's cancelled
+    pub original: String,
+}
@@ -0,0 +1,737 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use thiserror::Error;
+
+use crate::app::Action;
+
+/// Key handling mode; every mode has its own set of bindings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// The task tree
+    Normal,
+    /// The details pane
+    Details,
+    /// The docked log pane
+    #[serde(rename = "log_pane")]
+    LogPane,
+    /// The log viewer
+    Log,
+    /// The activity feed
+    Activity,
+    /// The message history
+    Messages,
+    /// The connection diagnostics
+    Diagnostics,
+    /// Text input dialogs
+    Input,
+    /// Confirmation dialogs
+    Confirm,
+    /// The help overlay
+    Help,
+    /// The command palette
+    Palette,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 11] = [
+        Mode::Normal,
+        Mode::Details,
+        Mode::LogPane,
+        Mode::Log,
+        Mode::Activity,
+        Mode::Messages,
+        Mode::Diagnostics,
+        Mode::Input,
+        Mode::Confirm,
+        Mode::Help,
+        Mode::Palette,
+    ];
+
+    /// Name as used in the `[keys.<mode>]` config tables
+    pub fn name(self) -> &'static str {
+        match self {
+            Mode::Normal => "normal",
+            Mode::Details => "details",
+            Mode::LogPane => "log_pane",
+            Mode::Log => "log",
+            Mode::Activity => "activity",
+            Mode::Messages => "messages",
+            Mode::Diagnostics => "diagnostics",
+            Mode::Input => "input",
+            Mode::Confirm => "confirm",
+            Mode::Help => "help",
+            Mode::Palette => "palette",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Mode::Normal => "Task list",
+            Mode::Details => "Details pane",
+            Mode::LogPane => "Log pane",
+            Mode::Log => "Log viewer",
+            Mode::Activity => "Activity feed",
+            Mode::Messages => "Message history",
+            Mode::Diagnostics => "Connection diagnostics",
+            Mode::Input => "Text input",
+            Mode::Confirm => "Confirmation dialog",
+            Mode::Help => "Help",
+            Mode::Palette => "Command palette",
+        }
+    }
+}
+
+/// Built-in bindings as `(mode, keys, action name)`, in display order.
+/// Keys use the same notation as the config file.
+const DEFAULT_BINDINGS: &[(Mode, &str, &str)] = &[
+    // Navigation
+    (Mode::Normal, "j", "navigate_down"),
+    (Mode::Normal, "down", "navigate_down"),
+    (Mode::Normal, "k", "navigate_up"),
+    (Mode::Normal, "up", "navigate_up"),
+    (Mode::Normal, "g g", "navigate_top"),
+    (Mode::Normal, "G", "navigate_bottom"),
+    (Mode::Normal, "ctrl-d", "half_page_down"),
+    (Mode::Normal, "ctrl-u", "half_page_up"),
+    (Mode::Normal, "z z", "center_selection"),
+    // Tree navigation: h collapses / goes to parent, l expands / views logs
+    (Mode::Normal, "h", "collapse_group"),
+    (Mode::Normal, "left", "collapse_group"),
+    (Mode::Normal, "l", "expand_group"),
+    (Mode::Normal, "right", "expand_group"),
+    (Mode::Normal, "enter", "expand_group"),
+    // Task management
+    (Mode::Normal, "a", "start_add_task"),
+    (Mode::Normal, "e", "start_edit_task"),
+    (Mode::Normal, "d d", "remove_task"),
+    (Mode::Normal, "x", "remove_task"),
+    (Mode::Normal, "space", "toggle_task_pause"),
+    (Mode::Normal, "p", "toggle_pause"),
+    (Mode::Normal, "K", "kill_task"),
+    (Mode::Normal, "R", "restart_task"),
+    (Mode::Normal, "c", "clean_finished"),
+    (Mode::Normal, "r", "refresh"),
+    // Stash/Enqueue
+    (Mode::Normal, "s", "stash_task"),
+    (Mode::Normal, "S", "enqueue_task"),
+    // Switch task order
+    (Mode::Normal, "m", "toggle_mark"),
+    (Mode::Normal, "M", "start_move_to_group"),
+    (Mode::Normal, "esc", "clear_marks"),
+    (Mode::Normal, "<", "switch_up"),
+    (Mode::Normal, ">", "switch_down"),
+    (Mode::Normal, "{", "move_to_queue_top"),
+    (Mode::Normal, "}", "move_to_queue_bottom"),
+    (Mode::Normal, "]", "raise_priority"),
+    (Mode::Normal, "[", "lower_priority"),
+    // Parallel limit
+    (Mode::Normal, "+", "increase_parallel"),
+    (Mode::Normal, "=", "increase_parallel"),
+    (Mode::Normal, "-", "decrease_parallel"),
+    (Mode::Normal, "_", "decrease_parallel"),
+    // Viewing
+    (Mode::Normal, "f", "follow_logs"),
+    (Mode::Normal, "A", "toggle_activity"),
+    (Mode::Normal, "H", "toggle_messages"),
+    (Mode::Normal, "i", "toggle_diagnostics"),
+    // Layout
+    (Mode::Normal, ")", "grow_task_list"),
+    (Mode::Normal, "(", "shrink_task_list"),
+    (Mode::Normal, "L", "cycle_layout"),
+    (Mode::Normal, "Z", "toggle_zoom"),
+    (Mode::Normal, "alt-s", "toggle_status_bar"),
+    (Mode::Normal, "alt-h", "toggle_help_bar"),
+    // Panes
+    (Mode::Normal, "O", "toggle_log_pane"),
+    (Mode::Normal, "tab", "focus_next"),
+    (Mode::Normal, "backtab", "focus_prev"),
+    (Mode::Normal, "T", "save_as_template"),
+    (Mode::Normal, "/", "start_task_filter"),
+    (Mode::Normal, "o", "cycle_sort_mode"),
+    // Daemons
+    (Mode::Normal, "g t", "next_daemon"),
+    (Mode::Normal, "g T", "prev_daemon"),
+    (Mode::Normal, "u", "undo"),
+    (Mode::Normal, "U", "show_undo_history"),
+    (Mode::Normal, "?", "toggle_help"),
+    (Mode::Normal, ":", "open_palette"),
+    (Mode::Normal, "ctrl-p", "open_palette"),
+    (Mode::Normal, "q", "quit"),
+    (Mode::Normal, "ctrl-c", "quit"),
+    // Details pane
+    (Mode::Details, "j", "scroll_details_down"),
+    (Mode::Details, "down", "scroll_details_down"),
+    (Mode::Details, "k", "scroll_details_up"),
+    (Mode::Details, "up", "scroll_details_up"),
+    (Mode::Details, "ctrl-d", "scroll_details_page_down"),
+    (Mode::Details, "pagedown", "scroll_details_page_down"),
+    (Mode::Details, "ctrl-u", "scroll_details_page_up"),
+    (Mode::Details, "pageup", "scroll_details_page_up"),
+    (Mode::Details, "g g", "scroll_details_top"),
+    (Mode::Details, "G", "scroll_details_bottom"),
+    (Mode::Details, "tab", "focus_next"),
+    (Mode::Details, "backtab", "focus_prev"),
+    (Mode::Details, "esc", "focus_task_list"),
+    (Mode::Details, "Z", "toggle_zoom"),
+    (Mode::Details, "O", "toggle_log_pane"),
+    (Mode::Details, "?", "toggle_help"),
+    (Mode::Details, ":", "open_palette"),
+    (Mode::Details, "ctrl-p", "open_palette"),
+    (Mode::Details, "q", "quit"),
+    (Mode::Details, "ctrl-c", "quit"),
+    // Docked log pane
+    (Mode::LogPane, "j", "scroll_log_pane_down"),
+    (Mode::LogPane, "down", "scroll_log_pane_down"),
+    (Mode::LogPane, "k", "scroll_log_pane_up"),
+    (Mode::LogPane, "up", "scroll_log_pane_up"),
+    (Mode::LogPane, "ctrl-d", "scroll_log_pane_page_down"),
+    (Mode::LogPane, "pagedown", "scroll_log_pane_page_down"),
+    (Mode::LogPane, "ctrl-u", "scroll_log_pane_page_up"),
+    (Mode::LogPane, "pageup", "scroll_log_pane_page_up"),
+    (Mode::LogPane, "g g", "scroll_log_pane_top"),
+    (Mode::LogPane, "G", "scroll_log_pane_bottom"),
+    (Mode::LogPane, "enter", "view_logs"),
+    (Mode::LogPane, "tab", "focus_next"),
+    (Mode::LogPane, "backtab", "focus_prev"),
+    (Mode::LogPane, "esc", "focus_task_list"),
+    (Mode::LogPane, "Z", "toggle_zoom"),
+    (Mode::LogPane, "O", "toggle_log_pane"),
+    (Mode::LogPane, "?", "toggle_help"),
+    (Mode::LogPane, ":", "open_palette"),
+    (Mode::LogPane, "ctrl-p", "open_palette"),
+    (Mode::LogPane, "q", "quit"),
+    (Mode::LogPane, "ctrl-c", "quit"),
+    // Log viewer
+    (Mode::Log, "j", "scroll_log_down"),
+    (Mode::Log, "down", "scroll_log_down"),
+    (Mode::Log, "k", "scroll_log_up"),
+    (Mode::Log, "up", "scroll_log_up"),
+    (Mode::Log, "ctrl-d", "scroll_log_page_down"),
+    (Mode::Log, "pagedown", "scroll_log_page_down"),
+    (Mode::Log, "ctrl-u", "scroll_log_page_up"),
+    (Mode::Log, "pageup", "scroll_log_page_up"),
+    (Mode::Log, "g g", "scroll_log_top"),
+    (Mode::Log, "G", "scroll_log_bottom"),
+    (Mode::Log, "f", "follow_logs"),
+    (Mode::Log, "q", "close_logs"),
+    (Mode::Log, "esc", "close_logs"),
+    (Mode::Log, "enter", "close_logs"),
+    // Activity feed
+    (Mode::Activity, "j", "scroll_activity_down"),
+    (Mode::Activity, "down", "scroll_activity_down"),
+    (Mode::Activity, "k", "scroll_activity_up"),
+    (Mode::Activity, "up", "scroll_activity_up"),
+    (Mode::Activity, "ctrl-d", "scroll_activity_page_down"),
+    (Mode::Activity, "pagedown", "scroll_activity_page_down"),
+    (Mode::Activity, "ctrl-u", "scroll_activity_page_up"),
+    (Mode::Activity, "pageup", "scroll_activity_page_up"),
+    (Mode::Activity, "t", "cycle_activity_filter"),
+    (Mode::Activity, "tab", "cycle_activity_filter"),
+    (Mode::Activity, "/", "start_activity_search"),
+    (Mode::Activity, "q", "close_activity"),
+    (Mode::Activity, "esc", "close_activity"),
+    (Mode::Activity, "A", "close_activity"),
+    // Message history
+    (Mode::Messages, "j", "scroll_messages_down"),
+    (Mode::Messages, "down", "scroll_messages_down"),
+    (Mode::Messages, "k", "scroll_messages_up"),
+    (Mode::Messages, "up", "scroll_messages_up"),
+    (Mode::Messages, "q", "close_messages"),
+    (Mode::Messages, "esc", "close_messages"),
+    (Mode::Messages, "H", "close_messages"),
+    // Connection diagnostics
+    (Mode::Diagnostics, "j", "scroll_diagnostics_down"),
+    (Mode::Diagnostics, "down", "scroll_diagnostics_down"),
+    (Mode::Diagnostics, "k", "scroll_diagnostics_up"),
+    (Mode::Diagnostics, "up", "scroll_diagnostics_up"),
+    (Mode::Diagnostics, "r", "run_diagnostics"),
+    (Mode::Diagnostics, "q", "close_diagnostics"),
+    (Mode::Diagnostics, "esc", "close_diagnostics"),
+    (Mode::Diagnostics, "i", "close_diagnostics"),
+    // Text input
+    (Mode::Input, "enter", "submit_input"),
+    (Mode::Input, "alt-enter", "input_newline"),
+    (Mode::Input, "esc", "cancel_input"),
+    (Mode::Input, "ctrl-c", "cancel_input"),
+    (Mode::Input, "backspace", "input_backspace"),
+    (Mode::Input, "delete", "input_delete"),
+    (Mode::Input, "left", "input_left"),
+    (Mode::Input, "right", "input_right"),
+    (Mode::Input, "home", "input_home"),
+    (Mode::Input, "ctrl-a", "input_home"),
+    (Mode::Input, "end", "input_end"),
+    (Mode::Input, "ctrl-e", "input_end"),
+    (Mode::Input, "alt-b", "input_word_left"),
+    (Mode::Input, "ctrl-left", "input_word_left"),
+    (Mode::Input, "alt-f", "input_word_right"),
+    (Mode::Input, "ctrl-right", "input_word_right"),
+    (Mode::Input, "ctrl-w", "input_delete_word"),
+    (Mode::Input, "up", "input_history_prev"),
+    (Mode::Input, "down", "input_history_next"),
+    (Mode::Input, "ctrl-r", "input_history_search"),
+    (Mode::Input, "tab", "input_complete"),
+    // Confirmation: any other key cancels
+    (Mode::Confirm, "y", "confirm_action"),
+    (Mode::Confirm, "Y", "confirm_action"),
+    (Mode::Confirm, "enter", "confirm_action"),
+    (Mode::Confirm, "n", "cancel_confirm"),
+    (Mode::Confirm, "N", "cancel_confirm"),
+    (Mode::Confirm, "esc", "cancel_confirm"),
+    // Help overlay
+    (Mode::Help, "j", "scroll_help_down"),
+    (Mode::Help, "down", "scroll_help_down"),
+    (Mode::Help, "k", "scroll_help_up"),
+    (Mode::Help, "up", "scroll_help_up"),
+    (Mode::Help, "ctrl-d", "scroll_help_page_down"),
+    (Mode::Help, "pagedown", "scroll_help_page_down"),
+    (Mode::Help, "ctrl-u", "scroll_help_page_up"),
+    (Mode::Help, "pageup", "scroll_help_page_up"),
+    (Mode::Help, "/", "start_help_search"),
+    (Mode::Help, "?", "close_help"),
+    (Mode::Help, "q", "close_help"),
+    (Mode::Help, "esc", "close_help"),
+    // Command palette: other characters are typed into the query
+    (Mode::Palette, "enter", "execute_palette"),
+    (Mode::Palette, "esc", "close_palette"),
+    (Mode::Palette, "ctrl-c", "close_palette"),
+    (Mode::Palette, "down", "palette_next"),
+    (Mode::Palette, "ctrl-n", "palette_next"),
+    (Mode::Palette, "up", "palette_prev"),
+    (Mode::Palette, "ctrl-p", "palette_prev"),
+    (Mode::Palette, "backspace", "input_backspace"),
+    (Mode::Palette, "delete", "input_delete"),
+    (Mode::Palette, "left", "input_left"),
+    (Mode::Palette, "right", "input_right"),
+    (Mode::Palette, "home", "input_home"),
+    (Mode::Palette, "end", "input_end"),
+];
+
+/// Errors while building a keymap from the config
+#[derive(Debug, Error, PartialEq)]
+pub enum KeymapError {
+    #[error("keys.{mode}.\"{keys}\": {message}")]
+    InvalidKey {
+        mode: &'static str,
+        keys: String,
+        message: String,
+    },
+    #[error("keys.{mode}.\"{keys}\": unknown action `{action}`")]
+    UnknownAction {
+        mode: &'static str,
+        keys: String,
+        action: String,
+    },
+    #[error(
+        "keys.{mode}: \"{prefix}\" ({prefix_action}) shadows \"{keys}\" ({action}); \
+         unbind one of them with \"none\""
+    )]
+    Conflict {
+        mode: &'static str,
+        prefix: String,
+        prefix_action: String,
+        keys: String,
+        action: String,
+    },
+}
+
+/// A single key press with modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is part of the character itself ('G' vs 'g')
+        match code {
+            KeyCode::Char(c) => Self {
+                code: if modifiers.contains(KeyModifiers::SHIFT) {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else {
+                    KeyCode::Char(c)
+                },
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            _ => Self { code, modifiers },
+        }
+    }
+
+    /// Parse a single chord like `j`, `G`, `ctrl-d`, `alt-enter` or `space`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            let (modifier, len) = if lower.starts_with("ctrl-") {
+                (KeyModifiers::CONTROL, 5)
+            } else if lower.starts_with("alt-") {
+                (KeyModifiers::ALT, 4)
+            } else if lower.starts_with("shift-") {
+                (KeyModifiers::SHIFT, 6)
+            } else {
+                break;
+            };
+            // A lone "-" after a modifier is the minus key, e.g. "ctrl--"
+            if rest.len() == len {
+                break;
+            }
+            modifiers |= modifier;
+            rest = &rest[len..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => {
+                        return Err(format!(
+                            "unknown key `{}` (separate the keys of a sequence with spaces, e.g. \"g g\")",
+                            rest
+                        ))
+                    }
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+
+    /// Plain printable character without modifiers
+    pub fn is_plain_char(&self) -> bool {
+        matches!(self.code, KeyCode::Char(_)) && self.modifiers.is_empty()
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(key: KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Esc => write!(f, "esc"),
+            KeyCode::Tab => write!(f, "tab"),
+            KeyCode::BackTab => write!(f, "backtab"),
+            KeyCode::Backspace => write!(f, "backspace"),
+            KeyCode::Delete => write!(f, "delete"),
+            KeyCode::Insert => write!(f, "insert"),
+            KeyCode::Left => write!(f, "left"),
+            KeyCode::Right => write!(f, "right"),
+            KeyCode::Up => write!(f, "up"),
+            KeyCode::Down => write!(f, "down"),
+            KeyCode::Home => write!(f, "home"),
+            KeyCode::End => write!(f, "end"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// A sequence of chords, e.g. `g g`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeySequence(pub Vec<KeyChord>);
+
+impl KeySequence {
+    /// Parse whitespace-separated chords, e.g. `g g` or `ctrl-w d`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let chords = s
+            .split_whitespace()
+            .map(KeyChord::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if chords.is_empty() {
+            return Err("empty key sequence".to_string());
+        }
+        Ok(Self(chords))
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Sequences of plain characters read best without separators: "gg", "dd"
+        if self.0.len() > 1
+            && self
+                .0
+                .iter()
+                .all(|c| c.is_plain_char() && c.code != KeyCode::Char(' '))
+        {
+            for chord in &self.0 {
+                write!(f, "{}", chord)?;
+            }
+            return Ok(());
+        }
+        let parts: Vec<String> = self.0.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// A key sequence bound to an action
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub keys: KeySequence,
+    pub action: Action,
+}
+
+/// User overrides from the `[keys.<mode>]` config tables: key sequence to
+/// action name, or `"none"` to unbind
+pub type KeysConfig = HashMap<Mode, BTreeMap<String, String>>;
+
+/// One line of the help overlay: an action and all keys bound to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct HelpEntry {
+    pub keys: Vec<String>,
+    pub action: Action,
+}
+
+impl HelpEntry {
+    /// Case-insensitive match against keys, description and action name
+    fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.keys.iter().any(|k| k.to_lowercase() == query)
+            || self.action.description().to_lowercase().contains(&query)
+            || self
+                .action
+                .name()
+                .is_some_and(|name| name.contains(&query.replace(' ', "_")))
+    }
+}
+
+/// Result of feeding a key press to the keymap
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyResult {
+    /// A complete sequence was typed
+    Action(Action),
+    /// The key starts a longer sequence; wait for more keys
+    Pending,
+    /// Nothing is bound to the key
+    Unbound,
+}
+
+/// Maps key sequences to actions per mode and tracks partially typed sequences
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<Mode, Vec<Binding>>,
+    pending: Vec<KeyChord>,
+    pending_mode: Option<Mode>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&KeysConfig::new()).expect("default keymap must be valid")
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from the defaults plus user overrides, rejecting
+    /// unknown keys/actions and ambiguous sequences.
+    pub fn from_config(overrides: &KeysConfig) -> Result<Self, KeymapError> {
+        let mut bindings: HashMap<Mode, Vec<Binding>> = HashMap::new();
+        for (mode, keys, action) in DEFAULT_BINDINGS {
+            bindings.entry(*mode).or_default().push(Binding {
+                keys: KeySequence::parse(keys).expect("invalid default key"),
+                action: Action::from_name(action).expect("invalid default action"),
+            });
+        }
+
+        let mut modes: Vec<_> = overrides.keys().copied().collect();
+        modes.sort();
+        for mode in modes {
+            let mode_bindings = bindings.entry(mode).or_default();
+            for (keys_str, action_name) in &overrides[&mode] {
+                let keys =
+                    KeySequence::parse(keys_str).map_err(|message| KeymapError::InvalidKey {
+                        mode: mode.name(),
+                        keys: keys_str.clone(),
+                        message,
+                    })?;
+                if matches!(mode, Mode::Input | Mode::Palette)
+                    && keys.0.iter().any(|c| c.is_plain_char())
+                {
+                    return Err(KeymapError::InvalidKey {
+                        mode: mode.name(),
+                        keys: keys_str.clone(),
+                        message: format!(
+                            "plain characters are reserved for typing in {} mode",
+                            mode.name()
+                        ),
+                    });
+                }
+
+                // Overrides replace any existing binding of the same keys
+                mode_bindings.retain(|b| b.keys != keys);
+                if action_name == "none" {
+                    continue;
+                }
+                let action =
+                    Action::from_name(action_name).ok_or_else(|| KeymapError::UnknownAction {
+                        mode: mode.name(),
+                        keys: keys_str.clone(),
+                        action: action_name.clone(),
+                    })?;
+                mode_bindings.push(Binding { keys, action });
+            }
+        }
+
+        let keymap = Self {
+            bindings,
+            pending: Vec::new(),
+            pending_mode: None,
+        };
+        keymap.check_conflicts()?;
+        Ok(keymap)
+    }
+
+    /// A sequence that is a prefix of another could never complete
+    fn check_conflicts(&self) -> Result<(), KeymapError> {
+        for mode in Mode::ALL {
+            let bindings = self.bindings(mode);
+            for a in bindings {
+                for b in bindings {
+                    if a.keys.0.len() < b.keys.0.len() && b.keys.0.starts_with(&a.keys.0) {
+                        return Err(KeymapError::Conflict {
+                            mode: mode.name(),
+                            prefix: a.keys.to_string(),
+                            prefix_action: a.action.name().unwrap_or_default().to_string(),
+                            keys: b.keys.to_string(),
+                            action: b.action.name().unwrap_or_default().to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// All bindings of a mode, in definition order
+    pub fn bindings(&self, mode: Mode) -> &[Binding] {
+        self.bindings.get(&mode).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// All key sequences bound to `action` in `mode`
+    pub fn keys_for(&self, mode: Mode, action: &Action) -> Vec<&KeySequence> {
+        self.bindings(mode)
+            .iter()
+            .filter(|b| &b.action == action)
+            .map(|b| &b.keys)
+            .collect()
+    }
+
+    /// Bindings of every mode grouped by action, in definition order, filtered
+    /// by `query` (empty = everything). Modes without matches are omitted.
+    pub fn help_entries(&self, query: &str) -> Vec<(Mode, Vec<HelpEntry>)> {
+        let query = query.trim();
+        Mode::ALL
+            .iter()
+            .filter_map(|mode| {
+                let mut entries: Vec<HelpEntry> = Vec::new();
+                for binding in self.bindings(*mode) {
+                    match entries.iter_mut().find(|e| e.action == binding.action) {
+                        Some(entry) => entry.keys.push(binding.keys.to_string()),
+                        None => entries.push(HelpEntry {
+                            keys: vec![binding.keys.to_string()],
+                            action: binding.action.clone(),
+                        }),
+                    }
+                }
+                entries.retain(|entry| query.is_empty() || entry.matches(query));
+                (!entries.is_empty()).then_some((*mode, entries))
+            })
+            .collect()
+    }
+
+    /// Keys typed so far of an incomplete sequence
+    pub fn pending(&self) -> &[KeyChord] {
+        &self.pending
+    }
+
+    /// Feed a key press. Partial sequences are remembered until they complete
+    /// or a key that doesn't continue them is pressed.
+    pub fn handle_key(&mut self, mode: Mode, key: KeyEvent) -> KeyResult {
+        let chord = KeyChord::from(key);
+        if self.pending_mode != Some(mode) {
+            self.pending.clear();
+        }
+        self.pending_mode = Some(mode);
+
+        if !self.pending.is_empty() {
+            // Esc aborts a partially typed sequence
+            if chord.code == KeyCode::Esc && chord.modifiers.is_empty() {
+                self.pending.clear();
+                return KeyResult::Pending;
+            }
+            let mut sequence = self.pending.clone();
+            sequence.push(chord);
+            match self.lookup(mode, &sequence) {
+                KeyResult::Unbound => {
+                    // Drop the stale prefix and start over with this key
+                    self.pending.clear();
+                }
+                result => return self.finish(sequence, result),
+            }
+        }
+
+        let result = self.lookup(mode, &[chord]);
+        self.finish(vec![chord], result)
+    }
+
+    fn finish(&mut self, sequence: Vec<KeyChord>, result: KeyResult) -> KeyResult {
+        self.pending = match result {
+            KeyResult::Pending => sequence,
+            _ => Vec::new(),
+        };
+        result
+    }
+
+    fn lookup(&self, mode: Mode, sequence: &[KeyChord]) -> KeyResult {
+        let mut is_prefix = false;
+        for binding in self.bindings(mode) {
+            if binding.keys.0 == sequence {
+                return KeyResult::Action(binding.action.clone());
+            }
+            if binding.keys.0.starts_with(sequence) {
+                is_prefix = true;
+            }
+        }
+        if is_prefix {
+            KeyResult::Pending
+        } else {
+            KeyResult::Unbound
+        }
+    }
+}
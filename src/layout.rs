@@ -0,0 +1,129 @@
+use anyhow::{Context, Result};
+use ratatui::layout::Direction;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::config::{self, LayoutConfig};
+use crate::keymap::Mode;
+
+/// Percent the task list grows or shrinks by per key press
+pub const RESIZE_STEP: u16 = 5;
+
+/// How the task list and the details pane are arranged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LayoutPreset {
+    /// Vertical below `layout.vertical_below` columns, else horizontal
+    #[default]
+    Auto,
+    /// Side by side
+    Horizontal,
+    /// Stacked, for narrow terminals
+    Vertical,
+}
+
+impl LayoutPreset {
+    pub fn name(self) -> &'static str {
+        match self {
+            LayoutPreset::Auto => "auto",
+            LayoutPreset::Horizontal => "horizontal",
+            LayoutPreset::Vertical => "vertical",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            LayoutPreset::Auto => LayoutPreset::Horizontal,
+            LayoutPreset::Horizontal => LayoutPreset::Vertical,
+            LayoutPreset::Vertical => LayoutPreset::Auto,
+        }
+    }
+
+    /// Direction of the split for a terminal `width` columns wide
+    pub fn direction(self, width: u16, vertical_below: u16) -> Direction {
+        match self {
+            LayoutPreset::Horizontal => Direction::Horizontal,
+            LayoutPreset::Vertical => Direction::Vertical,
+            LayoutPreset::Auto if width < vertical_below => Direction::Vertical,
+            LayoutPreset::Auto => Direction::Horizontal,
+        }
+    }
+}
+
+/// The panes of the main screen, in focus order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Pane {
+    #[default]
+    TaskList,
+    Details,
+    /// The docked log of the selected task, if shown
+    Log,
+}
+
+impl Pane {
+    /// Key handling mode while the pane has the focus
+    pub fn mode(self) -> Mode {
+        match self {
+            Pane::TaskList => Mode::Normal,
+            Pane::Details => Mode::Details,
+            Pane::Log => Mode::LogPane,
+        }
+    }
+}
+
+/// Layout changes made in the TUI, kept between runs. Unset fields fall
+/// back to the config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutState {
+    pub preset: Option<LayoutPreset>,
+    pub task_list_percent: Option<u16>,
+    pub show_status_bar: Option<bool>,
+    pub show_help_bar: Option<bool>,
+    pub show_log_pane: Option<bool>,
+}
+
+impl LayoutState {
+    /// Default location, `$XDG_STATE_HOME/lazypueue/layout.toml`
+    pub fn default_path() -> Option<PathBuf> {
+        config::state_path("layout.toml")
+    }
+
+    /// Read the saved layout; a missing file is an empty one
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        toml::from_str(&content).with_context(|| format!("Invalid layout in {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Override the configured layout with the saved changes
+    pub fn apply(&self, layout: &mut LayoutConfig) {
+        if let Some(preset) = self.preset {
+            layout.preset = preset;
+        }
+        if let Some(percent) = self.task_list_percent {
+            layout.task_list_percent = percent.clamp(10, 90);
+        }
+        if let Some(show) = self.show_status_bar {
+            layout.show_status_bar = show;
+        }
+        if let Some(show) = self.show_help_bar {
+            layout.show_help_bar = show;
+        }
+        if let Some(show) = self.show_log_pane {
+            layout.show_log_pane = show;
+        }
+    }
+}
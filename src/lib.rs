@@ -1,5 +1,27 @@
 // Library exports for testing
+pub mod activity;
 pub mod app;
+pub mod cli;
+pub mod compat;
+pub mod complete;
+pub mod config;
+pub mod daemon;
+pub mod diagnostics;
 pub mod events;
+pub mod filter;
+pub mod fuzzy;
+pub mod history;
+pub mod keymap;
+pub mod layout;
+pub mod messages;
+pub mod move_tasks;
+pub mod notify;
+pub mod palette;
 pub mod pueue_client;
+pub mod queue;
+pub mod snapshot;
+pub mod ssh;
+pub mod templates;
+pub mod theme;
 pub mod ui;
+pub mod undo;
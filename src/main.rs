@@ -1,17 +1,28 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Parser;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
+    event::{DisableMouseCapture, EnableMouseCapture, Event},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use lazypueue::app::App;
+use lazypueue::cli::{self, Command};
+use lazypueue::config::{self, Config, DEFAULT_CONFIG};
+use lazypueue::daemon::Daemons;
 use lazypueue::events;
-use lazypueue::pueue_client::PueueClient;
+use lazypueue::history::History;
+use lazypueue::layout::LayoutState;
+use lazypueue::notify::{NotifyMethod, ResultFilter};
+use lazypueue::theme::ThemeName;
 use lazypueue::ui;
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
+use std::path::PathBuf;
 use std::time::Duration;
+use tokio::time::Instant;
+
+/// Frame rate of the pending spinner
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
 
 #[derive(Parser, Debug)]
 #[command(name = "lazypueue")]
@@ -20,11 +31,125 @@ struct Args {
     /// Pueue daemon URI
     #[arg(short, long)]
     uri: Option<String>,
+
+    /// Daemon to show on startup and for commands, by its name in the config
+    #[arg(short, long, value_name = "NAME")]
+    daemon: Option<String>,
+
+    /// Path to the config file [default: ~/.config/lazypueue/config.toml]
+    #[arg(short, long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Print a commented default config file and exit
+    #[arg(long)]
+    print_default_config: bool,
+
+    /// Color theme [default: dark, or no-color if NO_COLOR is set]
+    #[arg(long, value_enum)]
+    theme: Option<ThemeName>,
+
+    /// Notify when a task finishes (repeatable)
+    #[arg(long = "notify", value_enum, value_name = "METHOD")]
+    notify: Vec<NotifyMethod>,
+
+    /// Run a shell command when a task finishes.
+    /// Placeholders: {id} {group} {label} {command} {result} {exit_code}
+    #[arg(long, value_name = "COMMAND")]
+    notify_command: Option<String>,
+
+    /// Only notify for tasks in this group (repeatable)
+    #[arg(long, value_name = "GROUP")]
+    notify_group: Vec<String>,
+
+    /// Only notify for tasks with this label (repeatable)
+    #[arg(long, value_name = "LABEL")]
+    notify_label: Vec<String>,
+
+    /// Only notify for tasks with this result [default: all]
+    #[arg(long, value_enum)]
+    notify_result: Option<ResultFilter>,
+
+    /// Also append the activity feed to this file
+    #[arg(long, value_name = "PATH")]
+    activity_log: Option<PathBuf>,
+
+    /// Run a command without the TUI
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let _args = Args::parse();
+    let args = Args::parse();
+
+    if args.print_default_config {
+        print!("{}", DEFAULT_CONFIG);
+        return Ok(());
+    }
+
+    let mut config = Config::load(args.config.as_deref())?;
+    let daemon = daemon_index(&config, args.daemon.as_deref())?;
+    // The theme applies to snapshots too
+    if args.theme.is_some() {
+        config.general.theme = args.theme;
+    }
+
+    if let Some(command) = args.command {
+        // Without --daemon, commands use the first daemon and doctor all
+        let daemon = args.daemon.is_some().then_some(daemon);
+        let code = cli::run(command, &config, daemon).await?;
+        std::process::exit(code);
+    }
+
+    // Command line flags take precedence over the config file
+    let notifications = &mut config.notifications;
+    if !args.notify.is_empty() {
+        notifications.methods = args.notify;
+    }
+    if args.notify_command.is_some() {
+        notifications.command = args.notify_command;
+    }
+    if !args.notify_group.is_empty() {
+        notifications.groups = args.notify_group;
+    }
+    if !args.notify_label.is_empty() {
+        notifications.labels = args.notify_label;
+    }
+    if let Some(result) = args.notify_result {
+        notifications.result = result;
+    }
+    if args.activity_log.is_some() {
+        config.activity.log_file = args.activity_log;
+    }
+
+    let mut app = App::with_config(config);
+    app.active_daemon = daemon;
+    app.config_path = args.config.clone().or_else(Config::default_path);
+    if let Some(path) = &app.config.activity.log_file {
+        let path = config::expand_tilde(path);
+        app.activity = std::mem::take(&mut app.activity).with_file(&path)?;
+    }
+    app.history_path = History::default_path();
+    if let Some(path) = &app.history_path {
+        match History::load(path) {
+            Ok(history) => app.history = history,
+            Err(e) => app
+                .messages
+                .warn(format!("Failed to load command history: {:#}", e)),
+        }
+    }
+    app.layout_path = LayoutState::default_path();
+    if let Some(path) = &app.layout_path {
+        match LayoutState::load(path) {
+            Ok(state) => {
+                state.apply(&mut app.config.layout);
+                app.layout_state = state;
+            }
+            Err(e) => app
+                .messages
+                .warn(format!("Failed to load the saved layout: {:#}", e)),
+        }
+    }
 
     // Setup terminal
     enable_raw_mode()?;
@@ -34,7 +159,7 @@ async fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Run the app
-    let res = run_app(&mut terminal).await;
+    let res = run_app(&mut terminal, app).await;
 
     // Restore terminal
     disable_raw_mode()?;
@@ -52,51 +177,92 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-async fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>) -> Result<()> {
-    let mut app = App::new();
-    let mut client = PueueClient::new().await?;
-
-    // Initial fetch
-    app.refresh(&mut client).await?;
+async fn run_app<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    mut app: App,
+) -> Result<()> {
+    // Daemons are only talked to from background tasks, so a slow or
+    // unreachable daemon never blocks input or rendering
+    let endpoints = app
+        .config
+        .daemon_list()
+        .into_iter()
+        .map(|(name, daemon)| {
+            let endpoint = daemon.endpoint().map_err(anyhow::Error::msg)?;
+            Ok((name, endpoint))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut daemons = Daemons::spawn(endpoints);
+    app.poll();
+    let mut terminal_events = events::spawn_reader();
+    let mut spinner = tokio::time::interval(SPINNER_INTERVAL);
+    let mut next_poll = Instant::now() + poll_interval(&app);
 
     loop {
         // Render UI
         terminal.draw(|f| ui::render(f, &app))?;
 
-        // Use shorter poll interval when in follow mode
-        let poll_duration = if app.follow_mode {
-            Duration::from_millis(200)
-        } else {
-            Duration::from_millis(500)
-        };
-
-        // Handle events with timeout for periodic refresh
-        if event::poll(poll_duration)? {
-            if let Event::Key(key) = event::read()? {
-                // Use different event handler based on current mode
-                let action = if app.confirm_delete.is_some() {
-                    events::handle_confirm_mode_key_event(key)
-                } else if app.input_mode.is_some() {
-                    events::handle_input_mode_key_event(key)
-                } else if app.show_log_modal {
-                    events::handle_log_modal_key_event(key)
-                } else {
-                    events::handle_key_event(key)
+        tokio::select! {
+            event = terminal_events.recv() => {
+                let action = match event {
+                    // Resolve the key through the keymap of the current mode
+                    Some(Event::Key(key)) => events::handle_key_event(&mut app, key),
+                    Some(Event::Mouse(mouse)) => events::handle_mouse_event(&mut app, mouse),
+                    Some(_) => None,
+                    None => break,
                 };
-
                 if let Some(action) = action {
-                    let should_quit = app.handle_action(action, &mut client).await?;
-                    if should_quit {
+                    if app.handle_action(action) {
                         break;
                     }
                 }
             }
-        } else {
-            // Timeout - refresh task state and logs if in follow mode
-            app.refresh(&mut client).await?;
-            app.refresh_logs(&mut client).await?;
+            Some((id, outcome)) = daemons.done.recv() => {
+                app.complete(id, outcome);
+            }
+            _ = tokio::time::sleep_until(next_poll) => {
+                // Refresh task state, and logs if in follow mode
+                app.poll();
+                next_poll = Instant::now() + poll_interval(&app);
+            }
+            // Animate the spinner while user actions are in flight
+            _ = spinner.tick(), if app.pending_labels().next().is_some() => {
+                app.spinner_frame = app.spinner_frame.wrapping_add(1);
+            }
+        }
+
+        for job in app.take_jobs() {
+            daemons.send(job);
         }
     }
 
     Ok(())
 }
+
+/// Index of the daemon named on the command line, or the first one
+fn daemon_index(config: &Config, name: Option<&str>) -> Result<usize> {
+    let daemons = config.daemon_list();
+    let Some(name) = name else {
+        return Ok(0);
+    };
+    match daemons.iter().position(|(n, _)| n == name) {
+        Some(index) => Ok(index),
+        None => {
+            let names: Vec<_> = daemons.iter().map(|(n, _)| n.as_str()).collect();
+            bail!(
+                "Unknown daemon '{}', expected one of: {}",
+                name,
+                names.join(", ")
+            )
+        }
+    }
+}
+
+/// Use shorter poll interval when in follow mode
+fn poll_interval(app: &App) -> Duration {
+    if app.follow_mode || app.config.layout.show_log_pane {
+        Duration::from_millis(app.config.refresh.follow_interval_ms)
+    } else {
+        Duration::from_millis(app.config.refresh.interval_ms)
+    }
+}
@@ -0,0 +1,119 @@
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Number of messages kept in the history
+pub const HISTORY_CAPACITY: usize = 200;
+
+/// Toasts shown at the same time; older ones are hidden early
+pub const MAX_TOASTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MessageLevel {
+    Info,
+    Success,
+    Warn,
+    Error,
+}
+
+impl MessageLevel {
+    /// How long a toast of this level stays on screen
+    pub fn lifetime(self) -> Duration {
+        match self {
+            MessageLevel::Info | MessageLevel::Success => Duration::from_secs(3),
+            MessageLevel::Warn => Duration::from_secs(5),
+            MessageLevel::Error => Duration::from_secs(8),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MessageLevel::Info => "info",
+            MessageLevel::Success => "success",
+            MessageLevel::Warn => "warn",
+            MessageLevel::Error => "error",
+        }
+    }
+}
+
+/// Feedback about a user action, e.g. "Stashed task #3"
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub level: MessageLevel,
+    pub text: String,
+    pub time: DateTime<Local>,
+    /// When the toast was shown, for expiry
+    pub shown_at: Instant,
+}
+
+impl Message {
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now.duration_since(self.shown_at) >= self.level.lifetime()
+    }
+}
+
+/// Transient toasts plus a bounded history of every message
+#[derive(Debug, Clone, Default)]
+pub struct Messages {
+    history: VecDeque<Message>,
+}
+
+impl Messages {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, level: MessageLevel, text: impl Into<String>) {
+        if self.history.len() == HISTORY_CAPACITY {
+            self.history.pop_front();
+        }
+        self.history.push_back(Message {
+            level,
+            text: text.into(),
+            time: Local::now(),
+            shown_at: Instant::now(),
+        });
+    }
+
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.push(MessageLevel::Info, text);
+    }
+
+    pub fn success(&mut self, text: impl Into<String>) {
+        self.push(MessageLevel::Success, text);
+    }
+
+    pub fn warn(&mut self, text: impl Into<String>) {
+        self.push(MessageLevel::Warn, text);
+    }
+
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.push(MessageLevel::Error, text);
+    }
+
+    /// Unexpired toasts, oldest first, at most `MAX_TOASTS`
+    pub fn toasts(&self, now: Instant) -> Vec<&Message> {
+        let mut toasts: Vec<&Message> = self
+            .history
+            .iter()
+            .rev()
+            .filter(|m| !m.is_expired(now))
+            .take(MAX_TOASTS)
+            .collect();
+        toasts.reverse();
+        toasts
+    }
+
+    /// All messages, newest first
+    pub fn history(&self) -> impl Iterator<Item = &Message> {
+        self.history.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+}
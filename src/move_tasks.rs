@@ -0,0 +1,157 @@
+use pueue_lib::state::State;
+use pueue_lib::task::{Task, TaskStatus};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use crate::pueue_client::PueueClient;
+
+/// Why a task can't be moved to another group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Only queued and stashed tasks can be moved
+    NotQueued,
+    /// The task is already in the target group
+    SameGroup,
+    /// A task that isn't moved depends on it, and would lose the link
+    HasDependants,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::NotQueued => write!(f, "not queued or stashed"),
+            SkipReason::SameGroup => write!(f, "already in that group"),
+            SkipReason::HasDependants => write!(f, "other tasks depend on it"),
+        }
+    }
+}
+
+/// Which of the requested tasks can be moved to a group
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MovePlan {
+    /// Task ids to move, in ascending order so dependencies are re-added first
+    pub tasks: Vec<usize>,
+    pub skipped: Vec<(usize, SkipReason)>,
+}
+
+/// Decide which tasks can be moved to `target`.
+///
+/// Moving re-adds a task and removes the original, so it gets a new id.
+/// Dependencies between moved tasks are remapped, but links from tasks that
+/// stay behind can't be updated; such tasks are skipped.
+pub fn plan_move(state: &State, task_ids: &[usize], target: &str) -> MovePlan {
+    let mut plan = MovePlan::default();
+    let mut moving = BTreeSet::new();
+
+    for &id in task_ids {
+        let Some(task) = state.tasks.get(&id) else {
+            continue;
+        };
+        if !matches!(
+            task.status,
+            TaskStatus::Queued { .. } | TaskStatus::Stashed { .. }
+        ) {
+            plan.skipped.push((id, SkipReason::NotQueued));
+        } else if task.group == target {
+            plan.skipped.push((id, SkipReason::SameGroup));
+        } else {
+            moving.insert(id);
+        }
+    }
+
+    // Skipping a task may strand the tasks it depends on, so repeat until stable
+    loop {
+        let stranded: Vec<usize> = moving
+            .iter()
+            .copied()
+            .filter(|id| {
+                state.tasks.iter().any(|(other_id, other)| {
+                    !moving.contains(other_id)
+                        && !matches!(other.status, TaskStatus::Done { .. })
+                        && other.dependencies.contains(id)
+                })
+            })
+            .collect();
+        if stranded.is_empty() {
+            break;
+        }
+        for id in stranded {
+            moving.remove(&id);
+            plan.skipped.push((id, SkipReason::HasDependants));
+        }
+    }
+
+    plan.tasks = moving.into_iter().collect();
+    plan.skipped.sort_by_key(|(id, _)| *id);
+    plan
+}
+
+/// Point dependencies on moved tasks to their new ids
+pub fn remap_dependencies(dependencies: &[usize], new_ids: &HashMap<usize, usize>) -> Vec<usize> {
+    dependencies
+        .iter()
+        .map(|id| new_ids.get(id).copied().unwrap_or(*id))
+        .collect()
+}
+
+/// What a move did: the new id of every task that moved, and the error that
+/// stopped the rest
+#[derive(Debug, Default)]
+pub struct MoveResult {
+    pub new_ids: HashMap<usize, usize>,
+    pub error: Option<anyhow::Error>,
+}
+
+/// Move `tasks` to `group`, in the order of the plan.
+///
+/// Queued originals are stashed first, so none of them can start while its
+/// copy exists. If the originals can't be removed, the copies are removed
+/// again. Queued originals that didn't move are enqueued again.
+pub async fn move_tasks(client: &mut PueueClient, tasks: &[Task], group: &str) -> MoveResult {
+    let mut result = MoveResult::default();
+    let queued: Vec<usize> = tasks
+        .iter()
+        .filter(|task| matches!(task.status, TaskStatus::Queued { .. }))
+        .map(|task| task.id)
+        .collect();
+    if !queued.is_empty() {
+        if let Err(e) = client.stash(queued.clone()).await {
+            result.error = Some(e);
+            return result;
+        }
+    }
+
+    // Re-add copies in id order so dependencies on moved tasks can point to
+    // the new ids, then remove the originals
+    let mut copies = HashMap::new();
+    for task in tasks {
+        let dependencies = remap_dependencies(&task.dependencies, &copies);
+        match client.readd(task, group, dependencies).await {
+            Ok(new_id) => {
+                copies.insert(task.id, new_id);
+            }
+            Err(e) => {
+                result.error = Some(e);
+                break;
+            }
+        }
+    }
+    if !copies.is_empty() {
+        match client.remove(copies.keys().copied().collect()).await {
+            Ok(()) => result.new_ids = copies,
+            Err(e) => {
+                let _ = client.remove(copies.values().copied().collect()).await;
+                result.error = Some(e);
+            }
+        }
+    }
+
+    let stayed: Vec<usize> = queued
+        .into_iter()
+        .filter(|id| !result.new_ids.contains_key(id))
+        .collect();
+    if !stayed.is_empty() {
+        let _ = client.enqueue(stayed).await;
+    }
+    result
+}
@@ -0,0 +1,269 @@
+use pueue_lib::state::State;
+use pueue_lib::task::{TaskResult, TaskStatus};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// How to notify the user about a finished task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyMethod {
+    /// Ring the terminal bell
+    Bell,
+    /// OSC 9 desktop notification (iTerm2, WezTerm, Windows Terminal, ...)
+    Osc9,
+    /// OSC 777 desktop notification (urxvt, foot, Ghostty, ...)
+    Osc777,
+    /// tmux `display-message`
+    Tmux,
+}
+
+/// Which task results trigger a notification
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultFilter {
+    #[default]
+    All,
+    Success,
+    Failure,
+}
+
+/// Notification settings
+#[derive(Debug, Clone, Default)]
+pub struct NotifyConfig {
+    pub methods: Vec<NotifyMethod>,
+    /// Shell command to run for every finished task, with `{placeholders}` substituted
+    pub command: Option<String>,
+    /// Only notify for tasks in these groups (empty = all groups)
+    pub groups: Vec<String>,
+    /// Only notify for tasks with these labels (empty = any label)
+    pub labels: Vec<String>,
+    pub result: ResultFilter,
+}
+
+/// Short lowercase name of a task result, e.g. "success" or "failed"
+pub fn result_name(result: &TaskResult) -> &'static str {
+    match result {
+        TaskResult::Success => "success",
+        TaskResult::Failed(_) => "failed",
+        TaskResult::FailedToSpawn(_) => "failed-to-spawn",
+        TaskResult::Killed => "killed",
+        TaskResult::Errored => "errored",
+        TaskResult::DependencyFailed => "dependency-failed",
+    }
+}
+
+/// A task that transitioned into `TaskStatus::Done` between two states
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinishedTask {
+    pub id: usize,
+    pub group: String,
+    pub label: Option<String>,
+    pub command: String,
+    pub result: TaskResult,
+}
+
+impl FinishedTask {
+    /// Short lowercase name of the result, e.g. "success" or "failed"
+    pub fn result_name(&self) -> &'static str {
+        result_name(&self.result)
+    }
+
+    /// Exit code as a string, or "-" if the task didn't exit normally
+    pub fn exit_code(&self) -> String {
+        match self.result {
+            TaskResult::Success => "0".to_string(),
+            TaskResult::Failed(code) => code.to_string(),
+            _ => "-".to_string(),
+        }
+    }
+
+    /// One-line summary used as notification body
+    pub fn summary(&self) -> String {
+        format!("Task #{} {}: {}", self.id, self.result_name(), self.command)
+    }
+}
+
+/// Find all tasks that are done in `new` but weren't done in `previous`.
+///
+/// Tasks that didn't exist in `previous` at all are included too, so a task
+/// that was added and finished between two polls isn't missed.
+pub fn finished_tasks(previous: &State, new: &State) -> Vec<FinishedTask> {
+    new.tasks
+        .iter()
+        .filter_map(|(id, task)| {
+            let TaskStatus::Done { result, .. } = &task.status else {
+                return None;
+            };
+            let was_done = previous
+                .tasks
+                .get(id)
+                .is_some_and(|t| matches!(t.status, TaskStatus::Done { .. }));
+            if was_done {
+                return None;
+            }
+            Some(FinishedTask {
+                id: *id,
+                group: task.group.clone(),
+                label: task.label.clone(),
+                command: task.command.clone(),
+                result: result.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Emits notifications for finished tasks according to a `NotifyConfig`
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    pub config: NotifyConfig,
+}
+
+impl Notifier {
+    pub fn new(config: NotifyConfig) -> Self {
+        Self { config }
+    }
+
+    /// Whether any notification method is configured at all
+    pub fn is_enabled(&self) -> bool {
+        !self.config.methods.is_empty() || self.config.command.is_some()
+    }
+
+    /// Check the group, label and result filters
+    pub fn matches(&self, task: &FinishedTask) -> bool {
+        let config = &self.config;
+        if !config.groups.is_empty() && !config.groups.contains(&task.group) {
+            return false;
+        }
+        if !config.labels.is_empty()
+            && !task
+                .label
+                .as_ref()
+                .is_some_and(|label| config.labels.contains(label))
+        {
+            return false;
+        }
+        match config.result {
+            ResultFilter::All => true,
+            ResultFilter::Success => task.result == TaskResult::Success,
+            ResultFilter::Failure => task.result != TaskResult::Success,
+        }
+    }
+
+    /// Notify about a finished task, if it passes the filters. Tasks of a
+    /// daemon that isn't shown name it.
+    pub fn notify(&self, task: &FinishedTask, daemon: Option<&str>) {
+        if !self.is_enabled() || !self.matches(task) {
+            return;
+        }
+
+        let body = match daemon {
+            Some(daemon) => format!("[{}] {}", daemon, task.summary()),
+            None => task.summary(),
+        };
+        for method in &self.config.methods {
+            match method {
+                NotifyMethod::Bell => write_terminal("\x07"),
+                NotifyMethod::Osc9 => write_terminal(&osc(&format!("9;{}", body))),
+                NotifyMethod::Osc777 => {
+                    write_terminal(&osc(&format!("777;notify;lazypueue;{}", body)))
+                }
+                NotifyMethod::Tmux => {
+                    let mut command = Command::new("tmux");
+                    command.args(["display-message", &format!("lazypueue: {}", body)]);
+                    spawn_detached(command);
+                }
+            }
+        }
+
+        if let Some(template) = &self.config.command {
+            let mut command = Command::new("sh");
+            command
+                .arg("-c")
+                .arg(substitute(template, task))
+                .env("LAZYPUEUE_TASK_ID", task.id.to_string())
+                .env("LAZYPUEUE_TASK_GROUP", &task.group)
+                .env("LAZYPUEUE_TASK_LABEL", task.label.as_deref().unwrap_or(""))
+                .env("LAZYPUEUE_TASK_COMMAND", &task.command)
+                .env("LAZYPUEUE_TASK_RESULT", task.result_name())
+                .env("LAZYPUEUE_TASK_EXIT_CODE", task.exit_code());
+            spawn_detached(command);
+        }
+    }
+}
+
+/// Substitute `{id}`, `{group}`, `{label}`, `{command}`, `{result}` and
+/// `{exit_code}` in a notification command. Values are shell-quoted.
+pub fn substitute(template: &str, task: &FinishedTask) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    // Single pass, so substituted values are never expanded again
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start..];
+        rest = after;
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let value = match &after[1..end] {
+            "id" => Some(task.id.to_string()),
+            "group" => Some(shell_quote(&task.group)),
+            "label" => Some(shell_quote(task.label.as_deref().unwrap_or(""))),
+            "command" => Some(shell_quote(&task.command)),
+            "result" => Some(task.result_name().to_string()),
+            "exit_code" => Some(task.exit_code()),
+            _ => None,
+        };
+        match value {
+            Some(value) => {
+                output.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                output.push('{');
+                rest = &after[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Quote a string for `sh`, as one word
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Wrap an OSC payload, using tmux passthrough when running inside tmux.
+/// Control characters in the payload, which could end the sequence early and
+/// smuggle in escapes of their own, are replaced with spaces.
+pub fn osc(payload: &str) -> String {
+    let payload: String = payload
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    let sequence = format!("\x1b]{}\x07", payload);
+    if std::env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+/// Run a command in the background without touching the terminal, reaping it
+/// on a separate thread so it doesn't linger as a zombie
+fn spawn_detached(mut command: Command) {
+    let child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Ok(mut child) = child {
+        std::thread::spawn(move || child.wait());
+    }
+}
+
+fn write_terminal(sequence: &str) {
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(sequence.as_bytes());
+    let _ = stdout.flush();
+}
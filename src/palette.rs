@@ -0,0 +1,289 @@
+use pueue_lib::message::request::Signal;
+
+use crate::app::{Action, App};
+use crate::fuzzy::fuzzy_match;
+use crate::keymap::Mode;
+
+/// Signals offered by the palette, in display order
+const SIGNALS: [Signal; 5] = [
+    Signal::SigInt,
+    Signal::SigTerm,
+    Signal::SigKill,
+    Signal::SigStop,
+    Signal::SigCont,
+];
+
+/// Conventional upper case name, e.g. "SIGINT"
+pub fn signal_name(signal: &Signal) -> &'static str {
+    match signal {
+        Signal::SigInt => "SIGINT",
+        Signal::SigKill => "SIGKILL",
+        Signal::SigTerm => "SIGTERM",
+        Signal::SigCont => "SIGCONT",
+        Signal::SigStop => "SIGSTOP",
+    }
+}
+
+/// What the palette lists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PaletteScope {
+    /// Every command
+    #[default]
+    Commands,
+    /// Only the groups, as a picker for moving tasks
+    MoveToGroup,
+    /// Recent operations that can be undone
+    Undo,
+    /// Templates to add a task from
+    Templates,
+}
+
+/// A command listed in the palette
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteEntry {
+    pub label: String,
+    /// Key binding in normal mode, e.g. "K" or "d d"
+    pub keys: Option<String>,
+    /// `None` for hints that need more input, like a number
+    pub action: Option<Action>,
+}
+
+/// A palette entry matching the query, with the matched label char indices
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteMatch {
+    pub entry: PaletteEntry,
+    pub positions: Vec<usize>,
+}
+
+/// Bindable actions listed as commands, the ones that make sense from the
+/// task list. Actions that only work inside a dialog or pane are left out.
+const COMMANDS: &[Action] = &[
+    Action::NavigateUp,
+    Action::NavigateDown,
+    Action::NavigateTop,
+    Action::NavigateBottom,
+    Action::HalfPageDown,
+    Action::HalfPageUp,
+    Action::CenterSelection,
+    Action::KillTask,
+    Action::TogglePause,
+    Action::ToggleTaskPause,
+    Action::Refresh,
+    Action::ViewLogs,
+    Action::RestartTask,
+    Action::CleanFinished,
+    Action::FollowLogs,
+    Action::StartAddTask,
+    Action::StartEditTask,
+    Action::RemoveTask,
+    Action::StashTask,
+    Action::EnqueueTask,
+    Action::SwitchUp,
+    Action::SwitchDown,
+    Action::MoveToQueueTop,
+    Action::MoveToQueueBottom,
+    Action::RaisePriority,
+    Action::LowerPriority,
+    Action::IncreaseParallel,
+    Action::DecreaseParallel,
+    Action::ToggleMark,
+    Action::ClearMarks,
+    Action::StartMoveToGroup,
+    Action::StartTaskFilter,
+    Action::CycleSortMode,
+    Action::AddBlankTask,
+    Action::SaveAsTemplate,
+    Action::Undo,
+    Action::ShowUndoHistory,
+    Action::NextDaemon,
+    Action::PrevDaemon,
+    Action::GrowTaskList,
+    Action::ShrinkTaskList,
+    Action::CycleLayout,
+    Action::ToggleZoom,
+    Action::ToggleStatusBar,
+    Action::ToggleHelpBar,
+    Action::FocusNext,
+    Action::FocusPrev,
+    Action::FocusTaskList,
+    Action::ToggleLogPane,
+    Action::ToggleDiagnostics,
+    Action::CollapseGroup,
+    Action::ExpandGroup,
+    Action::ToggleActivity,
+    Action::ToggleMessages,
+    Action::ToggleHelp,
+    Action::Quit,
+];
+
+/// All palette entries for the current query and selection.
+///
+/// Parameterized entries are generated from the query, e.g. typing
+/// "parallel 4" offers setting the selected group's limit to 4.
+pub fn palette_entries(app: &App, query: &str) -> Vec<PaletteEntry> {
+    match app.palette_scope {
+        PaletteScope::Commands => {}
+        PaletteScope::MoveToGroup => return group_entries(app),
+        PaletteScope::Undo => return undo_entries(app),
+        PaletteScope::Templates => return template_entries(app),
+    }
+
+    let keys = |action: &Action| {
+        let keys: Vec<String> = app
+            .keymap
+            .keys_for(Mode::Normal, action)
+            .iter()
+            .map(|k| k.to_string())
+            .collect();
+        (!keys.is_empty()).then(|| keys.join(", "))
+    };
+
+    let mut entries: Vec<PaletteEntry> = Action::bindable()
+        .filter(|action| COMMANDS.contains(action))
+        .map(|action| PaletteEntry {
+            label: action.description().to_string(),
+            keys: keys(action),
+            action: Some(action.clone()),
+        })
+        .collect();
+
+    let group = app.get_selected_group();
+    let numbers: Vec<usize> = query
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|n| n.parse().ok())
+        .filter(|n| *n > 0)
+        .collect();
+    if numbers.is_empty() {
+        entries.push(PaletteEntry {
+            label: format!("Set parallel limit of '{}' to N (type a number)", group),
+            keys: None,
+            action: None,
+        });
+    }
+    for n in numbers {
+        entries.push(PaletteEntry {
+            label: format!("Set parallel limit of '{}' to {}", group, n),
+            keys: None,
+            action: Some(Action::SetParallel(n)),
+        });
+    }
+
+    for signal in SIGNALS {
+        entries.push(PaletteEntry {
+            label: format!("Send {} to the selected task", signal_name(&signal)),
+            keys: None,
+            action: Some(Action::KillWithSignal(signal)),
+        });
+    }
+
+    entries.extend(group_entries(app));
+    entries.extend(daemon_entries(app));
+    entries
+}
+
+/// One "switch to daemon" entry per daemon that isn't shown
+fn daemon_entries(app: &App) -> Vec<PaletteEntry> {
+    app.daemons
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != app.active_daemon)
+        .map(|(index, daemon)| PaletteEntry {
+            label: format!("Switch to daemon '{}'", daemon.name),
+            keys: None,
+            action: Some(Action::SwitchDaemon(index)),
+        })
+        .collect()
+}
+
+/// One "move to group" entry per group
+fn group_entries(app: &App) -> Vec<PaletteEntry> {
+    app.get_group_list()
+        .into_iter()
+        .map(|group| PaletteEntry {
+            label: format!("Move to group '{}'", group),
+            keys: None,
+            action: Some(Action::MoveToGroup(group)),
+        })
+        .collect()
+}
+
+/// A blank command followed by one entry per template
+fn template_entries(app: &App) -> Vec<PaletteEntry> {
+    let blank = PaletteEntry {
+        label: "New command".to_string(),
+        keys: None,
+        action: Some(Action::AddBlankTask),
+    };
+    std::iter::once(blank)
+        .chain(
+            app.config
+                .templates
+                .iter()
+                .map(|(name, template)| PaletteEntry {
+                    label: format!("{}: {}", name, template.command),
+                    keys: None,
+                    action: Some(Action::UseTemplate(name.clone())),
+                }),
+        )
+        .collect()
+}
+
+/// One entry per undoable operation, newest first
+fn undo_entries(app: &App) -> Vec<PaletteEntry> {
+    if app.undo.is_empty() {
+        return vec![PaletteEntry {
+            label: "Nothing to undo".to_string(),
+            keys: None,
+            action: None,
+        }];
+    }
+    app.undo
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| PaletteEntry {
+            label: match app
+                .daemons
+                .get(entry.daemon)
+                .filter(|_| app.daemons.len() > 1)
+            {
+                Some(daemon) => format!(
+                    "{}  [{}] {}",
+                    entry.time.format("%H:%M:%S"),
+                    daemon.name,
+                    entry.description
+                ),
+                None => format!("{}  {}", entry.time.format("%H:%M:%S"), entry.description),
+            },
+            keys: None,
+            action: Some(Action::UndoAt(index)),
+        })
+        .collect()
+}
+
+/// Entries matching `query`, best match first. An empty query keeps the
+/// original order.
+pub fn filter_entries(entries: Vec<PaletteEntry>, query: &str) -> Vec<PaletteMatch> {
+    let mut matches: Vec<(i64, usize, PaletteMatch)> = entries
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            let m = fuzzy_match(query, &entry.label)?;
+            Some((
+                m.score,
+                index,
+                PaletteMatch {
+                    entry,
+                    positions: m.positions,
+                },
+            ))
+        })
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    matches.into_iter().map(|(_, _, m)| m).collect()
+}
+
+/// Palette entries matching the app's current palette query
+pub fn palette_matches(app: &App) -> Vec<PaletteMatch> {
+    let query = &app.text_input.value;
+    filter_entries(palette_entries(app, query), query)
+}
@@ -1,15 +1,20 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use pueue_lib::message::request::{
     AddRequest, CleanRequest, EnqueueRequest, KillRequest, LogRequest, ParallelRequest,
-    PauseRequest, Request, StartRequest, StashRequest, SwitchRequest, TaskSelection,
+    PauseRequest, Request, Signal, StartRequest, StashRequest, SwitchRequest, TaskSelection,
 };
 use pueue_lib::message::response::*;
 use pueue_lib::message::EditableTask;
 use pueue_lib::network::client::Client;
+use pueue_lib::network::protocol::ConnectionSettings;
 use pueue_lib::settings::Settings;
 use pueue_lib::state::State;
+use pueue_lib::task::{Task, TaskStatus};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::ssh::{SshTarget, Tunnel};
 
 /// Options for restarting a task (creating a new copy at end of queue).
 pub struct RestartOptions {
@@ -21,13 +26,88 @@ pub struct RestartOptions {
     pub label: Option<String>,
 }
 
+/// Options for adding a task. Unset fields fall back to lazypueue's own
+/// working directory and environment.
+#[derive(Debug, Clone, Default)]
+pub struct AddOptions {
+    pub command: String,
+    pub group: String,
+    pub path: Option<PathBuf>,
+    /// Set on top of the inherited environment
+    pub envs: HashMap<String, String>,
+    pub label: Option<String>,
+    pub priority: Option<i32>,
+}
+
+/// Where a daemon is reached
+#[derive(Debug, Clone, PartialEq)]
+pub enum Endpoint {
+    /// The socket or TCP address of a pueue config file, or of pueue's
+    /// default config
+    Config(Option<PathBuf>),
+    /// A remote daemon's socket, forwarded by `command`, usually `ssh`
+    Ssh {
+        target: SshTarget,
+        command: Vec<String>,
+    },
+}
+
 pub struct PueueClient {
     client: Client,
+    /// Forwarding to a remote daemon, open as long as the client
+    _tunnel: Option<Tunnel>,
 }
 
 impl PueueClient {
     pub async fn new() -> Result<Self> {
-        let (settings, _) = Settings::read(&None)?;
+        Self::connect(None).await
+    }
+
+    pub async fn open(endpoint: &Endpoint) -> Result<Self> {
+        match endpoint {
+            Endpoint::Config(pueue_config) => Self::connect(pueue_config.as_deref()).await,
+            Endpoint::Ssh { target, command } => {
+                let tunnel = Tunnel::open(target, command).await?;
+                Self::through(tunnel, &target.destination).await
+            }
+        }
+    }
+
+    /// Connect through the forwarded socket of an open tunnel
+    #[cfg(unix)]
+    pub async fn through(tunnel: Tunnel, destination: &str) -> Result<Self> {
+        let settings = ConnectionSettings::UnixSocket {
+            path: tunnel.socket.clone(),
+        };
+        let client = Client::new(settings, &tunnel.secret, false)
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to connect to {} through ssh: {}", destination, e)
+            })?;
+        Ok(Self {
+            client,
+            _tunnel: Some(tunnel),
+        })
+    }
+
+    /// The tunnel forwards a unix socket, which pueue can't connect to here
+    #[cfg(not(unix))]
+    pub async fn through(_tunnel: Tunnel, destination: &str) -> Result<Self> {
+        anyhow::bail!(
+            "Failed to connect to {} through ssh: unix sockets aren't supported on this platform",
+            destination
+        )
+    }
+
+    /// Version the daemon reported in the handshake
+    pub fn daemon_version(&self) -> &str {
+        self.client.daemon_version()
+    }
+
+    /// Connect to the daemon of a pueue config file, or of pueue's default
+    /// config
+    pub async fn connect(pueue_config: Option<&Path>) -> Result<Self> {
+        let (settings, _) = Settings::read(&pueue_config.map(Path::to_path_buf))?;
 
         // Read shared secret before consuming settings
         let secret_path = settings.shared.shared_secret_path();
@@ -50,7 +130,10 @@ impl PueueClient {
             .await
             .map_err(|e| anyhow::anyhow!("Failed to create client: {}", e))?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            _tunnel: None,
+        })
     }
 
     pub async fn get_state(&mut self) -> Result<State> {
@@ -69,9 +152,22 @@ impl PueueClient {
     }
 
     pub async fn kill(&mut self, task_ids: Vec<usize>) -> Result<()> {
+        self.kill_with_signal(task_ids, None).await
+    }
+
+    /// Send a signal to the tasks instead of killing them
+    pub async fn send_signal(&mut self, task_ids: Vec<usize>, signal: Signal) -> Result<()> {
+        self.kill_with_signal(task_ids, Some(signal)).await
+    }
+
+    async fn kill_with_signal(
+        &mut self,
+        task_ids: Vec<usize>,
+        signal: Option<Signal>,
+    ) -> Result<()> {
         let request = Request::Kill(KillRequest {
             tasks: TaskSelection::TaskIds(task_ids),
-            signal: None,
+            signal,
         });
         self.client.send_request(request).await?;
         let response = self.client.receive_response().await?;
@@ -113,6 +209,14 @@ impl PueueClient {
     }
 
     pub async fn get_log(&mut self, task_id: usize) -> Result<String> {
+        Ok(self
+            .get_output(task_id)
+            .await?
+            .unwrap_or_else(|| "(No output)".to_string()))
+    }
+
+    /// A task's whole output, `None` if it has none (yet)
+    pub async fn get_output(&mut self, task_id: usize) -> Result<Option<String>> {
         let request = Request::Log(LogRequest {
             tasks: TaskSelection::TaskIds(vec![task_id]),
             send_logs: true,
@@ -122,18 +226,11 @@ impl PueueClient {
         let response = self.client.receive_response().await?;
 
         match response {
-            Response::Log(logs) => {
-                if let Some(task_log) = logs.get(&task_id) {
-                    if let Some(output) = &task_log.output {
-                        // Convert bytes to string, handling potential encoding issues
-                        Ok(String::from_utf8_lossy(output).to_string())
-                    } else {
-                        Ok("(No output)".to_string())
-                    }
-                } else {
-                    Ok("(No log found for this task)".to_string())
-                }
-            }
+            Response::Log(logs) => logs
+                .get(&task_id)
+                .and_then(|log| log.output.as_deref())
+                .map(decompress_output)
+                .transpose(),
             Response::Failure(text) => Err(anyhow::anyhow!("Failed to get log: {}", text)),
             _ => Err(anyhow::anyhow!("Unexpected response from daemon")),
         }
@@ -179,19 +276,32 @@ impl PueueClient {
     }
 
     pub async fn add(&mut self, command: String, group: &str) -> Result<usize> {
+        self.add_with(AddOptions {
+            command,
+            group: group.to_string(),
+            ..AddOptions::default()
+        })
+        .await
+    }
+
+    pub async fn add_with(&mut self, opts: AddOptions) -> Result<usize> {
         // Inherit current environment so tasks have access to PATH and other vars
-        let envs: HashMap<String, String> = std::env::vars().collect();
+        let mut envs: HashMap<String, String> = std::env::vars().collect();
+        envs.extend(opts.envs);
+        let path = opts
+            .path
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")));
         let request = Request::Add(AddRequest {
-            command,
-            path: std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")),
+            command: opts.command,
+            path,
             envs,
             start_immediately: false,
             stashed: false,
-            group: group.to_string(),
+            group: opts.group,
             enqueue_at: None,
             dependencies: vec![],
-            priority: None,
-            label: None,
+            priority: opts.priority,
+            label: opts.label,
         });
         self.client.send_request(request).await?;
         let response = self.client.receive_response().await?;
@@ -203,6 +313,41 @@ impl PueueClient {
         }
     }
 
+    /// Add a copy of a task to a group, keeping its command, path,
+    /// environment, label, priority and stash state. Used to move tasks
+    /// between groups and to restore removed ones.
+    pub async fn readd(
+        &mut self,
+        task: &Task,
+        group: &str,
+        dependencies: Vec<usize>,
+    ) -> Result<usize> {
+        let (stashed, enqueue_at) = match task.status {
+            TaskStatus::Stashed { enqueue_at } => (true, enqueue_at),
+            _ => (false, None),
+        };
+        let request = Request::Add(AddRequest {
+            command: task.original_command.clone(),
+            path: task.path.clone(),
+            envs: task.envs.clone(),
+            start_immediately: false,
+            stashed,
+            group: group.to_string(),
+            enqueue_at,
+            dependencies,
+            priority: Some(task.priority),
+            label: task.label.clone(),
+        });
+        self.client.send_request(request).await?;
+        let response = self.client.receive_response().await?;
+
+        match response {
+            Response::AddedTask(added) => Ok(added.task_id),
+            Response::Failure(text) => Err(anyhow::anyhow!("Failed to re-add task: {}", text)),
+            _ => Err(anyhow::anyhow!("Unexpected response from daemon")),
+        }
+    }
+
     pub async fn remove(&mut self, task_ids: Vec<usize>) -> Result<()> {
         let request = Request::Remove(task_ids);
         self.client.send_request(request).await?;
@@ -355,3 +500,13 @@ impl PueueClient {
         }
     }
 }
+
+/// Task output as sent by the daemon, which compresses it with snap.
+/// Invalid UTF-8 is replaced rather than rejected.
+pub fn decompress_output(compressed: &[u8]) -> Result<String> {
+    let mut output = Vec::new();
+    snap::read::FrameDecoder::new(compressed)
+        .read_to_end(&mut output)
+        .context("Failed to decompress the task's output")?;
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
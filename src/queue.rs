@@ -0,0 +1,109 @@
+use pueue_lib::state::State;
+use pueue_lib::task::TaskStatus;
+use std::cmp::Reverse;
+use thiserror::Error;
+
+/// Where to move a task within its group's queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueMove {
+    Up,
+    Down,
+    Top,
+    Bottom,
+}
+
+/// Why a task can't be moved within the queue
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum QueueError {
+    #[error("only queued or stashed tasks can be reordered")]
+    NotQueued,
+    #[error("task is already at the top of the queue")]
+    AtTop,
+    #[error("task is already at the bottom of the queue")]
+    AtBottom,
+    #[error("task #{0} has a higher priority; raise the priority instead")]
+    HigherPriority(usize),
+    #[error("task #{0} has a lower priority; lower the priority instead")]
+    LowerPriority(usize),
+}
+
+fn is_waiting(status: &TaskStatus) -> bool {
+    matches!(
+        status,
+        TaskStatus::Queued { .. } | TaskStatus::Stashed { .. }
+    )
+}
+
+/// Queued and stashed tasks of a group in the order the daemon starts them:
+/// highest priority first, then by id
+pub fn queue_order(state: &State, group: &str) -> Vec<usize> {
+    let mut tasks: Vec<_> = state
+        .tasks
+        .iter()
+        .filter(|(_, task)| task.group == group && is_waiting(&task.status))
+        .map(|(id, task)| (Reverse(task.priority), *id))
+        .collect();
+    tasks.sort();
+    tasks.into_iter().map(|(_, id)| id).collect()
+}
+
+/// The `Request::Switch` pairs that move a task, in order.
+///
+/// Switching swaps two task ids, which only changes the queue order of tasks
+/// with the same priority; moving past other priorities needs a priority edit.
+/// After the switches the task has the id of the last pair's second element.
+pub fn plan_switches(
+    state: &State,
+    task_id: usize,
+    direction: QueueMove,
+) -> Result<Vec<(usize, usize)>, QueueError> {
+    let task = state.tasks.get(&task_id).ok_or(QueueError::NotQueued)?;
+    if !is_waiting(&task.status) {
+        return Err(QueueError::NotQueued);
+    }
+
+    let order = queue_order(state, &task.group);
+    let pos = order
+        .iter()
+        .position(|id| *id == task_id)
+        .ok_or(QueueError::NotQueued)?;
+    let same_priority = |id: &usize| state.tasks[id].priority == task.priority;
+
+    let (neighbours, boundary_error, end_error): (Vec<usize>, fn(usize) -> QueueError, _) =
+        match direction {
+            QueueMove::Up | QueueMove::Top => (
+                order[..pos].iter().rev().copied().collect(),
+                QueueError::HigherPriority,
+                QueueError::AtTop,
+            ),
+            QueueMove::Down | QueueMove::Bottom => (
+                order[pos + 1..].to_vec(),
+                QueueError::LowerPriority,
+                QueueError::AtBottom,
+            ),
+        };
+
+    let band: Vec<usize> = neighbours
+        .iter()
+        .copied()
+        .take_while(same_priority)
+        .collect();
+    if band.is_empty() {
+        return Err(match neighbours.first() {
+            Some(other) => boundary_error(*other),
+            None => end_error,
+        });
+    }
+
+    let steps = match direction {
+        QueueMove::Up | QueueMove::Down => 1,
+        QueueMove::Top | QueueMove::Bottom => band.len(),
+    };
+    let mut current = task_id;
+    let mut switches = Vec::with_capacity(steps);
+    for other in band.into_iter().take(steps) {
+        switches.push((current, other));
+        current = other;
+    }
+    Ok(switches)
+}
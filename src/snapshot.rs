@@ -0,0 +1,332 @@
+use anyhow::Result;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+use ratatui::Terminal;
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::App;
+use crate::ui;
+
+/// Output formats of a rendered frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SnapshotFormat {
+    /// Plain text without colors
+    #[default]
+    Text,
+    /// Text with ANSI escape codes, for `cat` in a terminal
+    Ansi,
+    /// Standalone SVG image
+    Svg,
+    /// Standalone HTML page
+    Html,
+}
+
+/// Width and height of a terminal cell in SVG pixels
+const CELL_WIDTH: f32 = 8.4;
+const CELL_HEIGHT: usize = 17;
+
+/// Colors for the terminal's default foreground and background
+const DEFAULT_FG: &str = "#d0d0d0";
+const DEFAULT_BG: &str = "#1c1c1c";
+
+/// Views that can be opened on top of the task list in a snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SnapshotView {
+    /// The selected task's log, scrolled to the end
+    Logs,
+    Activity,
+    Help,
+}
+
+/// Render one frame of the UI offscreen
+pub fn render_frame(app: &App, width: u16, height: u16) -> Result<Buffer> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    terminal.draw(|f| ui::render(f, app))?;
+    Ok(terminal.backend().buffer().clone())
+}
+
+/// Consecutive cells of a row with the same style
+#[derive(Debug, Clone, PartialEq)]
+struct Run {
+    /// Column of the first cell
+    column: usize,
+    /// Width in cells, wide characters count twice
+    width: usize,
+    text: String,
+    fg: Color,
+    bg: Color,
+    modifier: Modifier,
+}
+
+/// Rows of style runs. Cells hidden behind a wide character are skipped.
+fn runs(buffer: &Buffer) -> Vec<Vec<Run>> {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            let mut runs: Vec<Run> = Vec::new();
+            let mut skip = 0;
+            for x in area.left()..area.right() {
+                let cell = &buffer[(x, y)];
+                if skip > 0 {
+                    skip -= 1;
+                    continue;
+                }
+                let symbol = cell.symbol();
+                let width = symbol.width().max(1);
+                skip = width - 1;
+                match runs.last_mut() {
+                    Some(run)
+                        if run.fg == cell.fg
+                            && run.bg == cell.bg
+                            && run.modifier == cell.modifier =>
+                    {
+                        run.text.push_str(symbol);
+                        run.width += width;
+                    }
+                    _ => runs.push(Run {
+                        column: (x - area.left()) as usize,
+                        width,
+                        text: symbol.to_string(),
+                        fg: cell.fg,
+                        bg: cell.bg,
+                        modifier: cell.modifier,
+                    }),
+                }
+            }
+            runs
+        })
+        .collect()
+}
+
+/// Render a frame in the given format
+pub fn render(buffer: &Buffer, format: SnapshotFormat) -> String {
+    match format {
+        SnapshotFormat::Text => to_text(buffer),
+        SnapshotFormat::Ansi => to_ansi(buffer),
+        SnapshotFormat::Svg => to_svg(buffer),
+        SnapshotFormat::Html => to_html(buffer),
+    }
+}
+
+/// Plain text, one line per row without trailing spaces
+pub fn to_text(buffer: &Buffer) -> String {
+    let mut text = String::new();
+    for row in runs(buffer) {
+        let line: String = row.iter().map(|run| run.text.as_str()).collect();
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// Text with SGR escape codes, reset at the end of every row
+pub fn to_ansi(buffer: &Buffer) -> String {
+    let mut text = String::new();
+    for row in runs(buffer) {
+        for run in row {
+            let codes = sgr_codes(&run);
+            let separator = if codes.is_empty() { "" } else { ";" };
+            text.push_str(&format!("\x1b[0{}{}m{}", separator, codes, run.text));
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+/// SGR parameters for the style of a run, e.g. "1;32;44"
+fn sgr_codes(run: &Run) -> String {
+    const MODIFIERS: [(Modifier, u8); 9] = [
+        (Modifier::BOLD, 1),
+        (Modifier::DIM, 2),
+        (Modifier::ITALIC, 3),
+        (Modifier::UNDERLINED, 4),
+        (Modifier::SLOW_BLINK, 5),
+        (Modifier::RAPID_BLINK, 6),
+        (Modifier::REVERSED, 7),
+        (Modifier::HIDDEN, 8),
+        (Modifier::CROSSED_OUT, 9),
+    ];
+    let mut codes: Vec<String> = MODIFIERS
+        .iter()
+        .filter(|(modifier, _)| run.modifier.contains(*modifier))
+        .map(|(_, code)| code.to_string())
+        .collect();
+    codes.extend(color_code(run.fg, false));
+    codes.extend(color_code(run.bg, true));
+    codes.join(";")
+}
+
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let offset = if background { 10 } else { 0 };
+    let base = match color {
+        Color::Reset => return None,
+        Color::Black => 30,
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Blue => 34,
+        Color::Magenta => 35,
+        Color::Cyan => 36,
+        Color::Gray => 37,
+        Color::DarkGray => 90,
+        Color::LightRed => 91,
+        Color::LightGreen => 92,
+        Color::LightYellow => 93,
+        Color::LightBlue => 94,
+        Color::LightMagenta => 95,
+        Color::LightCyan => 96,
+        Color::White => 97,
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{};{};{}", 38 + offset, r, g, b)),
+        Color::Indexed(i) => return Some(format!("{};5;{}", 38 + offset, i)),
+    };
+    Some((base + offset).to_string())
+}
+
+/// CSS color of a terminal color, using xterm's default palette
+fn css_color(color: Color) -> Option<String> {
+    const XTERM: [&str; 16] = [
+        "#000000", "#cd0000", "#00cd00", "#cdcd00", "#0000ee", "#cd00cd", "#00cdcd", "#e5e5e5",
+        "#7f7f7f", "#ff0000", "#00ff00", "#ffff00", "#5c5cff", "#ff00ff", "#00ffff", "#ffffff",
+    ];
+    let index = match color {
+        Color::Reset => return None,
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        Color::Rgb(r, g, b) => return Some(format!("#{:02x}{:02x}{:02x}", r, g, b)),
+        Color::Indexed(i) if i < 16 => i as usize,
+        Color::Indexed(i) => return Some(indexed_color(i)),
+    };
+    Some(XTERM[index].to_string())
+}
+
+/// The 6x6x6 color cube and gray ramp of the 256 color palette
+fn indexed_color(i: u8) -> String {
+    if i >= 232 {
+        let level = 8 + (i - 232) * 10;
+        return format!("#{:02x}{:02x}{:02x}", level, level, level);
+    }
+    let i = i - 16;
+    let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        level(i / 36),
+        level(i / 6 % 6),
+        level(i % 6)
+    )
+}
+
+/// Foreground and background of a run, with reversed video applied
+fn run_colors(run: &Run) -> (String, Option<String>) {
+    let fg = css_color(run.fg);
+    let bg = css_color(run.bg);
+    if run.modifier.contains(Modifier::REVERSED) {
+        (
+            bg.unwrap_or_else(|| DEFAULT_BG.to_string()),
+            Some(fg.unwrap_or_else(|| DEFAULT_FG.to_string())),
+        )
+    } else {
+        (fg.unwrap_or_else(|| DEFAULT_FG.to_string()), bg)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Font attributes shared by SVG and HTML, as CSS declarations
+fn font_css(modifier: Modifier) -> String {
+    let mut css = String::new();
+    if modifier.contains(Modifier::BOLD) {
+        css.push_str("font-weight:bold;");
+    }
+    if modifier.contains(Modifier::ITALIC) {
+        css.push_str("font-style:italic;");
+    }
+    if modifier.contains(Modifier::UNDERLINED) {
+        css.push_str("text-decoration:underline;");
+    }
+    if modifier.contains(Modifier::DIM) {
+        css.push_str("opacity:0.6;");
+    }
+    css
+}
+
+/// A standalone SVG image with one text element per run
+pub fn to_svg(buffer: &Buffer) -> String {
+    let width = buffer.area.width as f32 * CELL_WIDTH;
+    let height = buffer.area.height as usize * CELL_HEIGHT;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         font-family=\"monospace\" font-size=\"14\" xml:space=\"preserve\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"{DEFAULT_BG}\"/>\n"
+    );
+    for (y, row) in runs(buffer).into_iter().enumerate() {
+        let top = y * CELL_HEIGHT;
+        for run in row {
+            let (fg, bg) = run_colors(&run);
+            let x = run.column as f32 * CELL_WIDTH;
+            let run_width = run.width as f32 * CELL_WIDTH;
+            if let Some(bg) = bg {
+                svg.push_str(&format!(
+                    "<rect x=\"{x}\" y=\"{top}\" width=\"{run_width}\" height=\"{CELL_HEIGHT}\" fill=\"{bg}\"/>\n"
+                ));
+            }
+            if run.text.trim().is_empty() {
+                continue;
+            }
+            svg.push_str(&format!(
+                "<text x=\"{x}\" y=\"{}\" textLength=\"{run_width}\" fill=\"{fg}\" style=\"{}\">{}</text>\n",
+                top + CELL_HEIGHT - 4,
+                font_css(run.modifier),
+                escape(&run.text)
+            ));
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// A standalone HTML page with the frame in a `<pre>` block
+pub fn to_html(buffer: &Buffer) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>lazypueue</title></head>\n\
+         <body style=\"margin:0;background:{DEFAULT_BG}\">\n\
+         <pre style=\"margin:0;padding:8px;color:{DEFAULT_FG};font-family:monospace;line-height:1.2\">"
+    );
+    for row in runs(buffer) {
+        for run in row {
+            let (fg, bg) = run_colors(&run);
+            let mut css = format!("color:{};", fg);
+            if let Some(bg) = bg {
+                css.push_str(&format!("background:{};", bg));
+            }
+            css.push_str(&font_css(run.modifier));
+            html.push_str(&format!(
+                "<span style=\"{}\">{}</span>",
+                css,
+                escape(&run.text)
+            ));
+        }
+        html.push('\n');
+    }
+    html.push_str("</pre>\n</body>\n</html>\n");
+    html
+}
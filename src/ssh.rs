@@ -0,0 +1,233 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::{Child, Command};
+
+use crate::notify::shell_quote;
+
+/// How long connecting and authenticating may take
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Printed by the remote command after the shared secret
+const READY: &str = "lazypueue-tunnel-ready";
+
+/// A daemon reached over SSH, from a URL like
+/// `ssh://user@host:/run/user/1000/pueue_user.socket`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshTarget {
+    /// `user@host` or a host alias from `~/.ssh/config`
+    pub destination: String,
+    /// The daemon's socket on the remote host; pueue's default if unset
+    pub socket: Option<String>,
+}
+
+impl SshTarget {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let Some(rest) = url.strip_prefix("ssh://") else {
+            return Err(format!("'{}' isn't an ssh:// URL", url));
+        };
+        let (destination, socket) = match rest.split_once(':') {
+            Some((destination, socket)) => (destination, Some(socket)),
+            None => (rest, None),
+        };
+        if destination.is_empty() || destination.ends_with('@') {
+            return Err(format!("'{}' has no host", url));
+        }
+        if let Some(socket) = socket.filter(|s| !s.starts_with('/')) {
+            return Err(format!(
+                "the socket path '{}' of '{}' must be absolute",
+                socket, url
+            ));
+        }
+        Ok(Self {
+            destination: destination.to_string(),
+            socket: socket.map(str::to_string),
+        })
+    }
+}
+
+/// Remote command printing the default socket path, the way pueue builds it
+const SOCKET_SCRIPT: &str = r#"echo "${XDG_RUNTIME_DIR:-${XDG_DATA_HOME:-$HOME/.local/share}/pueue}/pueue_$(id -un).socket""#;
+
+/// Remote command printing the shared secret and a ready marker, then
+/// keeping the session, and with it the forwarding, open until its input
+/// is closed. The secret is looked up next to the socket, then in pueue's
+/// default directory.
+fn tunnel_script(socket: &str) -> String {
+    let dir = Path::new(socket)
+        .parent()
+        .map_or("/".into(), |dir| dir.to_string_lossy());
+    format!(
+        "s={}/shared_secret; [ -f \"$s\" ] || s=\"${{XDG_DATA_HOME:-$HOME/.local/share}}/pueue/shared_secret\"; \
+         cat \"$s\" || exit 1; printf '\\n{}\\n'; exec cat >/dev/null",
+        shell_quote(&dir),
+        READY
+    )
+}
+
+/// A running `ssh` forwarding the remote daemon's socket to a local one in
+/// a private temporary directory. Dropping it stops `ssh` and removes the
+/// directory.
+#[derive(Debug)]
+pub struct Tunnel {
+    /// Local end of the forwarded socket
+    pub socket: PathBuf,
+    /// The remote daemon's shared secret
+    pub secret: Vec<u8>,
+    dir: PathBuf,
+    // Killed on drop; keeps the remote command's input open until then
+    _child: Child,
+}
+
+impl Tunnel {
+    /// Start `ssh` (or `command`, which takes the same arguments) and wait
+    /// until the socket is forwarded and the secret was read
+    pub async fn open(target: &SshTarget, command: &[String]) -> Result<Self> {
+        let remote = match &target.socket {
+            Some(socket) => socket.clone(),
+            None => remote_socket(target, command).await?,
+        };
+        let dir = temp_dir()?;
+        let socket = dir.join("pueue.socket");
+        let result = tokio::time::timeout(CONNECT_TIMEOUT, async {
+            let mut child = ssh(command)
+                .arg("-o")
+                .arg("ExitOnForwardFailure=yes")
+                .arg("-L")
+                .arg(format!("{}:{}", socket.display(), remote))
+                .arg(&target.destination)
+                .arg(tunnel_script(&remote))
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .with_context(|| format!("Failed to run {}", command[0]))?;
+            let secret = read_secret(&mut child, &target.destination).await?;
+            // The forwarding may be set up after the command started
+            while !socket.exists() {
+                if let Some(status) = child.try_wait()? {
+                    bail!("ssh to {} exited with {}", target.destination, status);
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            Ok((child, secret))
+        })
+        .await;
+
+        match result {
+            Ok(Ok((child, secret))) => Ok(Self {
+                socket,
+                secret,
+                dir,
+                _child: child,
+            }),
+            Ok(Err(e)) => {
+                let _ = std::fs::remove_dir_all(&dir);
+                Err(e)
+            }
+            Err(_) => {
+                let _ = std::fs::remove_dir_all(&dir);
+                bail!("Timed out connecting to {} over ssh", target.destination)
+            }
+        }
+    }
+}
+
+impl Drop for Tunnel {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// The ssh program and its arguments, without a terminal so it never
+/// prompts over the TUI
+fn ssh(command: &[String]) -> Command {
+    let mut ssh = Command::new(&command[0]);
+    ssh.args(&command[1..])
+        .arg("-T")
+        .arg("-o")
+        .arg("BatchMode=yes")
+        .kill_on_drop(true);
+    ssh
+}
+
+/// Ask the remote host where pueue's default socket is
+async fn remote_socket(target: &SshTarget, command: &[String]) -> Result<String> {
+    let output = tokio::time::timeout(
+        CONNECT_TIMEOUT,
+        ssh(command)
+            .arg(&target.destination)
+            .arg(SOCKET_SCRIPT)
+            .stdin(Stdio::null())
+            .output(),
+    )
+    .await
+    .with_context(|| format!("Timed out connecting to {} over ssh", target.destination))?
+    .with_context(|| format!("Failed to run {}", command[0]))?;
+    if !output.status.success() {
+        bail!(
+            "ssh to {} failed: {}",
+            target.destination,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let socket = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if socket.is_empty() {
+        bail!("{} didn't report pueue's socket path", target.destination);
+    }
+    Ok(socket)
+}
+
+/// Read the output of the tunnel script up to the ready marker
+async fn read_secret(child: &mut Child, destination: &str) -> Result<Vec<u8>> {
+    let stdout = child.stdout.take().context("ssh has no output")?;
+    let mut lines = BufReader::new(stdout).split(b'\n');
+    let mut secret: Vec<u8> = Vec::new();
+    while let Some(line) = lines.next_segment().await? {
+        if line == READY.as_bytes() {
+            // Drop the newline printed before the marker
+            secret.pop();
+            return Ok(secret);
+        }
+        secret.extend_from_slice(&line);
+        secret.push(b'\n');
+    }
+
+    // The session ended before the marker, e.g. on a failed login
+    let mut stderr = String::new();
+    if let Some(mut pipe) = child.stderr.take() {
+        let _ = pipe.read_to_string(&mut stderr).await;
+    }
+    let status = child.wait().await?;
+    match stderr.trim() {
+        "" => bail!(
+            "ssh to {} exited with {} before the shared secret was read",
+            destination,
+            status
+        ),
+        stderr => bail!("ssh to {} failed: {}", destination, stderr),
+    }
+}
+
+/// A new directory only the user can access
+fn temp_dir() -> Result<PathBuf> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "lazypueue-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder
+        .create(&dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    Ok(dir)
+}
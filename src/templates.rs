@@ -0,0 +1,119 @@
+use anyhow::{Context, Result};
+use pueue_lib::task::Task;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A saved command, configured as `[templates.<name>]`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Template {
+    /// Command with `{placeholders}` that are asked for when adding
+    pub command: String,
+    /// Group to add to instead of the selected one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Working directory instead of lazypueue's own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    /// Environment variables set on top of lazypueue's environment
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+impl Template {
+    /// A template re-creating the task. Its environment isn't kept, since
+    /// pueue records the whole environment of the client that added it.
+    pub fn from_task(task: &Task) -> Self {
+        Self {
+            command: task.original_command.clone(),
+            group: Some(task.group.clone()),
+            path: Some(task.path.clone()),
+            label: task.label.clone(),
+            priority: (task.priority != 0).then_some(task.priority),
+            env: BTreeMap::new(),
+        }
+    }
+
+    pub fn placeholders(&self) -> Vec<String> {
+        placeholders(&self.command)
+    }
+}
+
+fn is_placeholder_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Positions of `{name}` placeholders as (start, end, name). Shell syntax
+/// like `${VAR}` or `{ a; b; }` isn't a placeholder.
+fn find_placeholders(command: &str) -> Vec<(usize, usize, &str)> {
+    let mut found = Vec::new();
+    let mut rest = 0;
+    while let Some(open) = command[rest..].find('{').map(|i| rest + i) {
+        rest = open + 1;
+        if command[..open].ends_with('$') {
+            continue;
+        }
+        if let Some(close) = command[open..].find('}').map(|i| open + i) {
+            let name = &command[open + 1..close];
+            if is_placeholder_name(name) {
+                found.push((open, close + 1, name));
+                rest = close + 1;
+            }
+        }
+    }
+    found
+}
+
+/// Distinct placeholder names in order of first appearance
+pub fn placeholders(command: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (_, _, name) in find_placeholders(command) {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Replace every placeholder with its value; unknown ones are kept
+pub fn fill(command: &str, values: &HashMap<String, String>) -> String {
+    let mut filled = String::with_capacity(command.len());
+    let mut last = 0;
+    for (start, end, name) in find_placeholders(command) {
+        filled.push_str(&command[last..start]);
+        match values.get(name) {
+            Some(value) => filled.push_str(value),
+            None => filled.push_str(&command[start..end]),
+        }
+        last = end;
+    }
+    filled.push_str(&command[last..]);
+    filled
+}
+
+/// Append a `[templates.<name>]` table to the config file, keeping the rest
+/// of the file (and its comments) untouched
+pub fn append_to_config(path: &Path, name: &str, template: &Template) -> Result<()> {
+    let table = BTreeMap::from([("templates", BTreeMap::from([(name, template)]))]);
+    let text = toml::to_string(&table).context("Failed to serialize template")?;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    write!(file, "\n{}", text).with_context(|| format!("Failed to write {}", path.display()))
+}
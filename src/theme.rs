@@ -0,0 +1,255 @@
+use pueue_lib::state::GroupStatus;
+use pueue_lib::task::{TaskResult, TaskStatus};
+use ratatui::style::{Color, Modifier, Style};
+
+/// Built-in themes, selected with `general.theme` or `--theme`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+    /// Only bold, dim and reverse video; the default if `NO_COLOR` is set
+    NoColor,
+}
+
+/// Styles of the UI by what they mean rather than by color
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    // Task status
+    pub running: Style,
+    pub queued: Style,
+    pub paused: Style,
+    pub stashed: Style,
+    pub locked: Style,
+    // Result of a finished task
+    pub success: Style,
+    pub failed: Style,
+    pub killed: Style,
+    pub errored: Style,
+    /// The selected row of the task list
+    pub selection: Style,
+    pub group_header: Style,
+    pub group_paused: Style,
+    /// Borders of the panels
+    pub border: Style,
+    /// Border of the panel with the focus
+    pub focused_border: Style,
+    /// Borders of overlays and dialogs
+    pub overlay_border: Style,
+    /// Section titles, e.g. in the help
+    pub heading: Style,
+    /// Secondary text, e.g. timestamps and paths
+    pub muted: Style,
+    /// Active filters and pending operations
+    pub accent: Style,
+    /// Matched characters and partially typed keys
+    pub highlight: Style,
+    pub info: Style,
+    pub warn: Style,
+    pub error: Style,
+    /// The version warning and focused dialog buttons
+    pub banner: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// The configured theme. Without one, `no-color` if `NO_COLOR` is set to
+    /// anything but an empty string, else `dark`.
+    pub fn from_env(name: Option<ThemeName>) -> Self {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        match name {
+            Some(name) => Self::named(name),
+            None if no_color => Self::no_color(),
+            None => Self::dark(),
+        }
+    }
+
+    pub fn named(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Dark => Self::dark(),
+            ThemeName::Light => Self::light(),
+            ThemeName::HighContrast => Self::high_contrast(),
+            ThemeName::NoColor => Self::no_color(),
+        }
+    }
+
+    /// For dark terminal backgrounds
+    pub fn dark() -> Self {
+        let fg = |color| Style::default().fg(color);
+        let bold = Modifier::BOLD;
+        Self {
+            running: fg(Color::Green),
+            queued: fg(Color::Yellow),
+            paused: fg(Color::Cyan),
+            stashed: fg(Color::Gray),
+            locked: fg(Color::Magenta),
+            success: fg(Color::Green),
+            failed: fg(Color::Red),
+            killed: fg(Color::Magenta),
+            errored: fg(Color::Red),
+            selection: Style::default()
+                .fg(Color::Black)
+                .bg(Color::White)
+                .add_modifier(bold),
+            group_header: fg(Color::Cyan).add_modifier(bold),
+            group_paused: fg(Color::Red),
+            border: Style::default(),
+            focused_border: fg(Color::Cyan),
+            overlay_border: fg(Color::Cyan),
+            heading: fg(Color::Cyan).add_modifier(bold),
+            muted: fg(Color::DarkGray),
+            accent: fg(Color::Magenta),
+            highlight: fg(Color::Yellow).add_modifier(bold),
+            info: fg(Color::Blue),
+            warn: fg(Color::Yellow),
+            error: fg(Color::Red).add_modifier(bold),
+            banner: Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(bold),
+        }
+    }
+
+    /// For light terminal backgrounds: no yellow, cyan or light gray text
+    pub fn light() -> Self {
+        let fg = |color| Style::default().fg(color);
+        let bold = Modifier::BOLD;
+        Self {
+            running: fg(Color::Green),
+            queued: fg(Color::Indexed(130)),
+            paused: fg(Color::Blue),
+            stashed: fg(Color::DarkGray),
+            locked: fg(Color::Magenta),
+            success: fg(Color::Green),
+            failed: fg(Color::Red),
+            killed: fg(Color::Magenta),
+            errored: fg(Color::Red),
+            selection: Style::default()
+                .fg(Color::White)
+                .bg(Color::Blue)
+                .add_modifier(bold),
+            group_header: fg(Color::Blue).add_modifier(bold),
+            group_paused: fg(Color::Red),
+            border: Style::default(),
+            focused_border: fg(Color::Blue),
+            overlay_border: fg(Color::Blue),
+            heading: fg(Color::Blue).add_modifier(bold),
+            muted: fg(Color::DarkGray),
+            accent: fg(Color::Magenta),
+            highlight: fg(Color::Indexed(130)).add_modifier(bold),
+            info: fg(Color::Blue),
+            warn: fg(Color::Indexed(130)),
+            error: fg(Color::Red).add_modifier(bold),
+            banner: Style::default()
+                .fg(Color::Black)
+                .bg(Color::Indexed(222))
+                .add_modifier(bold),
+        }
+    }
+
+    /// Bright colors, bold text and no dim gray
+    pub fn high_contrast() -> Self {
+        let fg = |color| Style::default().fg(color).add_modifier(Modifier::BOLD);
+        Self {
+            running: fg(Color::LightGreen),
+            queued: fg(Color::LightYellow),
+            paused: fg(Color::LightCyan),
+            stashed: fg(Color::White),
+            locked: fg(Color::LightMagenta),
+            success: fg(Color::LightGreen),
+            failed: fg(Color::LightRed),
+            killed: fg(Color::LightMagenta),
+            errored: fg(Color::LightRed),
+            selection: Style::default()
+                .fg(Color::Black)
+                .bg(Color::LightYellow)
+                .add_modifier(Modifier::BOLD),
+            group_header: fg(Color::LightCyan).add_modifier(Modifier::UNDERLINED),
+            group_paused: fg(Color::LightRed),
+            border: fg(Color::White),
+            focused_border: fg(Color::LightYellow),
+            overlay_border: fg(Color::LightYellow),
+            heading: fg(Color::LightCyan).add_modifier(Modifier::UNDERLINED),
+            muted: Style::default().fg(Color::White),
+            accent: fg(Color::LightMagenta),
+            highlight: fg(Color::LightYellow).add_modifier(Modifier::UNDERLINED),
+            info: fg(Color::LightCyan),
+            warn: fg(Color::LightYellow),
+            error: fg(Color::LightRed),
+            banner: Style::default()
+                .fg(Color::Black)
+                .bg(Color::LightYellow)
+                .add_modifier(Modifier::BOLD),
+        }
+    }
+
+    /// Text attributes only, for `NO_COLOR` and monochrome terminals
+    pub fn no_color() -> Self {
+        let plain = Style::default();
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let dim = Style::default().add_modifier(Modifier::DIM);
+        let reversed = Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD);
+        Self {
+            running: bold,
+            queued: plain,
+            paused: plain,
+            stashed: dim,
+            locked: plain,
+            success: plain,
+            failed: bold,
+            killed: bold,
+            errored: bold,
+            selection: reversed,
+            group_header: bold,
+            group_paused: bold,
+            border: plain,
+            focused_border: bold,
+            overlay_border: plain,
+            heading: bold.add_modifier(Modifier::UNDERLINED),
+            muted: dim,
+            accent: plain.add_modifier(Modifier::ITALIC),
+            highlight: bold.add_modifier(Modifier::UNDERLINED),
+            info: plain,
+            warn: bold,
+            error: bold,
+            banner: reversed,
+        }
+    }
+
+    pub fn task_status(&self, status: &TaskStatus) -> Style {
+        match status {
+            TaskStatus::Running { .. } => self.running,
+            TaskStatus::Queued { .. } => self.queued,
+            TaskStatus::Paused { .. } => self.paused,
+            TaskStatus::Stashed { .. } => self.stashed,
+            TaskStatus::Locked { .. } => self.locked,
+            TaskStatus::Done { result, .. } => self.task_result(result),
+        }
+    }
+
+    pub fn task_result(&self, result: &TaskResult) -> Style {
+        match result {
+            TaskResult::Success => self.success,
+            TaskResult::Failed(_) | TaskResult::FailedToSpawn(_) | TaskResult::DependencyFailed => {
+                self.failed
+            }
+            TaskResult::Killed => self.killed,
+            TaskResult::Errored => self.errored,
+        }
+    }
+
+    pub fn group_status(&self, status: &GroupStatus) -> Style {
+        match status {
+            GroupStatus::Running => self.running,
+            GroupStatus::Paused => self.group_paused,
+            GroupStatus::Reset => self.queued,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::activity::ActivityKind;
+use crate::app::App;
+use crate::theme::Theme;
+use crate::ui::HitTarget;
+use pueue_lib::task::TaskResult;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+pub fn render_activity_pane(f: &mut Frame, app: &App, area: Rect) {
+    let filter = &app.activity_filter;
+    let mut title = format!(
+        "Activity [{}] (j/k:scroll, t:filter, /:search, q:close)",
+        filter.category.name()
+    );
+    if !filter.query.is_empty() {
+        title = format!("{} /{}", title, filter.query);
+    }
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(app.theme.overlay_border);
+
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let events: Vec<_> = app.activity.filtered(filter).collect();
+    let max_scroll = events.len().saturating_sub(inner_height);
+    app.activity_max_scroll.set(max_scroll);
+    let scroll = app.activity_scroll.min(max_scroll);
+
+    let lines: Vec<Line> = if events.is_empty() {
+        let message = if app.activity.is_empty() {
+            "No activity yet"
+        } else {
+            "No activity matches the filter"
+        };
+        vec![Line::from(Span::styled(message, app.theme.muted))]
+    } else {
+        events
+            .iter()
+            .skip(scroll)
+            .take(inner_height)
+            .map(|event| {
+                Line::from(vec![
+                    Span::styled(event.time.format("%H:%M:%S ").to_string(), app.theme.muted),
+                    Span::styled(event.describe(), event_style(&app.theme, &event.kind)),
+                ])
+            })
+            .collect()
+    };
+
+    let paragraph = Paragraph::new(lines).block(block);
+
+    app.hit_regions
+        .borrow_mut()
+        .push(area, HitTarget::ActivityPane);
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn event_style(theme: &Theme, kind: &ActivityKind) -> Style {
+    match kind {
+        ActivityKind::TaskFinished(task) => match task.result {
+            TaskResult::Success | TaskResult::Killed => theme.task_result(&task.result),
+            _ => theme.task_result(&task.result).add_modifier(Modifier::BOLD),
+        },
+        ActivityKind::TaskAdded { .. } => theme.queued,
+        ActivityKind::TaskStarted { .. } | ActivityKind::TaskResumed { .. } => theme.running,
+        ActivityKind::TaskPaused { .. } => theme.paused,
+        ActivityKind::TaskRemoved { .. } | ActivityKind::TaskStashed { .. } => theme.stashed,
+        kind if kind.is_group_event() => theme.group_header,
+        _ => Style::default(),
+    }
+}
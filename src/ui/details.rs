@@ -1,11 +1,15 @@
-use crate::app::{App, TreeSelection};
+use crate::app::{Action, App, TreeSelection};
+use crate::keymap::Mode;
+use crate::layout::Pane;
+use crate::theme::Theme;
+use crate::ui::{format_keys, pane_border, HitTarget};
 use pueue_lib::state::GroupStatus;
 use pueue_lib::task::TaskStatus;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::Rect,
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
@@ -24,7 +28,7 @@ fn render_group_details(f: &mut Frame, app: &App, name: &str, area: Rect) {
     let state = match &app.state {
         Some(s) => s,
         None => {
-            render_empty(f, area);
+            render_empty(f, app, area);
             return;
         }
     };
@@ -32,7 +36,7 @@ fn render_group_details(f: &mut Frame, app: &App, name: &str, area: Rect) {
     let group = match state.groups.get(name) {
         Some(g) => g,
         None => {
-            render_empty(f, area);
+            render_empty(f, app, area);
             return;
         }
     };
@@ -65,21 +69,22 @@ fn render_group_details(f: &mut Frame, app: &App, name: &str, area: Rect) {
         .filter(|(_, t)| matches!(t.status, TaskStatus::Done { .. }))
         .count();
 
-    let (status_text, status_color) = match group.status {
-        GroupStatus::Running => ("Running", Color::Green),
-        GroupStatus::Paused => ("Paused", Color::Red),
-        GroupStatus::Reset => ("Reset", Color::Yellow),
+    let theme = &app.theme;
+    let status_text = match group.status {
+        GroupStatus::Running => "Running",
+        GroupStatus::Paused => "Paused",
+        GroupStatus::Reset => "Reset",
     };
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(vec![
             Span::styled("Group: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::styled(name, Style::default().fg(Color::Cyan)),
+            Span::styled(name, theme.group_header.remove_modifier(Modifier::BOLD)),
         ]),
         Line::from(""),
         Line::from(vec![
             Span::styled("Status: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::styled(status_text, Style::default().fg(status_color)),
+            Span::styled(status_text, theme.group_status(&group.status)),
         ]),
         Line::from(vec![
             Span::styled(
@@ -96,66 +101,57 @@ fn render_group_details(f: &mut Frame, app: &App, name: &str, area: Rect) {
         Line::from(vec![Span::raw("  Total: "), Span::raw(total.to_string())]),
         Line::from(vec![
             Span::raw("  "),
-            Span::styled(
-                format!("▶ Running: {}", running),
-                Style::default().fg(Color::Green),
-            ),
+            Span::styled(format!("▶ Running: {}", running), theme.running),
         ]),
         Line::from(vec![
             Span::raw("  "),
-            Span::styled(
-                format!("● Queued: {}", queued),
-                Style::default().fg(Color::Yellow),
-            ),
+            Span::styled(format!("● Queued: {}", queued), theme.queued),
         ]),
         Line::from(vec![
             Span::raw("  "),
-            Span::styled(
-                format!("⏸ Paused: {}", paused),
-                Style::default().fg(Color::Cyan),
-            ),
+            Span::styled(format!("⏸ Paused: {}", paused), theme.paused),
         ]),
         Line::from(vec![
             Span::raw("  "),
-            Span::styled(
-                format!("⊡ Stashed: {}", stashed),
-                Style::default().fg(Color::Gray),
-            ),
+            Span::styled(format!("⊡ Stashed: {}", stashed), theme.stashed),
         ]),
         Line::from(vec![
             Span::raw("  "),
-            Span::styled(
-                format!("✓ Done: {}", done),
-                Style::default().fg(Color::DarkGray),
-            ),
+            Span::styled(format!("✓ Done: {}", done), theme.muted),
         ]),
         Line::from(""),
         Line::from(vec![Span::styled(
             "Keybinds:",
             Style::default().add_modifier(Modifier::BOLD),
         )]),
-        Line::from("  p      Pause/resume group"),
-        Line::from("  +/-    Change parallel limit"),
-        Line::from("  a      Add task to group"),
-        Line::from("  c      Clean finished tasks"),
-        Line::from("  l/→    Expand / select first task"),
-        Line::from("  h/←    Collapse group"),
     ];
 
-    let details = Paragraph::new(lines).block(
-        Block::default()
-            .title("Group Details")
-            .borders(Borders::ALL),
-    );
+    // Group keybinds, taken from the active keymap
+    let keybinds: [(&[Action], &str); 6] = [
+        (&[Action::TogglePause], "Pause/resume group"),
+        (
+            &[Action::IncreaseParallel, Action::DecreaseParallel],
+            "Change parallel limit",
+        ),
+        (&[Action::StartAddTask], "Add task to group"),
+        (&[Action::CleanFinished], "Clean finished tasks"),
+        (&[Action::ExpandGroup], "Expand / select first task"),
+        (&[Action::CollapseGroup], "Collapse group"),
+    ];
+    for (actions, description) in keybinds {
+        if let Some(keys) = format_keys(&app.keymap, Mode::Normal, actions) {
+            lines.push(Line::from(format!("  {:<6} {}", keys, description)));
+        }
+    }
 
-    f.render_widget(details, area);
+    render_scrollable(f, app, "Group Details", lines, area);
 }
 
 fn render_task_details(f: &mut Frame, app: &App, task_id: usize, area: Rect) {
     let state = match &app.state {
         Some(s) => s,
         None => {
-            render_empty(f, area);
+            render_empty(f, app, area);
             return;
         }
     };
@@ -163,34 +159,59 @@ fn render_task_details(f: &mut Frame, app: &App, task_id: usize, area: Rect) {
     let task = match state.tasks.get(&task_id) {
         Some(t) => t,
         None => {
-            render_empty(f, area);
+            render_empty(f, app, area);
             return;
         }
     };
 
-    // Split into metadata and output sections (11 lines + 2 for borders = 13)
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Length(13), Constraint::Min(0)])
-        .split(area);
+    // Metadata, then the output section
+    let mut lines = metadata_lines(&app.theme, task_id, task);
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Output:",
+        Style::default().add_modifier(Modifier::BOLD),
+    )));
+    lines.extend(output_lines(task));
 
-    // Render metadata
-    render_metadata(f, task_id, task, chunks[0]);
+    render_scrollable(f, app, "Details", lines, area);
+}
+
+/// Render the lines as the details pane, scrolled by `details_scroll`
+fn render_scrollable(f: &mut Frame, app: &App, title: &str, lines: Vec<Line>, area: Rect) {
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let max_scroll = lines.len().saturating_sub(inner_height);
+    app.details_max_scroll.set(max_scroll);
+    let scroll = app.details_scroll.min(max_scroll);
 
-    // Render output
-    render_output(f, task, chunks[1]);
+    let details = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(pane_border(app, Pane::Details)),
+        )
+        .scroll((scroll as u16, 0));
+
+    app.hit_regions
+        .borrow_mut()
+        .push(area, HitTarget::DetailsPane);
+    f.render_widget(details, area);
 }
 
-fn render_metadata(f: &mut Frame, task_id: usize, task: &pueue_lib::task::Task, area: Rect) {
+fn metadata_lines<'a>(
+    theme: &Theme,
+    task_id: usize,
+    task: &'a pueue_lib::task::Task,
+) -> Vec<Line<'a>> {
     use pueue_lib::task::TaskResult;
 
-    let (status_text, start_time, end_time, duration, exit_code) = match &task.status {
+    let (status_label, start_time, end_time, duration, exit_code) = match &task.status {
         TaskStatus::Running { start, .. } => {
             let start_str = start.format("%Y-%m-%d %H:%M:%S").to_string();
             let dur = chrono::Local::now() - *start;
             let dur_str = format_duration(dur.num_seconds());
             (
-                ("Running", Color::Green),
+                "Running",
                 start_str,
                 "-".to_string(),
                 dur_str,
@@ -202,7 +223,7 @@ fn render_metadata(f: &mut Frame, task_id: usize, task: &pueue_lib::task::Task,
             let dur = chrono::Local::now() - *start;
             let dur_str = format_duration(dur.num_seconds());
             (
-                ("Paused", Color::Cyan),
+                "Paused",
                 start_str,
                 "-".to_string(),
                 dur_str,
@@ -216,43 +237,37 @@ fn render_metadata(f: &mut Frame, task_id: usize, task: &pueue_lib::task::Task,
             let end_str = end.format("%Y-%m-%d %H:%M:%S").to_string();
             let dur = *end - *start;
             let dur_str = format_duration(dur.num_seconds());
-            let (status_label, color) = match result {
-                TaskResult::Success => ("Success", Color::Green),
-                TaskResult::Failed(_) => ("Failed", Color::Red),
-                TaskResult::FailedToSpawn(_) => ("Failed to spawn", Color::Red),
-                TaskResult::Killed => ("Killed", Color::Magenta),
-                TaskResult::Errored => ("Errored", Color::Red),
-                TaskResult::DependencyFailed => ("Dependency failed", Color::Red),
+            let status_label = match result {
+                TaskResult::Success => "Success",
+                TaskResult::Failed(_) => "Failed",
+                TaskResult::FailedToSpawn(_) => "Failed to spawn",
+                TaskResult::Killed => "Killed",
+                TaskResult::Errored => "Errored",
+                TaskResult::DependencyFailed => "Dependency failed",
             };
             let exit_code_str = match result {
                 TaskResult::Failed(code) => code.to_string(),
                 TaskResult::Success => "0".to_string(),
                 _ => "-".to_string(),
             };
-            (
-                (status_label, color),
-                start_str,
-                end_str,
-                dur_str,
-                exit_code_str,
-            )
+            (status_label, start_str, end_str, dur_str, exit_code_str)
         }
         TaskStatus::Queued { .. } => (
-            ("Queued", Color::Yellow),
+            "Queued",
             "-".to_string(),
             "-".to_string(),
             "-".to_string(),
             "-".to_string(),
         ),
         TaskStatus::Stashed { .. } => (
-            ("Stashed", Color::Gray),
+            "Stashed",
             "-".to_string(),
             "-".to_string(),
             "-".to_string(),
             "-".to_string(),
         ),
         TaskStatus::Locked { .. } => (
-            ("Locked", Color::Magenta),
+            "Locked",
             "-".to_string(),
             "-".to_string(),
             "-".to_string(),
@@ -285,7 +300,7 @@ fn render_metadata(f: &mut Frame, task_id: usize, task: &pueue_lib::task::Task,
         path_str.to_string()
     };
 
-    let lines = vec![
+    vec![
         Line::from(vec![
             Span::styled("Task #", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(format!("{}  ", task_id)),
@@ -298,11 +313,11 @@ fn render_metadata(f: &mut Frame, task_id: usize, task: &pueue_lib::task::Task,
         ]),
         Line::from(vec![
             Span::styled("Path: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::styled(path_display, Style::default().fg(Color::DarkGray)),
+            Span::styled(path_display, theme.muted),
         ]),
         Line::from(vec![
             Span::styled("Status: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::styled(status_text.0, Style::default().fg(status_text.1)),
+            Span::styled(status_label, theme.task_status(&task.status)),
             Span::raw("  "),
             Span::styled("Priority: ", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(task.priority.to_string()),
@@ -336,36 +351,27 @@ fn render_metadata(f: &mut Frame, task_id: usize, task: &pueue_lib::task::Task,
             Span::styled("Exit Code: ", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(exit_code),
         ]),
-    ];
-
-    let metadata =
-        Paragraph::new(lines).block(Block::default().title("Details").borders(Borders::ALL));
-
-    f.render_widget(metadata, area);
+    ]
 }
 
-fn render_output(f: &mut Frame, task: &pueue_lib::task::Task, area: Rect) {
-    // For MVP, show a placeholder for output
-    // Full log reading will be implemented in next iteration
-    let output = match &task.status {
-        TaskStatus::Running { .. } => {
-            "Task is running...\n(Press Enter to view full logs)".to_string()
-        }
-        TaskStatus::Done { .. } => "Task completed.\n(Press Enter to view full logs)".to_string(),
-        _ => "No output available yet.".to_string(),
+fn output_lines(task: &pueue_lib::task::Task) -> Vec<Line<'static>> {
+    // The log itself is in the log viewer and the docked log pane
+    let output: &[&str] = match &task.status {
+        TaskStatus::Running { .. } => &["Task is running...", "(Press Enter to view full logs)"],
+        TaskStatus::Done { .. } => &["Task completed.", "(Press Enter to view full logs)"],
+        _ => &["No output available yet."],
     };
-
-    let output_widget = Paragraph::new(output)
-        .block(Block::default().title("Output").borders(Borders::ALL))
-        .wrap(Wrap { trim: false });
-
-    f.render_widget(output_widget, area);
+    output.iter().map(|line| Line::from(*line)).collect()
 }
 
-fn render_empty(f: &mut Frame, area: Rect) {
-    let empty = Paragraph::new("No task selected")
-        .block(Block::default().title("Details").borders(Borders::ALL));
-    f.render_widget(empty, area);
+fn render_empty(f: &mut Frame, app: &App, area: Rect) {
+    render_scrollable(
+        f,
+        app,
+        "Details",
+        vec![Line::from("No task selected")],
+        area,
+    );
 }
 
 fn format_duration(seconds: i64) -> String {
@@ -0,0 +1,82 @@
+use crate::app::App;
+use crate::diagnostics::CheckStatus;
+use crate::theme::Theme;
+use crate::ui::HitTarget;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+fn status_style(theme: &Theme, status: CheckStatus) -> Style {
+    match status {
+        CheckStatus::Ok => theme.success,
+        CheckStatus::Info => theme.info,
+        CheckStatus::Warn => theme.warn,
+        CheckStatus::Fail => theme.error,
+    }
+}
+
+/// Every step of connecting to the shown daemon, with hints for the failed
+/// ones
+pub fn render_diagnostics_pane(f: &mut Frame, app: &App, area: Rect) {
+    let daemon = &app.daemons[app.active_daemon].name;
+    let block = Block::default()
+        .title(format!(
+            "Diagnostics: {} (j/k:scroll, r:rerun, q:close)",
+            daemon
+        ))
+        .borders(Borders::ALL)
+        .border_style(app.theme.overlay_border);
+
+    let lines: Vec<Line> = match &app.diagnostics {
+        None => vec![Line::from(Span::styled("Running checks…", app.theme.muted))],
+        Some(report) => {
+            let mut lines = Vec::new();
+            for check in &report.checks {
+                let style = status_style(&app.theme, check.status);
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{} ", check.status.symbol()), style),
+                    Span::styled(
+                        format!("{:<15} ", check.name),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(check.detail.clone()),
+                ]));
+                if let Some(hint) = &check.hint {
+                    lines.push(Line::from(Span::styled(
+                        format!("  → {}", hint),
+                        app.theme.warn,
+                    )));
+                }
+            }
+            if report.is_ok() {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    "All checks passed",
+                    app.theme.success,
+                )));
+            }
+            lines
+        }
+    };
+
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let max_scroll = lines.len().saturating_sub(inner_height);
+    app.diagnostics_max_scroll.set(max_scroll);
+    let scroll = app.diagnostics_scroll.min(max_scroll);
+
+    app.hit_regions
+        .borrow_mut()
+        .push(area, HitTarget::DiagnosticsPane);
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll as u16, 0)),
+        area,
+    );
+}
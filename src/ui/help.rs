@@ -0,0 +1,63 @@
+use crate::app::App;
+use crate::ui::HitTarget;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Full-screen list of every key binding, generated from the active keymap
+pub fn render_help_overlay(f: &mut Frame, app: &App, area: Rect) {
+    let mut title = "Help (j/k:scroll, /:search, ?/q:close)".to_string();
+    if !app.help_query.is_empty() {
+        title = format!("{} /{}", title, app.help_query);
+    }
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(app.theme.overlay_border);
+
+    let mut lines = Vec::new();
+    for (mode, entries) in app.keymap.help_entries(&app.help_query) {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(mode.title(), app.theme.heading)));
+        for entry in entries {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<20}", entry.keys.join(", ")),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(entry.action.description()),
+                Span::styled(
+                    format!("  {}", entry.action.name().unwrap_or_default()),
+                    app.theme.muted,
+                ),
+            ]));
+        }
+    }
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No key binding matches the search",
+            app.theme.muted,
+        )));
+    }
+
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let max_scroll = lines.len().saturating_sub(inner_height);
+    app.help_max_scroll.set(max_scroll);
+    let scroll = app.help_scroll.min(max_scroll);
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .scroll((scroll as u16, 0));
+
+    app.hit_regions
+        .borrow_mut()
+        .push(area, HitTarget::HelpOverlay);
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
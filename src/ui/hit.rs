@@ -0,0 +1,48 @@
+use crate::app::TreeItem;
+use ratatui::layout::{Position, Rect};
+
+/// Something on screen that reacts to the mouse
+#[derive(Debug, Clone, PartialEq)]
+pub enum HitTarget {
+    /// The task tree, for wheel scrolling
+    TaskList,
+    /// A row of the task tree
+    TreeItem(TreeItem),
+    DetailsPane,
+    /// The docked log pane
+    LogPane,
+    LogModal,
+    ActivityPane,
+    MessagesPane,
+    DiagnosticsPane,
+    HelpOverlay,
+    Palette,
+    ConfirmYes,
+    ConfirmNo,
+}
+
+/// Screen regions recorded by the renderers during the last draw, so mouse
+/// events can be mapped back to what was under the cursor
+#[derive(Debug, Clone, Default)]
+pub struct HitRegions {
+    regions: Vec<(Rect, HitTarget)>,
+}
+
+impl HitRegions {
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+
+    pub fn push(&mut self, area: Rect, target: HitTarget) {
+        self.regions.push((area, target));
+    }
+
+    /// All targets under the given cell, topmost (last drawn) first
+    pub fn hits(&self, column: u16, row: u16) -> impl Iterator<Item = &HitTarget> {
+        self.regions
+            .iter()
+            .rev()
+            .filter(move |(area, _)| area.contains(Position::new(column, row)))
+            .map(|(_, target)| target)
+    }
+}
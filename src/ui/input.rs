@@ -1,17 +1,30 @@
 use ratatui::{
-    layout::Rect,
-    style::{Color, Modifier, Style},
+    layout::{Position, Rect},
+    style::{Modifier, Style},
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
-/// Text input state for add/edit dialogs
+/// Text input state for add/edit dialogs.
+///
+/// The cursor is a byte offset that always sits on a grapheme cluster
+/// boundary, so an accented letter, a CJK character or an emoji is edited as
+/// one unit.
 #[derive(Debug, Clone, Default)]
 pub struct TextInput {
     /// Current input text
     pub value: String,
-    /// Cursor position in the string
+    /// Cursor position in the string, in bytes
     pub cursor: usize,
+    /// Whether the text may span lines, e.g. for heredocs
+    pub multiline: bool,
+}
+
+/// Whether a grapheme counts as part of a word for word motions
+fn is_word(grapheme: &str) -> bool {
+    grapheme.chars().next().is_some_and(char::is_alphanumeric)
 }
 
 impl TextInput {
@@ -21,75 +34,226 @@ impl TextInput {
 
     pub fn with_value(value: String) -> Self {
         let cursor = value.len();
-        Self { value, cursor }
+        Self {
+            value,
+            cursor,
+            multiline: false,
+        }
     }
 
     pub fn insert(&mut self, c: char) {
+        if c == '\n' && !self.multiline {
+            return;
+        }
         self.value.insert(self.cursor, c);
-        self.cursor += 1;
+        self.cursor += c.len_utf8();
     }
 
-    pub fn delete_char(&mut self) {
-        if self.cursor > 0 {
-            self.cursor -= 1;
-            self.value.remove(self.cursor);
+    /// Start a new line at the cursor, if the input is multi-line
+    pub fn insert_newline(&mut self) -> bool {
+        self.multiline && {
+            self.insert('\n');
+            true
         }
     }
 
+    /// Byte offset of the grapheme boundary before the cursor
+    fn prev_boundary(&self) -> usize {
+        self.value[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    /// Byte offset of the grapheme boundary after the cursor
+    fn next_boundary(&self) -> usize {
+        self.value[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(self.cursor, |g| self.cursor + g.len())
+    }
+
+    pub fn delete_char(&mut self) {
+        let start = self.prev_boundary();
+        self.value.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
     pub fn delete_forward(&mut self) {
-        if self.cursor < self.value.len() {
-            self.value.remove(self.cursor);
-        }
+        let end = self.next_boundary();
+        self.value.replace_range(self.cursor..end, "");
     }
 
     pub fn move_left(&mut self) {
-        if self.cursor > 0 {
-            self.cursor -= 1;
-        }
+        self.cursor = self.prev_boundary();
     }
 
     pub fn move_right(&mut self) {
-        if self.cursor < self.value.len() {
-            self.cursor += 1;
-        }
+        self.cursor = self.next_boundary();
     }
 
+    /// Byte offsets of the start and end of the cursor's line
+    fn line_bounds(&self, at: usize) -> (usize, usize) {
+        let start = self.value[..at].rfind('\n').map_or(0, |i| i + 1);
+        let end = self.value[at..]
+            .find('\n')
+            .map_or(self.value.len(), |i| at + i);
+        (start, end)
+    }
+
+    /// Move to the start of the line
     pub fn move_start(&mut self) {
-        self.cursor = 0;
+        self.cursor = self.line_bounds(self.cursor).0;
     }
 
+    /// Move to the end of the line
     pub fn move_end(&mut self) {
-        self.cursor = self.value.len();
+        self.cursor = self.line_bounds(self.cursor).1;
+    }
+
+    /// Move to the previous line, keeping the column. Returns false on the
+    /// first line.
+    pub fn move_up(&mut self) -> bool {
+        let (start, _) = self.line_bounds(self.cursor);
+        if start == 0 {
+            return false;
+        }
+        let column = self.value[start..self.cursor].width();
+        let (prev_start, prev_end) = self.line_bounds(start - 1);
+        self.cursor = self.offset_at_column(prev_start, prev_end, column);
+        true
+    }
+
+    /// Move to the next line, keeping the column. Returns false on the last
+    /// line.
+    pub fn move_down(&mut self) -> bool {
+        let (start, end) = self.line_bounds(self.cursor);
+        if end == self.value.len() {
+            return false;
+        }
+        let column = self.value[start..self.cursor].width();
+        let (next_start, next_end) = self.line_bounds(end + 1);
+        self.cursor = self.offset_at_column(next_start, next_end, column);
+        true
     }
 
+    /// Offset of the last grapheme boundary in the line that doesn't go past
+    /// the display column
+    fn offset_at_column(&self, start: usize, end: usize, column: usize) -> usize {
+        let mut width = 0;
+        for (i, grapheme) in self.value[start..end].grapheme_indices(true) {
+            width += grapheme.width();
+            if width > column {
+                return start + i;
+            }
+        }
+        end
+    }
+
+    /// Empty the input, back to a single line
     pub fn clear(&mut self) {
-        self.value.clear();
-        self.cursor = 0;
+        *self = Self::default();
+    }
+
+    /// Replace the whole text, with the cursor at the end
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.len();
+    }
+
+    /// Replace the text from `start` to the cursor, e.g. with a completion
+    pub fn replace_before_cursor(&mut self, start: usize, text: &str) {
+        self.value.replace_range(start..self.cursor, text);
+        self.cursor = start + text.len();
+    }
+
+    /// Delete the whitespace separated word before the cursor (Ctrl-W)
+    pub fn delete_word(&mut self) {
+        let before = &self.value[..self.cursor];
+        let end = before.trim_end().len();
+        let start = before[..end]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        self.value.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    /// Move to the start of the alphanumeric word before the cursor (Alt-B)
+    pub fn move_word_left(&mut self) {
+        let mut graphemes = self.value[..self.cursor]
+            .grapheme_indices(true)
+            .rev()
+            .skip_while(|(_, g)| !is_word(g))
+            .peekable();
+        let mut start = 0;
+        while let Some((i, _)) = graphemes.next_if(|(_, g)| is_word(g)) {
+            start = i;
+        }
+        // Stopped before a separator, the word starts after it
+        if let Some((i, g)) = graphemes.next() {
+            start = i + g.len();
+        }
+        self.cursor = start;
+    }
+
+    /// Move past the end of the alphanumeric word after the cursor (Alt-F)
+    pub fn move_word_right(&mut self) {
+        let end = self.value[self.cursor..]
+            .grapheme_indices(true)
+            .skip_while(|(_, g)| !is_word(g))
+            .find(|(_, g)| !is_word(g))
+            .map_or(self.value.len(), |(i, _)| self.cursor + i);
+        self.cursor = end;
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.value.split('\n')
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines().count()
+    }
+
+    /// Cursor line and display column, counting wide characters as two
+    /// columns
+    pub fn cursor_position(&self) -> (usize, usize) {
+        let before = &self.value[..self.cursor];
+        let line = before.matches('\n').count();
+        let (start, _) = self.line_bounds(self.cursor);
+        (line, before[start..].width())
     }
 }
 
-/// Render a text input dialog
-pub fn render_input_dialog(f: &mut Frame, title: &str, input: &TextInput, area: Rect) {
+/// Render a text input dialog, scrolled so the cursor stays visible, and
+/// place the terminal cursor in it
+pub fn render_input_dialog(
+    f: &mut Frame,
+    title: &str,
+    input: &TextInput,
+    border: Style,
+    area: Rect,
+) {
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
-
-    // Create text with cursor indicator
-    let text = if input.cursor < input.value.len() {
-        format!(
-            "{}|{}",
-            &input.value[..input.cursor],
-            &input.value[input.cursor..]
-        )
-    } else {
-        format!("{}|", &input.value)
-    };
-
-    let paragraph = Paragraph::new(text)
+        .border_style(border);
+    let inner = block.inner(area);
+
+    let (line, column) = input.cursor_position();
+    let row_offset = (line + 1).saturating_sub(inner.height as usize);
+    let column_offset = (column + 1).saturating_sub(inner.width as usize);
+
+    let paragraph = Paragraph::new(input.value.as_str())
         .block(block)
-        .style(Style::default().add_modifier(Modifier::BOLD));
+        .style(Style::default().add_modifier(Modifier::BOLD))
+        .scroll((row_offset as u16, column_offset as u16));
 
     f.render_widget(Clear, area);
     f.render_widget(paragraph, area);
+    f.set_cursor_position(Position::new(
+        inner.x + (column - column_offset) as u16,
+        inner.y + (line - row_offset) as u16,
+    ));
 }
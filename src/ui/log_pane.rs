@@ -0,0 +1,50 @@
+use crate::app::App;
+use crate::layout::Pane;
+use crate::ui::{pane_border, HitTarget};
+use ratatui::{
+    layout::Rect,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+/// The log of the selected task, docked below the other panes. It keeps to
+/// the end of the output unless scrolled up.
+pub fn render_log_pane(f: &mut Frame, app: &App, area: Rect) {
+    let task_id = app.get_selected_task_id();
+    let content = match (task_id, &app.log_pane) {
+        (None, _) => "No task selected",
+        (Some(id), Some((log_id, content))) if id == *log_id => content.as_str(),
+        (Some(_), _) => "(Loading logs...)",
+    };
+
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let lines: Vec<&str> = content.lines().collect();
+    let max_scroll = lines.len().saturating_sub(inner_height);
+    app.log_pane_max_scroll.set(max_scroll);
+    let following = app.log_pane_scroll >= max_scroll;
+    let scroll = app.log_pane_scroll.min(max_scroll);
+
+    let title = match task_id {
+        Some(id) if following => format!("Log - Task #{} [END]", id),
+        Some(id) => format!("Log - Task #{} ({}/{})", id, scroll + 1, max_scroll + 1),
+        None => "Log".to_string(),
+    };
+    let visible = lines
+        .iter()
+        .skip(scroll)
+        .take(inner_height)
+        .copied()
+        .collect::<Vec<&str>>()
+        .join("\n");
+
+    app.hit_regions.borrow_mut().push(area, HitTarget::LogPane);
+    f.render_widget(
+        Paragraph::new(visible).block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(pane_border(app, Pane::Log)),
+        ),
+        area,
+    );
+}
@@ -0,0 +1,98 @@
+use crate::app::App;
+use crate::messages::MessageLevel;
+use crate::theme::Theme;
+use crate::ui::HitTarget;
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use std::time::Instant;
+
+fn level_style(theme: &Theme, level: MessageLevel) -> Style {
+    match level {
+        MessageLevel::Info => theme.info,
+        MessageLevel::Success => theme.success,
+        MessageLevel::Warn => theme.warn,
+        MessageLevel::Error => theme.error,
+    }
+}
+
+/// Stack of unexpired toasts in the bottom right corner of `area`, newest at
+/// the bottom
+pub fn render_toasts(f: &mut Frame, app: &App, area: Rect) {
+    let toasts = app.messages.toasts(Instant::now());
+    let width = area.width.min(50);
+    let mut bottom = area.bottom();
+
+    for message in toasts.iter().rev() {
+        // Wrap long messages over up to 4 lines
+        let text_width = width.saturating_sub(2).max(1) as usize;
+        let lines = message
+            .text
+            .chars()
+            .count()
+            .div_ceil(text_width)
+            .clamp(1, 4) as u16;
+        let height = lines + 2;
+        if bottom < area.y + height {
+            break;
+        }
+        let toast_area = Rect::new(area.right() - width, bottom - height, width, height);
+        bottom -= height;
+
+        let style = level_style(&app.theme, message.level);
+        let block = Block::default()
+            .title(message.level.name())
+            .borders(Borders::ALL)
+            .border_style(style);
+        let paragraph = Paragraph::new(Line::from(Span::styled(message.text.clone(), style)))
+            .block(block)
+            .wrap(Wrap { trim: true });
+
+        f.render_widget(Clear, toast_area);
+        f.render_widget(paragraph, toast_area);
+    }
+}
+
+/// Every message of this session, newest first
+pub fn render_messages_pane(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .title("Messages (j/k:scroll, q:close)")
+        .borders(Borders::ALL)
+        .border_style(app.theme.overlay_border);
+
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let max_scroll = app.messages.len().saturating_sub(inner_height);
+    app.messages_max_scroll.set(max_scroll);
+    let scroll = app.messages_scroll.min(max_scroll);
+
+    let lines: Vec<Line> = if app.messages.is_empty() {
+        vec![Line::from(Span::styled("No messages yet", app.theme.muted))]
+    } else {
+        app.messages
+            .history()
+            .skip(scroll)
+            .take(inner_height)
+            .map(|message| {
+                let style = level_style(&app.theme, message.level);
+                Line::from(vec![
+                    Span::styled(
+                        message.time.format("%H:%M:%S ").to_string(),
+                        app.theme.muted,
+                    ),
+                    Span::styled(format!("{:<8}", message.level.name()), style),
+                    Span::styled(message.text.clone(), style),
+                ])
+            })
+            .collect()
+    };
+
+    app.hit_regions
+        .borrow_mut()
+        .push(area, HitTarget::MessagesPane);
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
@@ -1,75 +1,210 @@
+mod activity;
 mod details;
+mod diagnostics;
+mod help;
+mod hit;
 pub mod input;
+mod log_pane;
+mod messages;
+mod palette;
 mod status_bar;
 mod task_list;
 
+pub use activity::render_activity_pane;
 pub use details::render_details_panel;
+pub use diagnostics::render_diagnostics_pane;
+pub use help::render_help_overlay;
+pub use hit::{HitRegions, HitTarget};
 pub use input::{render_input_dialog, TextInput};
-pub use status_bar::{render_help_bar, render_status_bar};
-pub use task_list::render_task_list;
-
-use crate::app::App;
+pub use log_pane::render_log_pane;
+pub use messages::{render_messages_pane, render_toasts};
+pub use palette::render_command_palette;
+pub use status_bar::{format_keys, render_help_bar, render_status_bar};
+pub use task_list::{render_task_list, scroll_offset};
+
+use crate::app::{Action, App, InputMode, Overlay};
+use crate::keymap::Mode;
+use crate::layout::Pane;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::{Modifier, Style},
     widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
 
+/// Lines shown by the input dialog before it scrolls
+const MAX_INPUT_LINES: usize = 10;
+
 pub fn render(f: &mut Frame, app: &App) {
+    app.hit_regions.borrow_mut().clear();
+
     // Check for error message
-    if let Some(error) = &app.error_message {
-        render_error(f, error);
+    // Daemons of other versions are tried anyway, with a warning
+    let version_warning = app.daemons[app.active_daemon]
+        .version
+        .as_ref()
+        .and_then(|version| version.warning());
+
+    if let Some(error) = &app.connection_error {
+        let mut text = error.clone();
+        if let Some(warning) = &version_warning {
+            text.push_str(&format!("\n\n{}", warning));
+        }
+        let diagnose = app
+            .keymap
+            .keys_for(Mode::Normal, &Action::ToggleDiagnostics);
+        if let Some(key) = diagnose.first() {
+            text.push_str(&format!("\n\nPress {} to diagnose the connection.", key));
+        }
+        // Other daemons are still reachable from the error screen
+        let next = app.keymap.keys_for(Mode::Normal, &Action::NextDaemon);
+        if let Some(key) = next.first().filter(|_| app.daemons.len() > 1) {
+            text.push_str(&format!("\nPress {} to switch to the next daemon.", key));
+        }
+        render_error(f, app, &text);
+        if app.is_open(Mode::Diagnostics) {
+            render_diagnostics_pane(f, app, centered_rect(80, 80, f.area()));
+        }
         return;
     }
 
-    // Main layout
-    let chunks = Layout::default()
+    // Main layout, with the version warning above the status bar
+    let area = match &version_warning {
+        Some(warning) => {
+            let [banner, rest] = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .areas(f.area());
+            f.render_widget(
+                Paragraph::new(format!("⚠ {}", warning)).style(app.theme.banner),
+                banner,
+            );
+            rest
+        }
+        None => f.area(),
+    };
+    let layout = &app.config.layout;
+    let bar_height = |show: bool| if show { 3 } else { 0 };
+    let [status_area, main_area, help_area] = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // Status bar
-            Constraint::Min(0),    // Main content
-            Constraint::Length(3), // Help bar
+            Constraint::Length(bar_height(layout.show_status_bar)),
+            Constraint::Min(0),
+            Constraint::Length(bar_height(layout.show_help_bar)),
         ])
-        .split(f.area());
+        .areas(area);
 
-    // Render status bar
-    render_status_bar(f, app, chunks[0]);
+    if layout.show_status_bar {
+        render_status_bar(f, app, status_area);
+    }
 
-    // Split main content into task list and details
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
-        .split(chunks[1]);
+    // Render the focused pane alone when zoomed, else split main content
+    // into task list and details, with the log docked below
+    if app.zoomed {
+        render_pane(f, app, app.focus, main_area);
+    } else {
+        let log_percent = if layout.show_log_pane { 40 } else { 0 };
+        let [panes_area, log_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(100 - log_percent),
+                Constraint::Percentage(log_percent),
+            ])
+            .areas(main_area);
+        let [list_area, details_area] = Layout::default()
+            .direction(layout.preset.direction(area.width, layout.vertical_below))
+            .constraints([
+                Constraint::Percentage(layout.task_list_percent),
+                Constraint::Percentage(100 - layout.task_list_percent),
+            ])
+            .areas(panes_area);
+        render_pane(f, app, Pane::TaskList, list_area);
+        render_pane(f, app, Pane::Details, details_area);
+        if layout.show_log_pane {
+            render_pane(f, app, Pane::Log, log_area);
+        }
+    }
+
+    if layout.show_help_bar {
+        render_help_bar(f, app, help_area);
+    }
 
-    // Render task list and details
-    render_task_list(f, app, main_chunks[0]);
-    render_details_panel(f, app, main_chunks[1]);
+    // Dialogs and modal views, bottom to top
+    for overlay in &app.overlays {
+        render_overlay(f, app, overlay);
+    }
 
-    // Render help bar
-    render_help_bar(f, chunks[2]);
+    // Toasts stay on top, above the help bar
+    render_toasts(f, app, main_area);
+}
 
-    // Render log modal if active
-    if app.show_log_modal {
-        render_log_modal(f, app);
+fn render_pane(f: &mut Frame, app: &App, pane: Pane, area: Rect) {
+    match pane {
+        Pane::TaskList => render_task_list(f, app, area),
+        Pane::Details => render_details_panel(f, app, area),
+        Pane::Log => render_log_pane(f, app, area),
     }
+}
 
-    // Render input dialog if in input mode
-    if let Some(input_mode) = &app.input_mode {
-        let title = match input_mode {
-            crate::app::InputMode::AddTask => "Add Task (Enter: submit, Esc: cancel)",
-            crate::app::InputMode::EditTask(_) => "Edit Task (Enter: submit, Esc: cancel)",
-        };
-        let area = input_dialog_rect(f.area());
-        render_input_dialog(f, title, &app.text_input, area);
+/// Border of a pane, highlighted while it has the focus
+pub fn pane_border(app: &App, pane: Pane) -> Style {
+    if app.focus == pane && !app.zoomed {
+        app.theme.focused_border
+    } else {
+        app.theme.border
     }
+}
 
-    // Render delete confirmation dialog
-    if let Some(task_id) = app.confirm_delete {
-        render_confirm_dialog(f, app, task_id);
+fn render_overlay(f: &mut Frame, app: &App, overlay: &Overlay) {
+    match overlay {
+        Overlay::Log => render_log_modal(f, app),
+        Overlay::Activity => render_activity_pane(f, app, centered_rect(80, 80, f.area())),
+        Overlay::Messages => render_messages_pane(f, app, centered_rect(80, 80, f.area())),
+        Overlay::Diagnostics => render_diagnostics_pane(f, app, centered_rect(80, 80, f.area())),
+        Overlay::Help => render_help_overlay(f, app, centered_rect(90, 90, f.area())),
+        Overlay::Palette => render_command_palette(f, app, centered_rect(70, 70, f.area())),
+        Overlay::Input(input_mode) => render_input(f, app, input_mode),
+        Overlay::ConfirmDelete(task_id) => render_confirm_dialog(f, app, *task_id),
     }
 }
 
+fn render_input(f: &mut Frame, app: &App, input_mode: &InputMode) {
+    let title = match (input_mode, &app.history_search) {
+        (_, Some(search)) => format!(
+            "History Search: {}{} (Ctrl-R: older, Enter: accept, Esc: cancel)",
+            search.query,
+            if search.found.is_none() && !search.query.is_empty() {
+                " (no match)"
+            } else {
+                ""
+            }
+        ),
+        (InputMode::AddTask, _) => {
+            "Add Task (Enter: submit, Alt-Enter: new line, Esc: cancel)".to_string()
+        }
+        (InputMode::TemplateValue(fill), _) => format!(
+            "{}: {{{}}} ({}/{}) (Enter: next, Esc: cancel)",
+            fill.name,
+            fill.current().unwrap_or_default(),
+            fill.values.len() + 1,
+            fill.placeholders.len()
+        ),
+        (InputMode::EditTask(_), _) => {
+            "Edit Task (Enter: submit, Alt-Enter: new line, Esc: cancel)".to_string()
+        }
+        (InputMode::SaveTemplate(task_id), _) => {
+            format!("Save Task #{} as Template (name)", task_id)
+        }
+        (InputMode::TaskFilter, _) => {
+            "Filter Tasks, e.g. status:failed group:ci (Enter: apply, Esc: cancel)".to_string()
+        }
+        (InputMode::ActivitySearch, _) => "Filter Activity (Enter: apply, Esc: cancel)".to_string(),
+        (InputMode::HelpSearch, _) => "Search Help (Enter: apply, Esc: cancel)".to_string(),
+    };
+    let area = input_dialog_rect(f.area(), app.text_input.line_count());
+    render_input_dialog(f, &title, &app.text_input, app.theme.overlay_border, area);
+}
+
 fn render_confirm_dialog(f: &mut Frame, app: &App, task_id: usize) {
     let command = app
         .state
@@ -85,15 +220,12 @@ fn render_confirm_dialog(f: &mut Frame, app: &App, task_id: usize) {
         command.to_string()
     };
 
-    let text = format!(
-        "Delete task #{}?\n\n  {}\n\n(y)es  (n)o",
-        task_id, display_cmd
-    );
+    let text = format!("Delete task #{}?\n\n  {}", task_id, display_cmd);
 
     let confirm_block = Block::default()
         .title("Confirm Delete")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow));
+        .border_style(app.theme.warn);
 
     let confirm_text = Paragraph::new(text)
         .block(confirm_block)
@@ -102,6 +234,26 @@ fn render_confirm_dialog(f: &mut Frame, app: &App, task_id: usize) {
     let area = confirm_dialog_rect(f.area());
     f.render_widget(Clear, area);
     f.render_widget(confirm_text, area);
+    render_confirm_buttons(f, app, area);
+}
+
+/// Clickable "[ Yes (y) ]  [ No (n) ]" buttons on the last line inside the
+/// dialog, which also name the keys
+fn render_confirm_buttons(f: &mut Frame, app: &App, area: Rect) {
+    const YES: &str = "[ Yes (y) ]";
+    const NO: &str = "[ No (n) ]";
+
+    let row = area.y + area.height.saturating_sub(2);
+    let yes_area = Rect::new(area.x + 2, row, YES.len() as u16, 1).intersection(area);
+    let no_area = Rect::new(yes_area.right() + 2, row, NO.len() as u16, 1).intersection(area);
+
+    let button = app.theme.banner;
+    f.render_widget(Paragraph::new(YES).style(button), yes_area);
+    f.render_widget(Paragraph::new(NO).style(button), no_area);
+
+    let mut regions = app.hit_regions.borrow_mut();
+    regions.push(yes_area, HitTarget::ConfirmYes);
+    regions.push(no_area, HitTarget::ConfirmNo);
 }
 
 fn confirm_dialog_rect(r: Rect) -> Rect {
@@ -125,11 +277,11 @@ fn confirm_dialog_rect(r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
-fn render_error(f: &mut Frame, error: &str) {
+fn render_error(f: &mut Frame, app: &App, error: &str) {
     let error_block = Block::default()
         .title("Error")
         .borders(Borders::ALL)
-        .style(Style::default().fg(Color::Red));
+        .style(app.theme.error.remove_modifier(Modifier::BOLD));
 
     let error_text = Paragraph::new(error)
         .block(error_block)
@@ -152,9 +304,9 @@ fn render_log_modal(f: &mut Frame, app: &App) {
             .title(title)
             .borders(Borders::ALL)
             .border_style(if app.follow_mode {
-                Style::default().fg(Color::Green)
+                app.theme.running
             } else {
-                Style::default()
+                app.theme.border
             });
 
         let output = app.log_content.as_deref().unwrap_or("(Loading logs...)");
@@ -167,12 +319,13 @@ fn render_log_modal(f: &mut Frame, app: &App) {
         let lines: Vec<&str> = output.lines().collect();
         let total_lines = lines.len();
 
-        // Calculate scroll position
-        let scroll = if app.follow_mode || app.log_scroll == usize::MAX {
-            // Follow mode: show the last lines
-            total_lines.saturating_sub(inner_height)
+        // Calculate scroll position; follow mode shows the last lines
+        let max_scroll = total_lines.saturating_sub(inner_height);
+        app.log_max_scroll.set(max_scroll);
+        let scroll = if app.follow_mode {
+            max_scroll
         } else {
-            app.log_scroll.min(total_lines.saturating_sub(inner_height))
+            app.log_scroll.min(max_scroll)
         };
 
         // Get visible lines
@@ -185,6 +338,7 @@ fn render_log_modal(f: &mut Frame, app: &App) {
             .join("\n");
 
         let log_text = Paragraph::new(visible_lines).block(log_block);
+        app.hit_regions.borrow_mut().push(area, HitTarget::LogModal);
 
         f.render_widget(Clear, area);
         f.render_widget(log_text, area);
@@ -211,13 +365,14 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
-fn input_dialog_rect(r: Rect) -> Rect {
-    // Create a centered dialog that's 80% wide and 3 lines tall
+fn input_dialog_rect(r: Rect, lines: usize) -> Rect {
+    // Create a centered dialog that's 80% wide and grows with the lines
+    let height = lines.clamp(1, MAX_INPUT_LINES) as u16 + 2;
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Percentage(40),
-            Constraint::Length(3),
+            Constraint::Length(height),
             Constraint::Percentage(60),
         ])
         .split(r);
@@ -0,0 +1,98 @@
+use crate::app::App;
+use crate::palette::{palette_matches, PaletteScope};
+use crate::ui::{render_input_dialog, HitTarget};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Query input with the fuzzy-matched commands below it
+pub fn render_command_palette(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    let title = match app.palette_scope {
+        PaletteScope::Commands => "Command Palette (Enter: run, Esc: close)".to_string(),
+        PaletteScope::MoveToGroup => format!(
+            "Move {} task(s) to group (Enter: move, Esc: cancel)",
+            app.move_targets().len()
+        ),
+        PaletteScope::Undo => "Undo (Enter: undo, Esc: close)".to_string(),
+        PaletteScope::Templates => "Add Task (Enter: choose, Esc: cancel)".to_string(),
+    };
+    render_input_dialog(
+        f,
+        &title,
+        &app.text_input,
+        app.theme.overlay_border,
+        chunks[0],
+    );
+
+    let matches = palette_matches(app);
+    let inner_height = chunks[1].height.saturating_sub(2) as usize;
+    let inner_width = chunks[1].width.saturating_sub(2) as usize;
+    let selected = app.palette_selected.min(matches.len().saturating_sub(1));
+    // Keep the selected entry in view
+    let offset = (selected + 1).saturating_sub(inner_height);
+
+    let mut lines = Vec::new();
+    for (index, m) in matches.iter().enumerate().skip(offset).take(inner_height) {
+        let is_selected = index == selected;
+        let base = if m.entry.action.is_none() {
+            app.theme.muted
+        } else {
+            Style::default()
+        };
+        let base = if is_selected {
+            base.add_modifier(Modifier::REVERSED)
+        } else {
+            base
+        };
+        let highlight = base.patch(app.theme.highlight);
+
+        let mut spans: Vec<Span> = m
+            .entry
+            .label
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let style = if m.positions.contains(&i) {
+                    highlight
+                } else {
+                    base
+                };
+                Span::styled(c.to_string(), style)
+            })
+            .collect();
+
+        // Right-align the key binding
+        let keys = m.entry.keys.clone().unwrap_or_default();
+        let used = m.entry.label.chars().count() + keys.chars().count();
+        spans.push(Span::styled(
+            " ".repeat(inner_width.saturating_sub(used).max(1)),
+            base,
+        ));
+        spans.push(Span::styled(keys, base.patch(app.theme.heading)));
+        lines.push(Line::from(spans));
+    }
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No command matches",
+            app.theme.muted,
+        )));
+    }
+
+    let block = Block::default()
+        .title(format!("Commands ({})", matches.len()))
+        .borders(Borders::ALL)
+        .border_style(app.theme.overlay_border);
+
+    app.hit_regions.borrow_mut().push(area, HitTarget::Palette);
+    f.render_widget(Clear, chunks[1]);
+    f.render_widget(Paragraph::new(lines).block(block), chunks[1]);
+}
@@ -1,15 +1,22 @@
-use crate::app::App;
+use crate::app::{Action, App};
+use crate::daemon::Connection;
+use crate::filter::SortMode;
+use crate::keymap::{Keymap, Mode};
 use pueue_lib::task::TaskStatus;
 use ratatui::{
     layout::Rect,
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
+/// Frames of the spinner shown while actions wait for the daemon
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
 pub fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
-    let status_text = if let Some(state) = &app.state {
+    let theme = &app.theme;
+    let mut status_text = if let Some(state) = &app.state {
         // Get all tasks for overall stats
         let all_tasks = app.get_task_list();
         let running_count = all_tasks
@@ -28,15 +35,9 @@ pub fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
             Span::raw(format!("{}", group_count)),
             Span::raw(" | "),
             Span::styled("Tasks: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::styled(
-                format!("{} run", running_count),
-                Style::default().fg(Color::Green),
-            ),
+            Span::styled(format!("{} run", running_count), theme.running),
             Span::raw("/"),
-            Span::styled(
-                format!("{} queue", queued_count),
-                Style::default().fg(Color::Yellow),
-            ),
+            Span::styled(format!("{} queue", queued_count), theme.queued),
             Span::raw("/"),
             Span::raw(format!("{} total", total_count)),
         ])
@@ -44,40 +45,229 @@ pub fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
         Line::from("Connecting to pueue daemon...")
     };
 
-    let status =
-        Paragraph::new(status_text).block(Block::default().title("Status").borders(Borders::ALL));
+    // Connection status of every daemon, the shown one highlighted
+    if app.daemons.len() > 1 {
+        let mut spans = daemon_spans(app);
+        spans.push(Span::raw(" | "));
+        status_text.spans.splice(0..0, spans);
+    }
+
+    // Filter and order of the task list, unless they're the defaults
+    if !app.task_filter.is_empty() {
+        status_text.push_span(Span::raw(" | "));
+        status_text.push_span(Span::styled(
+            format!("filter: {}", app.task_filter),
+            theme.accent,
+        ));
+    }
+    if app.sort_mode != SortMode::default() {
+        status_text.push_span(Span::raw(" | "));
+        status_text.push_span(Span::styled(
+            format!("sort: {}", app.sort_mode.name()),
+            theme.accent,
+        ));
+    }
+
+    // Oldest in-flight action, with a count of the others
+    let mut pending = app.pending_labels();
+    if let Some(label) = pending.next() {
+        let others = pending.count();
+        let style = theme.accent;
+        status_text.push_span(Span::raw(" | "));
+        status_text.push_span(Span::styled(
+            format!("{} {}", SPINNER[app.spinner_frame % SPINNER.len()], label),
+            style,
+        ));
+        if others > 0 {
+            status_text.push_span(Span::styled(format!(" (+{})", others), style));
+        }
+    }
+
+    let status = Paragraph::new(status_text).block(
+        Block::default()
+            .title("Status")
+            .borders(Borders::ALL)
+            .border_style(theme.border),
+    );
 
     f.render_widget(status, area);
 }
 
-pub fn render_help_bar(f: &mut Frame, area: Rect) {
-    let help_text = Line::from(vec![
-        Span::styled("j/k", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":nav "),
-        Span::styled("h/l", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":fold "),
-        Span::styled("a", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":add "),
-        Span::styled("d", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":del "),
-        Span::styled("e", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":edit "),
-        Span::styled("s/S", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":stash/enq "),
-        Span::styled("Space", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":pause "),
-        Span::styled("K", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":kill "),
-        Span::styled("R", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":restart "),
-        Span::styled("+/-", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":parallel "),
-        Span::styled("q", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(":quit"),
-    ]);
-
-    let help =
-        Paragraph::new(help_text).block(Block::default().title("Help").borders(Borders::ALL));
+/// "● local ○ build ✗ ci": connected, connecting and unreachable daemons
+fn daemon_spans(app: &App) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    for (index, daemon) in app.daemons.iter().enumerate() {
+        if index > 0 {
+            spans.push(Span::raw(" "));
+        }
+        let (symbol, style) = match daemon.connection {
+            Connection::Connecting => ("○", app.theme.muted),
+            Connection::Connected => ("●", app.theme.success),
+            Connection::Failed(_) => ("✗", app.theme.failed),
+        };
+        spans.push(Span::styled(format!("{} ", symbol), style));
+        let style = if index == app.active_daemon {
+            Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else {
+            Style::default()
+        };
+        spans.push(Span::styled(daemon.name.clone(), style));
+    }
+    spans
+}
+
+/// Actions shown in the help bar per mode, with a short label.
+/// Keys are taken from the active keymap; unbound entries are skipped.
+fn help_bar_entries(mode: Mode) -> &'static [(&'static [Action], &'static str)] {
+    match mode {
+        Mode::Normal => &[
+            (&[Action::NavigateDown, Action::NavigateUp], "nav"),
+            (&[Action::CollapseGroup, Action::ExpandGroup], "fold"),
+            (&[Action::StartAddTask], "add"),
+            (&[Action::RemoveTask], "del"),
+            (&[Action::StartEditTask], "edit"),
+            (&[Action::StashTask, Action::EnqueueTask], "stash/enq"),
+            (&[Action::ToggleTaskPause], "pause"),
+            (&[Action::KillTask], "kill"),
+            (&[Action::RestartTask], "restart"),
+            (&[Action::Undo], "undo"),
+            (&[Action::ToggleActivity], "activity"),
+            (&[Action::FocusNext], "panes"),
+            (
+                &[Action::IncreaseParallel, Action::DecreaseParallel],
+                "parallel",
+            ),
+            (&[Action::OpenPalette], "commands"),
+            (&[Action::ToggleHelp], "help"),
+            (&[Action::Quit], "quit"),
+        ],
+        Mode::Details => &[
+            (
+                &[Action::ScrollDetailsDown, Action::ScrollDetailsUp],
+                "scroll",
+            ),
+            (&[Action::FocusNext], "next pane"),
+            (&[Action::ToggleZoom], "zoom"),
+            (&[Action::FocusTaskList], "tasks"),
+            (&[Action::ToggleHelp], "help"),
+        ],
+        Mode::LogPane => &[
+            (
+                &[Action::ScrollLogPaneDown, Action::ScrollLogPaneUp],
+                "scroll",
+            ),
+            (&[Action::ScrollLogPaneBottom], "follow"),
+            (&[Action::ViewLogs], "full log"),
+            (&[Action::FocusNext], "next pane"),
+            (&[Action::ToggleZoom], "zoom"),
+            (&[Action::ToggleLogPane], "hide"),
+            (&[Action::FocusTaskList], "tasks"),
+        ],
+        Mode::Log => &[
+            (&[Action::ScrollLogDown, Action::ScrollLogUp], "scroll"),
+            (
+                &[Action::ScrollLogPageDown, Action::ScrollLogPageUp],
+                "page",
+            ),
+            (
+                &[Action::ScrollLogTop, Action::ScrollLogBottom],
+                "top/bottom",
+            ),
+            (&[Action::FollowLogs], "follow"),
+            (&[Action::CloseLogs], "close"),
+        ],
+        Mode::Activity => &[
+            (
+                &[Action::ScrollActivityDown, Action::ScrollActivityUp],
+                "scroll",
+            ),
+            (&[Action::CycleActivityFilter], "filter"),
+            (&[Action::StartActivitySearch], "search"),
+            (&[Action::CloseActivity], "close"),
+        ],
+        Mode::Input => &[
+            (&[Action::SubmitInput], "submit"),
+            (&[Action::CancelInput], "cancel"),
+            (&[Action::InputHome, Action::InputEnd], "start/end"),
+        ],
+        Mode::Confirm => &[
+            (&[Action::ConfirmAction], "confirm"),
+            (&[Action::CancelConfirm], "cancel"),
+        ],
+        Mode::Help => &[
+            (&[Action::ScrollHelpDown, Action::ScrollHelpUp], "scroll"),
+            (&[Action::StartHelpSearch], "search"),
+            (&[Action::CloseHelp], "close"),
+        ],
+        Mode::Messages => &[
+            (
+                &[Action::ScrollMessagesDown, Action::ScrollMessagesUp],
+                "scroll",
+            ),
+            (&[Action::CloseMessages], "close"),
+        ],
+        Mode::Diagnostics => &[
+            (
+                &[Action::ScrollDiagnosticsDown, Action::ScrollDiagnosticsUp],
+                "scroll",
+            ),
+            (&[Action::RunDiagnostics], "rerun"),
+            (&[Action::CloseDiagnostics], "close"),
+        ],
+        Mode::Palette => &[
+            (&[Action::PaletteNext, Action::PalettePrev], "select"),
+            (&[Action::ExecutePalette], "run"),
+            (&[Action::ClosePalette], "close"),
+        ],
+    }
+}
+
+/// First key bound to each action, joined with "/", e.g. "j/k"
+pub fn format_keys(keymap: &Keymap, mode: Mode, actions: &[Action]) -> Option<String> {
+    let keys: Vec<String> = actions
+        .iter()
+        .filter_map(|action| keymap.keys_for(mode, action).first().map(|k| k.to_string()))
+        .collect();
+    if keys.is_empty() {
+        None
+    } else {
+        Some(keys.join("/"))
+    }
+}
+
+pub fn render_help_bar(f: &mut Frame, app: &App, area: Rect) {
+    let mode = app.mode();
+    let mut spans = Vec::new();
+
+    // Show a partially typed key sequence, like vim's showcmd
+    let pending = app.keymap.pending();
+    if !pending.is_empty() {
+        let typed: Vec<String> = pending.iter().map(|c| c.to_string()).collect();
+        spans.push(Span::styled(
+            format!("{}- ", typed.join(" ")),
+            app.theme.highlight,
+        ));
+    }
+
+    for (actions, label) in help_bar_entries(mode) {
+        if let Some(keys) = format_keys(&app.keymap, mode, actions) {
+            if spans.len() > usize::from(!pending.is_empty()) {
+                spans.push(Span::raw(" "));
+            }
+            spans.push(Span::styled(
+                keys,
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::raw(format!(":{}", label)));
+        }
+    }
+
+    let help = Paragraph::new(Line::from(spans)).block(
+        Block::default()
+            .title("Help")
+            .borders(Borders::ALL)
+            .border_style(app.theme.border),
+    );
 
     f.render_widget(help, area);
 }
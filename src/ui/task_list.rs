@@ -1,45 +1,75 @@
 use crate::app::{App, TreeItem, TreeSelection};
+use crate::layout::Pane;
+use crate::theme::Theme;
+use crate::ui::{pane_border, HitTarget};
 use pueue_lib::state::GroupStatus;
 use pueue_lib::task::TaskStatus;
 use ratatui::{
-    layout::Rect,
-    style::{Color, Modifier, Style},
+    layout::{Margin, Rect},
+    style::Modifier,
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem},
+    widgets::{Block, Borders, List, ListItem, Scrollbar, ScrollbarOrientation, ScrollbarState},
     Frame,
 };
+use std::collections::HashMap;
 
 pub fn render_task_list(f: &mut Frame, app: &App, area: Rect) {
     let tree_items = app.get_tree_items();
     let state = match &app.state {
         Some(s) => s,
         None => {
-            let list = List::new::<Vec<ListItem>>(vec![])
-                .block(Block::default().title("Tasks").borders(Borders::ALL));
+            let list = List::new::<Vec<ListItem>>(vec![]).block(
+                Block::default()
+                    .title("Tasks")
+                    .borders(Borders::ALL)
+                    .border_style(pane_border(app, Pane::TaskList)),
+            );
             f.render_widget(list, area);
             return;
         }
     };
 
-    let items: Vec<ListItem> = tree_items
+    let inner = area.inner(Margin::new(1, 1));
+    let height = inner.height as usize;
+    let selected = tree_items.iter().position(|item| is_selected(app, item));
+    let offset = scroll_offset(
+        app.task_list_offset.get(),
+        selected,
+        tree_items.len(),
+        height,
+    );
+    app.task_list_offset.set(offset);
+    app.task_list_height.set(height);
+
+    // Running/total task counts per group, counted once per frame
+    let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+    for task in state.tasks.values() {
+        let (running, total) = counts.entry(&task.group).or_default();
+        *total += 1;
+        if matches!(task.status, TaskStatus::Running { .. }) {
+            *running += 1;
+        }
+    }
+
+    // Only build the rows that are visible
+    let visible = &tree_items[offset..(offset + height).min(tree_items.len())];
+    let items: Vec<ListItem> = visible
         .iter()
         .map(|item| {
-            let is_selected = match (&app.selection, item) {
-                (TreeSelection::Group(a), TreeItem::Group(b)) => a == b,
-                (TreeSelection::Task(g1, t1), TreeItem::Task(g2, t2)) => g1 == g2 && t1 == t2,
-                _ => false,
-            };
-
+            let is_selected = is_selected(app, item);
             match item {
                 TreeItem::Group(name) => render_group_item(
+                    &app.theme,
                     state,
                     name,
+                    counts.get(name.as_str()).copied().unwrap_or_default(),
                     app.collapsed_groups.contains(name),
                     is_selected,
                 ),
                 TreeItem::Task(_group, task_id) => {
                     if let Some(task) = state.tasks.get(task_id) {
-                        render_task_item(*task_id, task, is_selected)
+                        let is_marked = app.marked_tasks.contains(task_id);
+                        render_task_item(&app.theme, *task_id, task, is_selected, is_marked)
                     } else {
                         ListItem::new(Line::from(format!("  ? #{} (unknown)", task_id)))
                     }
@@ -48,52 +78,87 @@ pub fn render_task_list(f: &mut Frame, app: &App, area: Rect) {
         })
         .collect();
 
-    let list = List::new(items).block(Block::default().title("Tasks").borders(Borders::ALL));
+    let list = List::new(items).block(
+        Block::default()
+            .title("Tasks")
+            .borders(Borders::ALL)
+            .border_style(pane_border(app, Pane::TaskList)),
+    );
 
     f.render_widget(list, area);
+
+    if tree_items.len() > height {
+        let mut scrollbar_state = ScrollbarState::new(tree_items.len().saturating_sub(height))
+            .position(offset)
+            .viewport_content_length(height);
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            area.inner(Margin::new(0, 1)),
+            &mut scrollbar_state,
+        );
+    }
+
+    // Record a region per visible row for mouse clicks
+    let mut regions = app.hit_regions.borrow_mut();
+    regions.push(area, HitTarget::TaskList);
+    for (row, item) in (inner.y..inner.bottom()).zip(visible) {
+        regions.push(
+            Rect::new(inner.x, row, inner.width, 1),
+            HitTarget::TreeItem(item.clone()),
+        );
+    }
+}
+
+fn is_selected(app: &App, item: &TreeItem) -> bool {
+    match (&app.selection, item) {
+        (TreeSelection::Group(a), TreeItem::Group(b)) => a == b,
+        (TreeSelection::Task(g1, t1), TreeItem::Task(g2, t2)) => g1 == g2 && t1 == t2,
+        _ => false,
+    }
+}
+
+/// Adjust the previous scroll offset so the selected row is visible and no
+/// space is wasted below the last row
+pub fn scroll_offset(offset: usize, selected: Option<usize>, total: usize, height: usize) -> usize {
+    let mut offset = offset;
+    if let Some(selected) = selected {
+        if selected < offset {
+            offset = selected;
+        } else if height > 0 && selected >= offset + height {
+            offset = selected + 1 - height;
+        }
+    }
+    offset.min(total.saturating_sub(height))
 }
 
 fn render_group_item(
+    theme: &Theme,
     state: &pueue_lib::state::State,
     name: &str,
+    (running, total): (usize, usize),
     is_collapsed: bool,
     is_selected: bool,
 ) -> ListItem<'static> {
     let group = state.groups.get(name);
 
-    // Count tasks in group
-    let tasks_in_group: Vec<_> = state
-        .tasks
-        .iter()
-        .filter(|(_, t)| t.group == name)
-        .collect();
-    let total = tasks_in_group.len();
-    let running = tasks_in_group
-        .iter()
-        .filter(|(_, t)| matches!(t.status, TaskStatus::Running { .. }))
-        .count();
-
     // Collapse indicator
     let indicator = if is_collapsed { "▶" } else { "▼" };
 
     // Group status indicator
-    let (status_indicator, status_color) = match group.map(|g| &g.status) {
-        Some(GroupStatus::Paused) => (" [PAUSED]", Color::Red),
-        _ => ("", Color::Reset),
+    let status_indicator = match group.map(|g| &g.status) {
+        Some(GroupStatus::Paused) => " [PAUSED]",
+        _ => "",
     };
 
     // Parallel limit
     let parallel = group.map(|g| g.parallel_tasks).unwrap_or(1);
 
     let style = if is_selected {
-        Style::default()
-            .fg(Color::Black)
-            .bg(Color::White)
-            .add_modifier(Modifier::BOLD)
+        theme.selection
     } else {
-        Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD)
+        theme.group_header
     };
 
     let mut spans = vec![Span::styled(
@@ -107,9 +172,7 @@ fn render_group_item(
     if !status_indicator.is_empty() && !is_selected {
         spans.push(Span::styled(
             status_indicator.to_string(),
-            Style::default()
-                .fg(status_color)
-                .add_modifier(Modifier::BOLD),
+            theme.group_paused.add_modifier(Modifier::BOLD),
         ));
     } else if !status_indicator.is_empty() {
         spans.push(Span::styled(status_indicator.to_string(), style));
@@ -119,11 +182,13 @@ fn render_group_item(
 }
 
 fn render_task_item(
+    theme: &Theme,
     task_id: usize,
     task: &pueue_lib::task::Task,
     is_selected: bool,
+    is_marked: bool,
 ) -> ListItem<'static> {
-    let (icon, color) = get_status_icon_and_color(&task.status);
+    let icon = status_icon(&task.status);
 
     let duration = match &task.status {
         TaskStatus::Running { start, .. } | TaskStatus::Paused { start, .. } => {
@@ -140,38 +205,36 @@ fn render_task_item(
 
     let command = truncate_string(&task.command, 35);
 
-    // Indent with 2 spaces for tasks under groups
-    let content = format!("  {} #{:<4} {} {}", icon, task_id, duration, command);
+    // Indent with 2 spaces for tasks under groups, marked tasks get a "*"
+    let mark = if is_marked { '*' } else { ' ' };
+    let content = format!(" {}{} #{:<4} {} {}", mark, icon, task_id, duration, command);
 
     let style = if is_selected {
-        Style::default()
-            .fg(Color::Black)
-            .bg(Color::White)
-            .add_modifier(Modifier::BOLD)
+        theme.selection
     } else {
-        Style::default().fg(color)
+        theme.task_status(&task.status)
     };
 
     ListItem::new(Line::from(Span::styled(content, style)))
 }
 
-fn get_status_icon_and_color(status: &TaskStatus) -> (&str, Color) {
+fn status_icon(status: &TaskStatus) -> &'static str {
     use pueue_lib::task::TaskResult;
 
     match status {
-        TaskStatus::Running { .. } => ("▶", Color::Green),
-        TaskStatus::Queued { .. } => ("●", Color::Yellow),
-        TaskStatus::Paused { .. } => ("⏸", Color::Cyan),
-        TaskStatus::Stashed { .. } => ("⊡", Color::Gray),
+        TaskStatus::Running { .. } => "▶",
+        TaskStatus::Queued { .. } => "●",
+        TaskStatus::Paused { .. } => "⏸",
+        TaskStatus::Stashed { .. } => "⊡",
         TaskStatus::Done { result, .. } => match result {
-            TaskResult::Success => ("✓", Color::Green),
+            TaskResult::Success => "✓",
             TaskResult::Failed(_) | TaskResult::FailedToSpawn(_) | TaskResult::DependencyFailed => {
-                ("✗", Color::Red)
+                "✗"
             }
-            TaskResult::Killed => ("⊠", Color::Magenta),
-            TaskResult::Errored => ("⚠", Color::Red),
+            TaskResult::Killed => "⊠",
+            TaskResult::Errored => "⚠",
         },
-        TaskStatus::Locked { .. } => ("🔒", Color::Magenta),
+        TaskStatus::Locked { .. } => "🔒",
     }
 }
 
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use pueue_lib::task::{Task, TaskStatus};
+use std::collections::{HashMap, VecDeque};
+
+use crate::move_tasks::remap_dependencies;
+use crate::pueue_client::PueueClient;
+
+/// Number of operations that can be undone
+pub const UNDO_CAPACITY: usize = 50;
+
+/// The inverse of an operation
+#[derive(Debug, Clone)]
+pub enum UndoOp {
+    /// Add copies of removed, cleaned or killed tasks
+    Readd(Vec<Task>),
+    Stash(Vec<usize>),
+    Enqueue(Vec<usize>),
+    PauseGroup(String),
+    StartGroup(String),
+    PauseTasks(Vec<usize>),
+    StartTasks(Vec<usize>),
+    Parallel {
+        group: String,
+        limit: usize,
+    },
+}
+
+/// An operation that can be undone, e.g. "Remove task #3"
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub description: String,
+    pub op: UndoOp,
+    pub time: DateTime<Local>,
+    /// Index of the daemon the operation ran on
+    pub daemon: usize,
+}
+
+impl UndoEntry {
+    pub fn new(description: impl Into<String>, op: UndoOp) -> Self {
+        Self {
+            description: description.into(),
+            op,
+            time: Local::now(),
+            daemon: 0,
+        }
+    }
+}
+
+/// Recent undoable operations, bounded to `UNDO_CAPACITY`
+#[derive(Debug, Clone, Default)]
+pub struct UndoStack {
+    entries: VecDeque<UndoEntry>,
+}
+
+impl UndoStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, entry: UndoEntry) {
+        if self.entries.len() == UNDO_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Remove the entry at `index`, counted from the newest
+    pub fn take(&mut self, index: usize) -> Option<UndoEntry> {
+        let index = self.entries.len().checked_sub(index + 1)?;
+        self.entries.remove(index)
+    }
+
+    /// Put an entry back at `position`, counted from the oldest, e.g. after
+    /// undoing it failed
+    pub fn insert(&mut self, position: usize, entry: UndoEntry) {
+        if self.entries.len() == UNDO_CAPACITY {
+            self.entries.pop_front();
+        }
+        let position = position.min(self.entries.len());
+        self.entries.insert(position, entry);
+    }
+
+    /// All entries, newest first
+    pub fn iter(&self) -> impl Iterator<Item = &UndoEntry> {
+        self.entries.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A removed task as it is added again: stashed when `stashed`, so nothing
+/// starts right away, otherwise queued. Tasks that had already finished are
+/// always stashed, or undoing a clean would run them all again.
+pub fn restored_task(task: &Task, stashed: bool) -> Task {
+    let finished = matches!(task.status, TaskStatus::Done { .. });
+    let mut task = task.clone();
+    task.status = if stashed || finished {
+        TaskStatus::Stashed { enqueue_at: None }
+    } else {
+        TaskStatus::Queued {
+            enqueued_at: Local::now(),
+        }
+    };
+    task
+}
+
+/// Run the inverse operation. If re-adding tasks fails part way, `op` keeps
+/// only the tasks that weren't added again.
+pub async fn revert(op: &mut UndoOp, client: &mut PueueClient, stashed: bool) -> Result<()> {
+    match op {
+        UndoOp::Readd(tasks) => {
+            // In id order, so dependencies between the tasks point to the new ids
+            let mut new_ids = HashMap::new();
+            while let Some(task) = tasks.first() {
+                let dependencies = remap_dependencies(&task.dependencies, &new_ids);
+                match client
+                    .readd(&restored_task(task, stashed), &task.group, dependencies)
+                    .await
+                {
+                    Ok(new_id) => {
+                        new_ids.insert(task.id, new_id);
+                        tasks.remove(0);
+                    }
+                    Err(e) => {
+                        for task in tasks.iter_mut() {
+                            task.dependencies = remap_dependencies(&task.dependencies, &new_ids);
+                        }
+                        return Err(e);
+                    }
+                }
+            }
+            Ok(())
+        }
+        UndoOp::Stash(ids) => client.stash(ids.clone()).await,
+        UndoOp::Enqueue(ids) => client.enqueue(ids.clone()).await,
+        UndoOp::PauseGroup(group) => client.pause_group(group).await,
+        UndoOp::StartGroup(group) => client.start_group(group).await,
+        UndoOp::PauseTasks(ids) => client.pause_tasks(ids.clone()).await,
+        UndoOp::StartTasks(ids) => client.start_tasks(ids.clone()).await,
+        UndoOp::Parallel { group, limit } => client.parallel(group, *limit).await,
+    }
+}
@@ -0,0 +1,133 @@
+use chrono::Local;
+use lazypueue::activity::{
+    diff_states, ActivityCategory, ActivityFilter, ActivityKind, ActivityLog,
+};
+use lazypueue::app::{Action, App};
+use lazypueue::snapshot::render_frame;
+use pueue_lib::state::{Group, GroupStatus, State};
+use pueue_lib::task::{Task, TaskResult, TaskStatus};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn task(command: &str, status: TaskStatus) -> Task {
+    Task::new(
+        command.to_string(),
+        PathBuf::from("/tmp"),
+        HashMap::new(),
+        "default".to_string(),
+        status,
+        vec![],
+        0,
+        None,
+    )
+}
+
+fn queued() -> TaskStatus {
+    TaskStatus::Queued {
+        enqueued_at: Local::now(),
+    }
+}
+
+fn running() -> TaskStatus {
+    TaskStatus::Running {
+        enqueued_at: Local::now(),
+        start: Local::now(),
+    }
+}
+
+#[test]
+fn test_diff_states() {
+    let mut previous = State::new();
+    previous.groups.insert(
+        "default".to_string(),
+        Group {
+            status: GroupStatus::Running,
+            parallel_tasks: 1,
+        },
+    );
+    previous.add_task(task("sleep 1", queued()));
+    previous.add_task(task("sleep 2", running()));
+    previous.add_task(task("sleep 3", queued()));
+
+    let mut new = previous.clone();
+    new.groups.get_mut("default").unwrap().status = GroupStatus::Paused;
+    new.groups.get_mut("default").unwrap().parallel_tasks = 2;
+    new.tasks.get_mut(&0).unwrap().status = running();
+    new.tasks.get_mut(&1).unwrap().status = TaskStatus::Done {
+        enqueued_at: Local::now(),
+        start: Local::now(),
+        end: Local::now(),
+        result: TaskResult::Failed(1),
+    };
+    new.add_task(task("make", queued()));
+    new.tasks.remove(&2);
+
+    let changes = diff_states(&previous, &new);
+    assert_eq!(changes.len(), 6);
+    assert_eq!(
+        changes[0],
+        ActivityKind::GroupPaused {
+            group: "default".to_string()
+        }
+    );
+    assert_eq!(
+        changes[1],
+        ActivityKind::ParallelChanged {
+            group: "default".to_string(),
+            from: 1,
+            to: 2
+        }
+    );
+    assert_eq!(changes[2], ActivityKind::TaskStarted { id: 0 });
+    assert!(matches!(&changes[3], ActivityKind::TaskFinished(t) if t.id == 1));
+    assert!(matches!(changes[4], ActivityKind::TaskAdded { id: 3, .. }));
+    assert_eq!(changes[5], ActivityKind::TaskRemoved { id: 2 });
+}
+
+#[test]
+fn test_activity_log_filter() {
+    let mut log = ActivityLog::new();
+    log.push(ActivityKind::TaskStarted { id: 1 });
+    log.push(ActivityKind::GroupPaused {
+        group: "build".to_string(),
+    });
+    log.push(ActivityKind::TaskRemoved { id: 7 });
+
+    let all = ActivityFilter::default();
+    let newest: Vec<_> = log.filtered(&all).map(|e| e.kind.clone()).collect();
+    assert_eq!(newest[0], ActivityKind::TaskRemoved { id: 7 });
+
+    let groups = ActivityFilter {
+        category: ActivityCategory::Groups,
+        query: String::new(),
+    };
+    assert_eq!(log.filtered(&groups).count(), 1);
+
+    let search = ActivityFilter {
+        category: ActivityCategory::All,
+        query: "#7".to_string(),
+    };
+    assert_eq!(log.filtered(&search).count(), 1);
+}
+
+#[test]
+fn test_activity_scroll_stays_within_the_feed() {
+    let mut app = App::new();
+    for id in 0..30 {
+        app.activity.push(ActivityKind::TaskStarted { id });
+    }
+    app.handle_action(Action::ToggleActivity);
+    render_frame(&app, 100, 20).unwrap();
+    let max = app.activity_max_scroll.get();
+    assert!(max > 0);
+
+    // Scrolling past the end doesn't pile up, so one step up moves again
+    for _ in 0..50 {
+        app.handle_action(Action::ScrollActivityDown);
+    }
+    assert_eq!(app.activity_scroll, max);
+    app.handle_action(Action::ScrollActivityUp);
+    assert_eq!(app.activity_scroll, max - 1);
+    app.handle_action(Action::ScrollActivityPageUp);
+    assert_eq!(app.activity_scroll, 0);
+}
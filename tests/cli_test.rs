@@ -0,0 +1,247 @@
+use chrono::{Duration, Local};
+use lazypueue::app::{Action, App, TreeItem, TreeSelection};
+use lazypueue::cli::{exit_code, last_lines, new_output, status_json, status_text, wait_result};
+use lazypueue::filter::{select_tasks, SortMode, StatusFilter, TaskFilter};
+use lazypueue::pueue_client::decompress_output;
+use pueue_lib::state::{Group, GroupStatus, State};
+use pueue_lib::task::{Task, TaskResult, TaskStatus};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+fn task(command: &str, group: &str, label: Option<&str>, status: TaskStatus) -> Task {
+    Task::new(
+        command.to_string(),
+        PathBuf::from("/tmp"),
+        HashMap::new(),
+        group.to_string(),
+        status,
+        vec![],
+        0,
+        label.map(str::to_string),
+    )
+}
+
+fn done(minutes_ago: i64, seconds: i64, result: TaskResult) -> TaskStatus {
+    let start = Local::now() - Duration::minutes(minutes_ago);
+    TaskStatus::Done {
+        enqueued_at: start,
+        start,
+        end: start + Duration::seconds(seconds),
+        result,
+    }
+}
+
+/// #0 ci failed, #1 default running, #2 ci succeeded, #3 default queued
+fn state() -> State {
+    let mut state = State::new();
+    for name in ["default", "ci"] {
+        state.groups.insert(
+            name.to_string(),
+            Group {
+                status: GroupStatus::Running,
+                parallel_tasks: 2,
+            },
+        );
+    }
+    state.add_task(task(
+        "cargo test",
+        "ci",
+        Some("nightly"),
+        done(30, 100, TaskResult::Failed(101)),
+    ));
+    state.add_task(task(
+        "make deploy",
+        "default",
+        None,
+        TaskStatus::Running {
+            enqueued_at: Local::now(),
+            start: Local::now() - Duration::seconds(5),
+        },
+    ));
+    state.add_task(task(
+        "cargo build",
+        "ci",
+        None,
+        done(10, 300, TaskResult::Success),
+    ));
+    state.add_task(task(
+        "backup",
+        "default",
+        None,
+        TaskStatus::Queued {
+            enqueued_at: Local::now(),
+        },
+    ));
+    state
+}
+
+fn ids(tasks: Vec<&Task>) -> Vec<usize> {
+    tasks.into_iter().map(|t| t.id).collect()
+}
+
+#[test]
+fn test_filter_queries() {
+    let state = state();
+    let select = |query: &str| {
+        ids(select_tasks(
+            &state,
+            &TaskFilter::parse(query).unwrap(),
+            SortMode::Id,
+        ))
+    };
+
+    assert_eq!(select(""), vec![0, 1, 2, 3]);
+    assert_eq!(select("status:failed"), vec![0]);
+    assert_eq!(select("status:success status:running"), vec![1, 2]);
+    assert_eq!(select("group:ci"), vec![0, 2]);
+    assert_eq!(select("group:ci BUILD"), vec![2]);
+    assert_eq!(select("label:nightly"), vec![0]);
+    assert_eq!(select("NIGHTLY"), vec![0]);
+
+    let filter = TaskFilter::parse("deploy status:done group:ci").unwrap();
+    assert_eq!(filter.statuses, vec![StatusFilter::Done]);
+    assert_eq!(filter.to_string(), "status:done group:ci deploy");
+    assert_eq!(TaskFilter::parse(&filter.to_string()).unwrap(), filter);
+
+    let err = TaskFilter::parse("status:borked").unwrap_err();
+    assert!(err.contains("borked") && err.contains("running"), "{}", err);
+}
+
+#[test]
+fn test_sort_modes() {
+    let state = state();
+    let sorted = |sort| ids(select_tasks(&state, &TaskFilter::default(), sort));
+
+    assert_eq!(sorted(SortMode::Id), vec![0, 1, 2, 3]);
+    assert_eq!(sorted(SortMode::Status), vec![1, 3, 0, 2]);
+    assert_eq!(sorted(SortMode::Start), vec![1, 2, 0, 3]);
+    assert_eq!(sorted(SortMode::Runtime), vec![2, 0, 1, 3]);
+    assert_eq!(sorted(SortMode::Command), vec![3, 2, 0, 1]);
+
+    let mut mode = SortMode::Id;
+    for _ in 0..5 {
+        mode = mode.next();
+    }
+    assert_eq!(mode, SortMode::Id);
+}
+
+#[test]
+fn test_tree_uses_filter_and_sort() {
+    let mut app = App::new();
+    app.state = Some(state());
+    app.task_filter = TaskFilter::parse("status:done status:queued").unwrap();
+    app.handle_action(Action::CycleSortMode);
+    assert_eq!(app.sort_mode, SortMode::Status);
+
+    assert_eq!(
+        app.get_tree_items(),
+        vec![
+            TreeItem::Group("default".to_string()),
+            TreeItem::Task("default".to_string(), 3),
+            TreeItem::Group("ci".to_string()),
+            TreeItem::Task("ci".to_string(), 0),
+            TreeItem::Task("ci".to_string(), 2),
+        ]
+    );
+
+    // Typed in the filter dialog, which starts with the current query
+    app.handle_action(Action::StartTaskFilter);
+    assert_eq!(app.text_input.value, "status:done status:queued");
+    app.handle_action(Action::InputChar('x'));
+    app.handle_action(Action::SubmitInput);
+    assert_eq!(app.task_filter.statuses.len(), 2);
+    assert!(app.messages.history().any(|m| m
+        .text
+        .starts_with("Invalid filter: unknown status 'queuedx'")));
+
+    app.handle_action(Action::StartTaskFilter);
+    app.text_input.set_value("group:ci");
+    app.handle_action(Action::SubmitInput);
+    assert_eq!(app.get_tree_items().len(), 4);
+}
+
+#[test]
+fn test_expanding_a_group_selects_its_first_shown_task() {
+    let mut app = App::new();
+    app.state = Some(state());
+    // Task #1 is running and filtered out, so #3 comes first
+    app.task_filter = TaskFilter::parse("status:queued").unwrap();
+    app.selection = TreeSelection::Group("default".to_string());
+
+    app.handle_action(Action::ExpandGroup);
+    assert_eq!(app.selection, TreeSelection::Task("default".to_string(), 3));
+}
+
+#[test]
+fn test_status_output() {
+    let state = state();
+    let text = status_text(&state, &TaskFilter::default(), SortMode::Id);
+    assert_eq!(
+        text,
+        "default (running, 2 parallel)\n\
+         \x20 #1    running           make deploy\n\
+         \x20 #3    queued            backup\n\
+         ci (running, 2 parallel)\n\
+         \x20 #0    failed            cargo test [nightly]\n\
+         \x20 #2    success           cargo build\n"
+    );
+    // Groups without matches are left out when filtering
+    let filter = TaskFilter::parse("group:ci").unwrap();
+    assert!(status_text(&state, &filter, SortMode::Id).starts_with("ci "));
+
+    let json = status_json(&state, &filter, SortMode::Runtime);
+    let tasks = json["tasks"].as_array().unwrap();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0]["command"], "cargo build");
+    assert_eq!(json["groups"]["default"]["parallel_tasks"], 2);
+}
+
+#[test]
+fn test_exit_codes() {
+    let state = state();
+    let codes: Vec<_> = state.tasks.values().map(exit_code).collect();
+    assert_eq!(codes, vec![Some(101), None, Some(0), None]);
+
+    let tasks = |ids: &[usize]| -> Vec<&Task> { ids.iter().map(|id| &state.tasks[id]).collect() };
+    assert_eq!(wait_result(&tasks(&[2])), Some(0));
+    assert_eq!(wait_result(&tasks(&[0, 2])), Some(1));
+    assert_eq!(wait_result(&tasks(&[1, 2])), None);
+    assert_eq!(wait_result(&[]), Some(0));
+
+    let mut stashed = task(
+        "later",
+        "default",
+        None,
+        TaskStatus::Stashed { enqueue_at: None },
+    );
+    stashed.id = 9;
+    assert_eq!(wait_result(&[&stashed, &state.tasks[&2]]), Some(0));
+}
+
+#[test]
+fn test_log_helpers() {
+    let log = "one\ntwo\nthree\n";
+    assert_eq!(last_lines(log, None), log);
+    assert_eq!(last_lines(log, Some(2)), "two\nthree\n");
+    assert_eq!(last_lines(log, Some(5)), log);
+    assert_eq!(last_lines(log, Some(0)), "");
+    assert_eq!(last_lines("a\nb", Some(1)), "b");
+
+    assert_eq!(new_output("one\n", log), "two\nthree\n");
+    assert_eq!(new_output("other\n", log), log);
+}
+
+#[test]
+fn test_log_output_is_decompressed() {
+    // The daemon sends output compressed the way pueue's log module does
+    let mut compressed = Vec::new();
+    {
+        let mut encoder = snap::write::FrameEncoder::new(&mut compressed);
+        encoder.write_all("one\ntwo ✓\n".as_bytes()).unwrap();
+    }
+    assert_ne!(compressed, "one\ntwo ✓\n".as_bytes());
+    assert_eq!(decompress_output(&compressed).unwrap(), "one\ntwo ✓\n");
+    assert_eq!(decompress_output(&[]).unwrap(), "");
+    assert!(decompress_output(b"plain text").is_err());
+}
@@ -0,0 +1,71 @@
+use lazypueue::app::{Action, App};
+use lazypueue::compat::{Compatibility, DaemonVersion, RequestKind, Version};
+use lazypueue::daemon::{Effect, Outcome};
+
+#[test]
+fn test_parse_versions() {
+    assert_eq!(Version::parse("0.30.1"), Some(Version::new(0, 30, 1)));
+    assert_eq!(Version::parse("4.0.0-rc.1"), Some(Version::new(4, 0, 0)));
+    assert_eq!(Version::parse("3.4"), Some(Version::new(3, 4, 0)));
+    assert_eq!(Version::parse("v1"), None);
+    assert_eq!(Version::parse("1.2.3.4"), None);
+}
+
+#[test]
+fn test_compatibility_of_daemon_versions() {
+    let cases = [
+        ("0.30.1", Compatibility::Supported),
+        ("0.29.0", Compatibility::Supported),
+        ("0.28.1", Compatibility::Older),
+        ("3.4.1", Compatibility::Older),
+        ("0.31.0", Compatibility::Newer),
+        ("garbage", Compatibility::Unknown),
+    ];
+    for (text, expected) in cases {
+        assert_eq!(
+            DaemonVersion::new(text).compatibility(),
+            expected,
+            "{}",
+            text
+        );
+    }
+
+    let supported = DaemonVersion::new("0.30.1");
+    assert!(supported.warning().is_none());
+
+    // Older daemons keep the requests whose format didn't change
+    let older = DaemonVersion::new("0.28.1");
+    assert!(older.supports(RequestKind::Status));
+    assert!(!older.supports(RequestKind::Kill));
+    assert!(older.warning().unwrap().contains("0.29 to 0.30"));
+
+    let legacy = DaemonVersion::new("3.4.1");
+    assert!(!legacy.supports(RequestKind::Status));
+
+    let newer = DaemonVersion::new("0.31.0");
+    assert!(newer.supports(RequestKind::Edit));
+    assert!(newer.warning().unwrap().contains("Update lazypueue"));
+}
+
+#[test]
+fn test_unsupported_actions_are_disabled() {
+    let mut app = App::new();
+    app.poll();
+    let poll = app.take_jobs()[0].id;
+    app.complete(
+        poll,
+        Outcome::new().effect(Effect::Connected("0.28.1".to_string())),
+    );
+    assert_eq!(app.daemons[0].version, Some(DaemonVersion::new("0.28.1")));
+
+    app.handle_action(Action::CleanFinished);
+    assert!(app.take_jobs().is_empty());
+    assert_eq!(
+        app.messages.history().last().unwrap().text.as_str(),
+        "Daemon version 0.28.1 doesn't support the clean request"
+    );
+
+    // Refreshing still works
+    app.handle_action(Action::Refresh);
+    assert_eq!(app.take_jobs().len(), 1);
+}
@@ -0,0 +1,44 @@
+use lazypueue::config::{Config, ConfigError, DEFAULT_CONFIG};
+use lazypueue::notify::NotifyMethod;
+use std::path::Path;
+
+#[test]
+fn test_default_config_template_matches_defaults() {
+    let config = Config::parse(DEFAULT_CONFIG, Path::new("default.toml")).unwrap();
+    assert_eq!(config, Config::default());
+}
+
+#[test]
+fn test_partial_config() {
+    let config = Config::parse(
+        r#"
+        [refresh]
+        interval_ms = 1000
+
+        [notifications]
+        methods = ["bell", "tmux"]
+        "#,
+        Path::new("config.toml"),
+    )
+    .unwrap();
+    assert_eq!(config.refresh.interval_ms, 1000);
+    assert_eq!(config.refresh.follow_interval_ms, 200);
+    assert_eq!(
+        config.notifications.methods,
+        vec![NotifyMethod::Bell, NotifyMethod::Tmux]
+    );
+}
+
+#[test]
+fn test_config_errors_name_the_key() {
+    let err = Config::parse("[refresh]\nintervall_ms = 1\n", Path::new("c.toml")).unwrap_err();
+    assert!(matches!(err, ConfigError::Parse { .. }));
+    assert!(err.to_string().contains("intervall_ms"), "{}", err);
+
+    let err = Config::parse("[layout]\ntask_list_percent = 95\n", Path::new("c.toml")).unwrap_err();
+    assert!(
+        err.to_string().contains("layout.task_list_percent"),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,147 @@
+use chrono::Local;
+use lazypueue::app::{Action, App, TreeSelection};
+use lazypueue::daemon::{Effect, Outcome};
+use lazypueue::keymap::Mode;
+use lazypueue::ui;
+use pueue_lib::state::{Group, GroupStatus, State};
+use pueue_lib::task::{Task, TaskStatus};
+use ratatui::{backend::TestBackend, Terminal};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn app_with_task() -> App {
+    let mut state = State::new();
+    state.groups.insert(
+        "default".to_string(),
+        Group {
+            status: GroupStatus::Running,
+            parallel_tasks: 1,
+        },
+    );
+    state.add_task(Task::new(
+        "sleep 10".to_string(),
+        PathBuf::from("/tmp"),
+        HashMap::new(),
+        "default".to_string(),
+        TaskStatus::Running {
+            enqueued_at: Local::now(),
+            start: Local::now(),
+        },
+        vec![],
+        0,
+        None,
+    ));
+
+    let mut app = App::new();
+    app.state = Some(state);
+    app.selection = TreeSelection::Task("default".to_string(), 0);
+    app
+}
+
+fn render(app: &App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+    terminal.draw(|f| ui::render(f, app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let area = buffer.area;
+    (0..area.height)
+        .map(|y| {
+            (0..area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_actions_queue_jobs_until_complete() {
+    let mut app = app_with_task();
+
+    assert!(!app.handle_action(Action::KillTask));
+    let jobs = app.take_jobs();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].label.as_deref(), Some("Killing task #0"));
+    assert!(app.take_jobs().is_empty());
+
+    // The action is shown as pending until its outcome arrives
+    assert_eq!(
+        app.pending_labels().collect::<Vec<_>>(),
+        ["Killing task #0"]
+    );
+    assert!(render(&app).contains("Killing task #0"));
+
+    app.complete(jobs[0].id, Outcome::new().success("Killed task #0"));
+    assert_eq!(app.pending_labels().count(), 0);
+    assert!(!render(&app).contains("Killing task #0"));
+    assert_eq!(
+        app.messages.history().next().map(|m| m.text.as_str()),
+        Some("Killed task #0")
+    );
+}
+
+#[test]
+fn test_polls_do_not_pile_up() {
+    let mut app = app_with_task();
+
+    app.poll();
+    app.poll();
+    let jobs = app.take_jobs();
+    assert_eq!(jobs.len(), 1);
+    // Background polls don't show the spinner
+    assert_eq!(jobs[0].label, None);
+    assert_eq!(app.pending_labels().count(), 0);
+
+    app.complete(jobs[0].id, Outcome::new());
+    app.poll();
+    assert_eq!(app.take_jobs().len(), 1);
+}
+
+#[test]
+fn test_log_pane_loads_do_not_hold_up_polls() {
+    let mut app = app_with_task();
+    app.config.layout.show_log_pane = true;
+
+    // Loading the docked log has no label either, but isn't a poll
+    app.handle_action(Action::NavigateDown);
+    assert_eq!(app.take_jobs().len(), 1);
+    app.poll();
+    assert_eq!(app.take_jobs().len(), 1);
+    app.poll();
+    assert!(app.take_jobs().is_empty());
+}
+
+#[test]
+fn test_log_effects() {
+    let mut app = app_with_task();
+
+    // Log output of a viewer that was closed in the meantime is dropped
+    app.complete(
+        1,
+        Outcome::new().effect(Effect::FollowLogs {
+            task_id: 0,
+            content: "late".to_string(),
+        }),
+    );
+    assert!(!app.is_open(Mode::Log));
+    assert_eq!(app.log_content, None);
+
+    app.complete(
+        2,
+        Outcome::new().effect(Effect::ShowLogs {
+            content: "hello".to_string(),
+            follow: true,
+        }),
+    );
+    assert!(app.is_open(Mode::Log));
+    assert!(app.follow_mode);
+    assert_eq!(app.log_content.as_deref(), Some("hello"));
+
+    app.complete(
+        3,
+        Outcome::new().effect(Effect::FollowLogs {
+            task_id: 0,
+            content: "hello world".to_string(),
+        }),
+    );
+    assert_eq!(app.log_content.as_deref(), Some("hello world"));
+}
@@ -0,0 +1,147 @@
+use chrono::Local;
+use lazypueue::activity::ActivityFilter;
+use lazypueue::app::{Action, App, TreeSelection};
+use lazypueue::config::{Config, DaemonConfig};
+use lazypueue::daemon::{Connection, Effect, Outcome};
+use lazypueue::undo::{UndoEntry, UndoOp};
+use pueue_lib::state::{Group, GroupStatus, State};
+use pueue_lib::task::{Task, TaskStatus};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn state(commands: &[&str]) -> State {
+    let mut state = State::new();
+    state.groups.insert(
+        "default".to_string(),
+        Group {
+            status: GroupStatus::Running,
+            parallel_tasks: 1,
+        },
+    );
+    for command in commands {
+        state.add_task(Task::new(
+            command.to_string(),
+            PathBuf::from("/tmp"),
+            HashMap::new(),
+            "default".to_string(),
+            TaskStatus::Queued {
+                enqueued_at: Local::now(),
+            },
+            vec![],
+            0,
+            None,
+        ));
+    }
+    state
+}
+
+fn app_with_daemons() -> App {
+    let mut config = Config::default();
+    for name in ["build", "local"] {
+        config
+            .daemons
+            .insert(name.to_string(), DaemonConfig::default());
+    }
+    let mut app = App::with_config(config);
+    app.state = Some(state(&["make"]));
+    app.selection = TreeSelection::Task("default".to_string(), 0);
+    app
+}
+
+#[test]
+fn test_daemons_from_config() {
+    let app = App::new();
+    assert_eq!(app.daemons.len(), 1);
+    assert_eq!(app.daemons[0].name, "local");
+
+    let app = app_with_daemons();
+    let names: Vec<_> = app.daemons.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, ["build", "local"]);
+    assert_eq!(app.active_daemon, 0);
+}
+
+#[test]
+fn test_poll_and_actions_are_routed_to_daemons() {
+    let mut app = app_with_daemons();
+
+    // Every daemon is polled, for its connection status
+    app.poll();
+    let jobs = app.take_jobs();
+    assert_eq!(jobs.iter().map(|j| j.daemon).collect::<Vec<_>>(), [0, 1]);
+
+    // Actions go to the shown daemon
+    app.handle_action(Action::NextDaemon);
+    app.take_jobs();
+    app.state = Some(state(&["cargo test"]));
+    app.selection = TreeSelection::Task("default".to_string(), 0);
+    app.handle_action(Action::KillTask);
+    let jobs = app.take_jobs();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].daemon, 1);
+}
+
+#[test]
+fn test_switching_keeps_each_daemons_view() {
+    let mut app = app_with_daemons();
+    app.marked_tasks.insert(0);
+
+    app.handle_action(Action::NextDaemon);
+    assert_eq!(app.active_daemon, 1);
+    assert!(app.state.is_none());
+    assert!(app.marked_tasks.is_empty());
+
+    // The hidden daemon's state arrives in the background
+    app.poll();
+    let jobs = app.take_jobs();
+    let build = jobs.iter().find(|j| j.daemon == 0).unwrap().id;
+    app.complete(
+        build,
+        Outcome::new().effect(Effect::State(Ok(state(&["make", "make install"])))),
+    );
+    assert!(app.state.is_none());
+    assert_eq!(app.daemons[0].connection, Connection::Connected);
+    // What changed there is recorded under the daemon's name
+    let filter = ActivityFilter::default();
+    let event = app.activity.filtered(&filter).next();
+    assert_eq!(
+        event.map(|e| e.describe()).as_deref(),
+        Some("[build] Task #1 added to 'default': make install")
+    );
+
+    // Going back restores the selection, the marks and the newer state
+    app.handle_action(Action::PrevDaemon);
+    assert_eq!(app.active_daemon, 0);
+    assert_eq!(app.state.as_ref().unwrap().tasks.len(), 2);
+    assert_eq!(app.selection, TreeSelection::Task("default".to_string(), 0));
+    assert!(app.marked_tasks.contains(&0));
+}
+
+#[test]
+fn test_background_outcomes_are_attributed() {
+    let mut app = app_with_daemons();
+    app.handle_action(Action::KillTask);
+    let kill = app.take_jobs()[0].id;
+    app.handle_action(Action::NextDaemon);
+    app.take_jobs();
+
+    app.complete(
+        kill,
+        Outcome::new()
+            .success("Killed task #0")
+            .undoable(UndoEntry::new("Kill task #0", UndoOp::Readd(Vec::new())))
+            .effect(Effect::State(Err(anyhow::anyhow!("connection refused")))),
+    );
+    assert_eq!(
+        app.messages.history().last().unwrap().text.as_str(),
+        "[build] Killed task #0"
+    );
+    // The failure is shown in the status bar, not as the error screen
+    assert!(app.connection_error.is_none());
+    assert!(matches!(app.daemons[0].connection, Connection::Failed(_)));
+
+    // Undo goes to the daemon the operation ran on
+    app.handle_action(Action::Undo);
+    let jobs = app.take_jobs();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].daemon, 0);
+}
@@ -0,0 +1,102 @@
+use lazypueue::app::{Action, App};
+use lazypueue::daemon::{Effect, Outcome};
+use lazypueue::diagnostics::{self, CheckStatus, Report};
+use lazypueue::keymap::Mode;
+use lazypueue::snapshot::render_frame;
+use pueue_lib::settings::Shared;
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("lazypueue-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[cfg(unix)]
+#[test]
+fn test_settings_checks_explain_missing_files() {
+    let dir = temp_dir("diagnostics");
+    std::fs::write(dir.join("shared_secret"), "s3cret").unwrap();
+    let shared = Shared {
+        use_unix_socket: true,
+        unix_socket_path: Some(dir.join("pueue.socket")),
+        shared_secret_path: Some(dir.join("shared_secret")),
+        ..Shared::default()
+    };
+
+    let checks = diagnostics::settings_checks(&shared);
+    let names: Vec<_> = checks.iter().map(|c| c.name).collect();
+    assert_eq!(names, ["Mode", "Socket", "Shared secret"]);
+    assert_eq!(checks[1].status, CheckStatus::Fail);
+    assert!(checks[1].detail.ends_with("pueue.socket doesn't exist"));
+    assert!(checks[1].hint.as_ref().unwrap().contains("pueued -d"));
+    assert_eq!(checks[2].status, CheckStatus::Ok);
+    assert!(checks[2].detail.ends_with("(6 bytes)"));
+}
+
+#[test]
+fn test_missing_config_file_fails() {
+    let check = diagnostics::config_file_check(Some(Path::new("/nonexistent/pueue.yml")));
+    assert_eq!(check.status, CheckStatus::Fail);
+    assert_eq!(check.detail, "/nonexistent/pueue.yml doesn't exist");
+
+    let report = Report {
+        daemon: "local".to_string(),
+        checks: vec![check],
+    };
+    assert!(!report.is_ok());
+    assert_eq!(
+        diagnostics::to_text(&[report]),
+        "Daemon 'local'\n  ✗ Config file     /nonexistent/pueue.yml doesn't exist\n                    \
+         → Point pueue_config at the pueue.yml the daemon uses\n"
+    );
+}
+
+#[test]
+fn test_diagnostics_view_runs_the_checks() {
+    let mut app = App::new();
+    app.handle_action(Action::ToggleDiagnostics);
+    assert_eq!(app.mode(), Mode::Diagnostics);
+    let jobs = app.take_jobs();
+    assert_eq!(jobs.len(), 1);
+    assert!(app.diagnostics.is_none());
+
+    let report = Report {
+        daemon: "local".to_string(),
+        checks: Vec::new(),
+    };
+    app.complete(
+        jobs[0].id,
+        Outcome::new().effect(Effect::Diagnostics(report.clone())),
+    );
+    assert_eq!(app.diagnostics, Some(report));
+
+    // Rerunning clears the old report until the new one arrives
+    app.handle_action(Action::RunDiagnostics);
+    assert_eq!(app.take_jobs().len(), 1);
+    assert!(app.diagnostics.is_none());
+
+    app.handle_action(Action::CloseDiagnostics);
+    assert_eq!(app.mode(), Mode::Normal);
+}
+
+#[test]
+fn test_diagnostics_scroll_stays_within_the_report() {
+    let mut app = App::new();
+    app.handle_action(Action::ToggleDiagnostics);
+    let check = diagnostics::config_file_check(Some(Path::new("/nonexistent/pueue.yml")));
+    app.diagnostics = Some(Report {
+        daemon: "local".to_string(),
+        checks: vec![check; 30],
+    });
+    render_frame(&app, 100, 30).unwrap();
+    let max = app.diagnostics_max_scroll.get();
+    assert!(max > 0);
+
+    for _ in 0..200 {
+        app.handle_action(Action::ScrollDiagnosticsDown);
+    }
+    assert_eq!(app.diagnostics_scroll, max);
+    app.handle_action(Action::ScrollDiagnosticsUp);
+    assert_eq!(app.diagnostics_scroll, max - 1);
+}
@@ -0,0 +1,185 @@
+use chrono::Local;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use lazypueue::app::{Action, App, InputMode, Overlay, TreeSelection};
+use lazypueue::daemon::{Effect, Outcome};
+use lazypueue::events::{handle_key_event, handle_mouse_event};
+use lazypueue::keymap::Mode;
+use lazypueue::layout::Pane;
+use lazypueue::snapshot::render_frame;
+use pueue_lib::state::{Group, GroupStatus, State};
+use pueue_lib::task::{Task, TaskStatus};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn app_with_task() -> App {
+    let mut state = State::new();
+    state.groups.insert(
+        "default".to_string(),
+        Group {
+            status: GroupStatus::Running,
+            parallel_tasks: 1,
+        },
+    );
+    state.add_task(Task::new(
+        "make".to_string(),
+        PathBuf::from("/tmp"),
+        HashMap::new(),
+        "default".to_string(),
+        TaskStatus::Queued {
+            enqueued_at: Local::now(),
+        },
+        vec![],
+        0,
+        None,
+    ));
+    let mut app = App::new();
+    app.state = Some(state);
+    app.selection = TreeSelection::Task("default".to_string(), 0);
+    app
+}
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn screen(app: &App, width: u16, height: u16) -> String {
+    let buffer = render_frame(app, width, height).unwrap();
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_tab_cycles_the_focus() {
+    let mut app = app_with_task();
+    assert_eq!(app.focus, Pane::TaskList);
+    assert_eq!(
+        handle_key_event(&mut app, key(KeyCode::Tab)),
+        Some(Action::FocusNext)
+    );
+    app.handle_action(Action::FocusNext);
+    assert_eq!(app.focus, Pane::Details);
+    assert_eq!(app.mode(), Mode::Details);
+    assert_eq!(
+        handle_key_event(&mut app, key(KeyCode::Char('j'))),
+        Some(Action::ScrollDetailsDown)
+    );
+
+    // Without the docked log there are only two panes
+    app.handle_action(Action::FocusNext);
+    assert_eq!(app.focus, Pane::TaskList);
+
+    app.handle_action(Action::ToggleLogPane);
+    assert_eq!(app.focus, Pane::Log);
+    assert_eq!(app.mode(), Mode::LogPane);
+    app.handle_action(Action::FocusNext);
+    assert_eq!(app.focus, Pane::TaskList);
+    app.handle_action(Action::FocusPrev);
+    assert_eq!(app.focus, Pane::Log);
+
+    // Hiding the focused log pane returns to the task list
+    app.handle_action(Action::ToggleLogPane);
+    assert_eq!(app.focus, Pane::TaskList);
+    assert_eq!(app.panes(), vec![Pane::TaskList, Pane::Details]);
+}
+
+#[test]
+fn test_overlays_stack() {
+    let mut app = app_with_task();
+    app.focus = Pane::Details;
+    app.handle_action(Action::ToggleHelp);
+    app.handle_action(Action::StartHelpSearch);
+    assert_eq!(app.mode(), Mode::Input);
+    assert!(matches!(app.input_mode(), Some(InputMode::HelpSearch)));
+
+    // Closing the search returns to the help, then to the focused pane
+    app.handle_action(Action::CancelInput);
+    assert_eq!(app.mode(), Mode::Help);
+    app.handle_action(Action::CloseHelp);
+    assert_eq!(app.mode(), Mode::Details);
+    assert!(app.overlays.is_empty());
+
+    // Opening an overlay again brings it to the top
+    app.open(Overlay::Activity);
+    app.open(Overlay::Messages);
+    app.open(Overlay::Activity);
+    assert_eq!(app.overlays.len(), 2);
+    assert_eq!(app.mode(), Mode::Activity);
+
+    app.handle_action(Action::RemoveTask);
+    assert_eq!(app.confirm_delete(), Some(0));
+    app.handle_action(Action::CancelConfirm);
+    assert_eq!(app.confirm_delete(), None);
+    assert_eq!(app.mode(), Mode::Activity);
+}
+
+#[test]
+fn test_docked_log_pane() {
+    let mut app = app_with_task();
+    app.handle_action(Action::ToggleLogPane);
+    let jobs = app.take_jobs();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].label, None);
+    assert!(screen(&app, 100, 40).contains("Loading logs"));
+
+    let content: Vec<String> = (1..=50).map(|i| format!("line {}", i)).collect();
+    app.complete(
+        1,
+        Outcome::new().effect(Effect::FollowLogs {
+            task_id: 0,
+            content: content.join("\n"),
+        }),
+    );
+    let text = screen(&app, 100, 40);
+    assert!(text.contains("Log - Task #0 [END]"));
+    assert!(text.contains("line 50"));
+    assert!(!text.contains("line 1 "));
+
+    // Scrolling up stops following, scrolling back down follows again
+    app.handle_action(Action::ScrollLogPaneUp);
+    let max = app.log_pane_max_scroll.get();
+    assert_eq!(app.log_pane_scroll, max - 1);
+    assert!(!screen(&app, 100, 40).contains("line 50"));
+    app.handle_action(Action::ScrollLogPaneDown);
+    assert_eq!(app.log_pane_scroll, usize::MAX);
+    app.handle_action(Action::ScrollLogPaneTop);
+    assert!(screen(&app, 100, 40).contains("line 1 "));
+
+    // Already requested for this task; polls keep it fresh
+    app.handle_action(Action::NavigateDown);
+    assert!(app.take_jobs().is_empty());
+}
+
+#[test]
+fn test_details_scroll_and_mouse_focus() {
+    let mut app = app_with_task();
+    app.config.layout.show_status_bar = false;
+    app.config.layout.show_help_bar = false;
+    // Too short for all the details
+    assert!(screen(&app, 100, 10).contains("Task #0"));
+    let max = app.details_max_scroll.get();
+    assert!(max > 0);
+    for _ in 0..50 {
+        app.handle_action(Action::ScrollDetailsDown);
+    }
+    assert_eq!(app.details_scroll, max);
+    assert!(!screen(&app, 100, 10).contains("Task #0"));
+    app.handle_action(Action::ScrollDetailsTop);
+    assert_eq!(app.details_scroll, 0);
+
+    let click = MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column: 80,
+        row: 5,
+        modifiers: KeyModifiers::NONE,
+    };
+    let action = handle_mouse_event(&mut app, click);
+    assert_eq!(action, Some(Action::Focus(Pane::Details)));
+    app.handle_action(action.unwrap());
+    assert_eq!(app.mode(), Mode::Details);
+}
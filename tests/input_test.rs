@@ -0,0 +1,167 @@
+use lazypueue::app::{Action, App, InputMode};
+use lazypueue::complete::complete;
+use lazypueue::history::{History, HISTORY_CAPACITY};
+use lazypueue::ui::TextInput;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("lazypueue-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        app.handle_action(Action::InputChar(c));
+    }
+}
+
+#[test]
+fn test_history_deduplicates_and_persists() {
+    let mut history = History::new();
+    history.push("make");
+    history.push("cargo test\n  --workspace");
+    history.push("make");
+    history.push("   ");
+    assert_eq!(
+        history.iter().collect::<Vec<_>>(),
+        vec!["make", "cargo test\n  --workspace"]
+    );
+
+    // Task commands go before everything else
+    history.seed(["sleep 10", "make", "sleep 10"]);
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.get(2), Some("sleep 10"));
+    assert_eq!(history.search("test", 0), Some(1));
+    assert_eq!(history.search("e", 1), Some(1));
+    assert_eq!(history.search("nope", 0), None);
+
+    let dir = temp_dir("history");
+    let path = dir.join("state").join("history");
+    history.save(&path).unwrap();
+    let loaded = History::load(&path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    // Seeded commands aren't saved
+    assert_eq!(
+        loaded.iter().collect::<Vec<_>>(),
+        vec!["make", "cargo test\n  --workspace"]
+    );
+    assert!(History::load(&dir.join("missing")).unwrap().is_empty());
+
+    for n in 0..HISTORY_CAPACITY + 5 {
+        history.push(format!("echo {}", n));
+    }
+    assert_eq!(history.len(), HISTORY_CAPACITY);
+}
+
+#[test]
+fn test_word_editing() {
+    let mut input = TextInput::with_value("rsync -av src/ host:/backup".to_string());
+    input.move_word_left();
+    assert_eq!(&input.value[input.cursor..], "backup");
+    input.move_word_left();
+    input.move_word_left();
+    assert_eq!(&input.value[input.cursor..], "src/ host:/backup");
+    input.move_word_right();
+    assert_eq!(&input.value[input.cursor..], "/ host:/backup");
+
+    input.move_end();
+    input.delete_word();
+    assert_eq!(input.value, "rsync -av src/ ");
+    input.delete_word();
+    assert_eq!(input.value, "rsync -av ");
+    assert_eq!(input.cursor, input.value.len());
+}
+
+#[test]
+fn test_completes_paths_and_executables() {
+    let dir = temp_dir("complete");
+    std::fs::create_dir_all(dir.join("bin")).unwrap();
+    std::fs::create_dir_all(dir.join("src/nested")).unwrap();
+    std::fs::write(dir.join("script.sh"), "").unwrap();
+    std::fs::write(dir.join(".hidden"), "").unwrap();
+    for tool in ["lazytool", "lazyother"] {
+        let tool = dir.join("bin").join(tool);
+        std::fs::write(&tool, "").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+    let path = OsString::from(dir.join("bin"));
+
+    let c = complete("lazyt", 5, Some(&path), &dir).unwrap();
+    assert_eq!((c.start, c.replacement.as_str()), (0, "lazytool "));
+
+    let c = complete("lazy", 4, Some(&path), &dir).unwrap();
+    assert_eq!(c.replacement, "lazy");
+    assert_eq!(c.candidates, vec!["lazyother", "lazytool"]);
+
+    let c = complete("bash sc", 7, Some(&path), &dir).unwrap();
+    assert_eq!((c.start, c.replacement.as_str()), (5, "script.sh "));
+
+    let c = complete("ls s", 4, Some(&path), &dir).unwrap();
+    assert_eq!(c.candidates, vec!["script.sh", "src/"]);
+
+    let c = complete("ls src/n", 8, Some(&path), &dir).unwrap();
+    assert_eq!(c.replacement, "src/nested/");
+
+    // Names the shell would split or expand are escaped, and escaped words
+    // complete further
+    std::fs::create_dir_all(dir.join("my files")).unwrap();
+    std::fs::write(dir.join("my files/a&b.txt"), "").unwrap();
+    let c = complete("ls my", 5, Some(&path), &dir).unwrap();
+    assert_eq!((c.start, c.replacement.as_str()), (3, "my\\ files/"));
+    let c = complete("ls my\\ files/a", 14, Some(&path), &dir).unwrap();
+    assert_eq!(
+        (c.start, c.replacement.as_str()),
+        (3, "my\\ files/a\\&b.txt ")
+    );
+
+    assert!(complete("ls .h", 5, Some(&path), &dir).is_some());
+    assert!(complete("ls h", 4, Some(&path), &dir).is_none());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_browse_and_search_history_in_add_dialog() {
+    let mut app = App::new();
+    app.history.push("make build");
+    app.history.push("cargo test");
+
+    app.handle_action(Action::StartAddTask);
+    type_text(&mut app, "draft");
+    app.handle_action(Action::InputHistoryPrev);
+    assert_eq!(app.text_input.value, "cargo test");
+    app.handle_action(Action::InputHistoryPrev);
+    app.handle_action(Action::InputHistoryPrev);
+    assert_eq!(app.text_input.value, "make build");
+    app.handle_action(Action::InputHistoryNext);
+    app.handle_action(Action::InputHistoryNext);
+    assert_eq!(app.text_input.value, "draft");
+
+    // Ctrl-R, type, accept with Enter, submit with another Enter
+    app.handle_action(Action::InputHistorySearch);
+    type_text(&mut app, "ma");
+    assert_eq!(app.text_input.value, "make build");
+    type_text(&mut app, "x");
+    assert_eq!(app.history_search.as_ref().unwrap().found, None);
+    app.handle_action(Action::CancelInput);
+    assert_eq!(app.text_input.value, "draft");
+    assert!(matches!(app.input_mode(), Some(InputMode::AddTask)));
+
+    app.handle_action(Action::InputHistorySearch);
+    type_text(&mut app, "t");
+    assert_eq!(app.text_input.value, "cargo test");
+    app.handle_action(Action::InputHistorySearch);
+    assert_eq!(app.text_input.value, "cargo test");
+    app.handle_action(Action::SubmitInput);
+    assert!(app.history_search.is_none());
+    assert!(app.take_jobs().is_empty());
+    app.handle_action(Action::SubmitInput);
+
+    assert_eq!(app.take_jobs().len(), 1);
+    assert_eq!(app.history.get(0), Some("cargo test"));
+}
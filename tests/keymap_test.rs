@@ -0,0 +1,134 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use lazypueue::app::{Action, App};
+use lazypueue::keymap::{KeyChord, KeyResult, Keymap, KeymapError, KeysConfig, Mode};
+use lazypueue::snapshot::render_frame;
+use std::collections::BTreeMap;
+
+fn key(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+}
+
+fn overrides(mode: Mode, bindings: &[(&str, &str)]) -> KeysConfig {
+    let table: BTreeMap<String, String> = bindings
+        .iter()
+        .map(|(k, a)| (k.to_string(), a.to_string()))
+        .collect();
+    KeysConfig::from([(mode, table)])
+}
+
+#[test]
+fn test_parse_chords() {
+    let chord = KeyChord::parse("ctrl-d").unwrap();
+    assert_eq!(chord.code, KeyCode::Char('d'));
+    assert_eq!(chord.modifiers, KeyModifiers::CONTROL);
+    assert_eq!(
+        KeyChord::parse("shift-g").unwrap(),
+        KeyChord::parse("G").unwrap()
+    );
+    assert_eq!(KeyChord::parse("ctrl--").unwrap().code, KeyCode::Char('-'));
+    assert_eq!(KeyChord::parse("space").unwrap().to_string(), "space");
+    assert!(KeyChord::parse("gg").is_err());
+}
+
+#[test]
+fn test_multi_key_sequences() {
+    let mut keymap = Keymap::default();
+    assert_eq!(
+        keymap.handle_key(Mode::Normal, key('g')),
+        KeyResult::Pending
+    );
+    assert_eq!(
+        keymap.handle_key(Mode::Normal, key('g')),
+        KeyResult::Action(Action::NavigateTop)
+    );
+
+    // A key that doesn't continue the sequence starts over
+    assert_eq!(
+        keymap.handle_key(Mode::Normal, key('d')),
+        KeyResult::Pending
+    );
+    assert_eq!(
+        keymap.handle_key(Mode::Normal, key('j')),
+        KeyResult::Action(Action::NavigateDown)
+    );
+    assert!(keymap.pending().is_empty());
+
+    // Shifted characters match their uppercase binding
+    assert_eq!(
+        keymap.handle_key(
+            Mode::Normal,
+            KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)
+        ),
+        KeyResult::Action(Action::NavigateBottom)
+    );
+}
+
+#[test]
+fn test_config_overrides() {
+    let mut keymap = Keymap::from_config(&overrides(
+        Mode::Normal,
+        &[("x", "none"), ("ctrl-r", "refresh")],
+    ))
+    .unwrap();
+    assert_eq!(
+        keymap.handle_key(Mode::Normal, key('x')),
+        KeyResult::Unbound
+    );
+    assert_eq!(
+        keymap.handle_key(
+            Mode::Normal,
+            KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL)
+        ),
+        KeyResult::Action(Action::Refresh)
+    );
+}
+
+#[test]
+fn test_config_errors() {
+    let err = Keymap::from_config(&overrides(Mode::Normal, &[("g", "refresh")])).unwrap_err();
+    assert!(matches!(err, KeymapError::Conflict { .. }));
+    assert!(err.to_string().contains("keys.normal"), "{}", err);
+
+    let err = Keymap::from_config(&overrides(Mode::Log, &[("z", "fly")])).unwrap_err();
+    assert!(matches!(err, KeymapError::UnknownAction { .. }));
+
+    let err = Keymap::from_config(&overrides(Mode::Input, &[("q", "cancel_input")])).unwrap_err();
+    assert!(matches!(err, KeymapError::InvalidKey { .. }));
+}
+
+#[test]
+fn test_help_entries_cover_every_binding() {
+    let keymap = Keymap::default();
+    let sections = keymap.help_entries("");
+    for mode in Mode::ALL {
+        let entries = &sections.iter().find(|(m, _)| *m == mode).unwrap().1;
+        for binding in keymap.bindings(mode) {
+            assert!(entries
+                .iter()
+                .any(|e| e.action == binding.action && e.keys.contains(&binding.keys.to_string())));
+        }
+    }
+
+    let sections = keymap.help_entries("kill");
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].1[0].action, Action::KillTask);
+    assert_eq!(sections[0].1[0].keys, vec!["K"]);
+}
+
+#[test]
+fn test_help_scroll_stays_within_the_entries() {
+    let mut app = App::new();
+    app.handle_action(Action::ToggleHelp);
+    render_frame(&app, 100, 30).unwrap();
+    let max = app.help_max_scroll.get();
+    assert!(max > 20);
+
+    app.handle_action(Action::ScrollHelpPageDown);
+    assert_eq!(app.help_scroll, 20);
+    for _ in 0..20 {
+        app.handle_action(Action::ScrollHelpPageDown);
+    }
+    assert_eq!(app.help_scroll, max);
+    app.handle_action(Action::ScrollHelpUp);
+    assert_eq!(app.help_scroll, max - 1);
+}
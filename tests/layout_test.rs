@@ -0,0 +1,161 @@
+use lazypueue::app::{Action, App};
+use lazypueue::config::{Config, LayoutConfig};
+use lazypueue::layout::{LayoutPreset, LayoutState};
+use lazypueue::snapshot::render_frame;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Direction;
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("lazypueue-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn lines(buffer: &Buffer) -> Vec<String> {
+    (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        })
+        .collect()
+}
+
+/// Row and column of the first occurrence of `text`
+fn find(lines: &[String], text: &str) -> Option<(usize, usize)> {
+    lines
+        .iter()
+        .enumerate()
+        .find_map(|(row, line)| line.find(text).map(|col| (row, col)))
+}
+
+#[test]
+fn test_preset_direction() {
+    assert_eq!(LayoutPreset::Auto.direction(80, 100), Direction::Vertical);
+    assert_eq!(
+        LayoutPreset::Auto.direction(120, 100),
+        Direction::Horizontal
+    );
+    assert_eq!(
+        LayoutPreset::Horizontal.direction(40, 100),
+        Direction::Horizontal
+    );
+    assert_eq!(
+        LayoutPreset::Vertical.direction(200, 100),
+        Direction::Vertical
+    );
+    assert_eq!(LayoutPreset::Vertical.next(), LayoutPreset::Auto);
+}
+
+#[test]
+fn test_layout_config() {
+    let config = Config::parse(
+        "[layout]\npreset = \"vertical\"\ntask_list_percent = 30\nshow_help_bar = false\n",
+        Path::new("config.toml"),
+    )
+    .unwrap();
+    assert_eq!(config.layout.preset, LayoutPreset::Vertical);
+    assert_eq!(config.layout.task_list_percent, 30);
+    assert!(config.layout.show_status_bar);
+    assert!(!config.layout.show_help_bar);
+    assert!(Config::parse(
+        "[layout]\npreset = \"diagonal\"\n",
+        Path::new("config.toml")
+    )
+    .is_err());
+}
+
+#[test]
+fn test_saved_layout_overrides_config() {
+    let path = temp_dir("layout").join("layout.toml");
+    assert_eq!(LayoutState::load(&path).unwrap(), LayoutState::default());
+
+    let state = LayoutState {
+        preset: Some(LayoutPreset::Horizontal),
+        task_list_percent: Some(95),
+        show_status_bar: None,
+        show_help_bar: Some(false),
+        show_log_pane: None,
+    };
+    state.save(&path).unwrap();
+    let loaded = LayoutState::load(&path).unwrap();
+    assert_eq!(loaded, state);
+
+    let mut layout = LayoutConfig {
+        preset: LayoutPreset::Vertical,
+        show_status_bar: false,
+        ..LayoutConfig::default()
+    };
+    loaded.apply(&mut layout);
+    assert_eq!(layout.preset, LayoutPreset::Horizontal);
+    assert_eq!(layout.task_list_percent, 90);
+    assert!(!layout.show_status_bar);
+    assert!(!layout.show_help_bar);
+}
+
+#[test]
+fn test_layout_changes_are_saved() {
+    let path = temp_dir("layout-actions").join("layout.toml");
+    let mut app = App::new();
+    app.layout_path = Some(path.clone());
+    let percent = app.config.layout.task_list_percent;
+
+    app.handle_action(Action::GrowTaskList);
+    assert_eq!(app.config.layout.task_list_percent, percent + 5);
+    for _ in 0..20 {
+        app.handle_action(Action::ShrinkTaskList);
+    }
+    assert_eq!(app.config.layout.task_list_percent, 10);
+
+    let preset = app.config.layout.preset;
+    app.handle_action(Action::CycleLayout);
+    assert_eq!(app.config.layout.preset, preset.next());
+    app.handle_action(Action::ToggleStatusBar);
+    assert!(!app.config.layout.show_status_bar);
+
+    let saved = LayoutState::load(&path).unwrap();
+    assert_eq!(saved.task_list_percent, Some(10));
+    assert_eq!(saved.preset, Some(preset.next()));
+    assert_eq!(saved.show_status_bar, Some(false));
+    assert_eq!(saved.show_help_bar, None);
+}
+
+#[test]
+fn test_render_presets() {
+    let mut app = App::new();
+    app.config.layout.preset = LayoutPreset::Horizontal;
+    let screen = lines(&render_frame(&app, 120, 30).unwrap());
+    let (tasks_row, _) = find(&screen, "Tasks").unwrap();
+    let (details_row, details_col) = find(&screen, "Details").unwrap();
+    assert_eq!(tasks_row, details_row);
+    assert!(details_col > 40);
+
+    // Auto stacks the panes on a narrow terminal
+    app.config.layout.preset = LayoutPreset::Auto;
+    let screen = lines(&render_frame(&app, 70, 30).unwrap());
+    let (tasks_row, _) = find(&screen, "Tasks").unwrap();
+    let (details_row, _) = find(&screen, "Details").unwrap();
+    assert!(details_row > tasks_row);
+}
+
+#[test]
+fn test_zoom_and_hidden_bars() {
+    let mut app = App::new();
+    let screen = lines(&render_frame(&app, 120, 30).unwrap());
+    assert!(find(&screen, "Help").is_some());
+    assert!(find(&screen, "Status").is_some());
+
+    app.handle_action(Action::ToggleHelpBar);
+    app.handle_action(Action::ToggleStatusBar);
+    app.handle_action(Action::ToggleZoom);
+    let screen = lines(&render_frame(&app, 120, 30).unwrap());
+    assert!(find(&screen, "Help").is_none());
+    assert!(find(&screen, "Status").is_none());
+    assert!(find(&screen, "Details").is_none());
+    assert_eq!(find(&screen, "Tasks").map(|(row, _)| row), Some(0));
+
+    // Resizing leaves the zoom
+    app.handle_action(Action::GrowTaskList);
+    assert!(!app.zoomed);
+}
@@ -0,0 +1,84 @@
+use lazypueue::app::{Action, App};
+use lazypueue::messages::{MessageLevel, Messages, HISTORY_CAPACITY, MAX_TOASTS};
+use lazypueue::ui;
+use ratatui::{backend::TestBackend, Terminal};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_toasts_expire_by_level() {
+    let mut messages = Messages::new();
+    messages.success("Stashed task #1");
+    messages.error("Failed to kill task");
+
+    let now = Instant::now();
+    assert_eq!(messages.toasts(now).len(), 2);
+
+    // Errors stay longer than success messages
+    let later = now + Duration::from_secs(4);
+    let toasts = messages.toasts(later);
+    assert_eq!(toasts.len(), 1);
+    assert_eq!(toasts[0].level, MessageLevel::Error);
+
+    assert!(messages.toasts(now + Duration::from_secs(10)).is_empty());
+    // Expired toasts stay in the history
+    assert_eq!(messages.len(), 2);
+    assert_eq!(
+        messages.history().next().unwrap().text,
+        "Failed to kill task"
+    );
+}
+
+#[test]
+fn test_history_and_toasts_are_bounded() {
+    let mut messages = Messages::new();
+    for i in 0..HISTORY_CAPACITY + 10 {
+        messages.info(format!("message {}", i));
+    }
+    assert_eq!(messages.len(), HISTORY_CAPACITY);
+
+    let toasts = messages.toasts(Instant::now());
+    assert_eq!(toasts.len(), MAX_TOASTS);
+    // The newest toast is last, at the bottom of the stack
+    assert_eq!(
+        toasts.last().unwrap().text,
+        format!("message {}", HISTORY_CAPACITY + 9)
+    );
+}
+
+#[test]
+fn test_action_errors_keep_the_ui() {
+    let mut app = App::new();
+    app.messages.error("Failed to stash task");
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|f| ui::render(f, &app)).unwrap();
+    let content: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|c| c.symbol())
+        .collect();
+    assert!(content.contains("Failed to stash task"));
+    assert!(content.contains("Status"));
+}
+
+#[test]
+fn test_messages_scroll_stays_within_the_history() {
+    let mut app = App::new();
+    for i in 0..40 {
+        app.messages.info(format!("message {}", i));
+    }
+    app.handle_action(Action::ToggleMessages);
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|f| ui::render(f, &app)).unwrap();
+    let max = app.messages_max_scroll.get();
+    assert!(max > 0);
+
+    for _ in 0..100 {
+        app.handle_action(Action::ScrollMessagesDown);
+    }
+    assert_eq!(app.messages_scroll, max);
+    app.handle_action(Action::ScrollMessagesUp);
+    assert_eq!(app.messages_scroll, max - 1);
+}
@@ -0,0 +1,114 @@
+use chrono::Local;
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use lazypueue::app::{Action, App, Overlay, TreeItem};
+use lazypueue::events::handle_mouse_event;
+use lazypueue::snapshot::render_frame;
+use lazypueue::ui;
+use pueue_lib::state::{Group, GroupStatus, State};
+use pueue_lib::task::{Task, TaskStatus};
+use ratatui::{backend::TestBackend, Terminal};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn app_with_tasks() -> App {
+    let mut state = State::new();
+    state.groups.insert(
+        "default".to_string(),
+        Group {
+            status: GroupStatus::Running,
+            parallel_tasks: 1,
+        },
+    );
+    for command in ["sleep 10", "make"] {
+        state.add_task(Task::new(
+            command.to_string(),
+            PathBuf::from("/tmp"),
+            HashMap::new(),
+            "default".to_string(),
+            TaskStatus::Queued {
+                enqueued_at: Local::now(),
+            },
+            vec![],
+            0,
+            None,
+        ));
+    }
+
+    let mut app = App::new();
+    app.state = Some(state);
+    app
+}
+
+fn draw(app: &App) {
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|f| ui::render(f, app)).unwrap();
+}
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+    MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    }
+}
+
+#[test]
+fn test_click_and_double_click_rows() {
+    let mut app = app_with_tasks();
+    draw(&app);
+
+    // Status bar takes 3 rows, then the task list border; row 4 is the group
+    let click = mouse(MouseEventKind::Down(MouseButton::Left), 5, 4);
+    assert_eq!(
+        handle_mouse_event(&mut app, click),
+        Some(Action::SelectItem(TreeItem::Group("default".to_string())))
+    );
+
+    let click = mouse(MouseEventKind::Down(MouseButton::Left), 5, 6);
+    let task = TreeItem::Task("default".to_string(), 1);
+    assert_eq!(
+        handle_mouse_event(&mut app, click),
+        Some(Action::SelectItem(task.clone()))
+    );
+    assert_eq!(
+        handle_mouse_event(&mut app, click),
+        Some(Action::OpenItem(task))
+    );
+
+    let wheel = mouse(MouseEventKind::ScrollDown, 5, 10);
+    assert_eq!(
+        handle_mouse_event(&mut app, wheel),
+        Some(Action::NavigateDown)
+    );
+}
+
+#[test]
+fn test_only_the_topmost_dialog_reacts() {
+    let mut app = app_with_tasks();
+    app.open(Overlay::ConfirmDelete(0));
+    draw(&app);
+
+    // The task list is covered by the modal dialog
+    let click = mouse(MouseEventKind::Down(MouseButton::Left), 5, 4);
+    assert_eq!(handle_mouse_event(&mut app, click), None);
+
+    let regions = app.hit_regions.borrow().clone();
+    let yes = (0..24)
+        .flat_map(|row| (0..80).map(move |column| (column, row)))
+        .find(|&(column, row)| {
+            regions
+                .hits(column, row)
+                .any(|t| *t == ui::HitTarget::ConfirmYes)
+        })
+        .unwrap();
+    // The buttons name their keys
+    let buffer = render_frame(&app, 80, 24).unwrap();
+    let buttons: String = (0..80).map(|x| buffer[(x, yes.1)].symbol()).collect();
+    assert!(buttons.contains("[ Yes (y) ]  [ No (n) ]"));
+    let click = mouse(MouseEventKind::Down(MouseButton::Left), yes.0, yes.1);
+    assert_eq!(
+        handle_mouse_event(&mut app, click),
+        Some(Action::ConfirmAction)
+    );
+}
@@ -0,0 +1,77 @@
+use chrono::Local;
+use lazypueue::move_tasks::{plan_move, remap_dependencies, SkipReason};
+use pueue_lib::state::State;
+use pueue_lib::task::{Task, TaskStatus};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn add(state: &mut State, group: &str, status: TaskStatus, dependencies: Vec<usize>) -> usize {
+    state.add_task(Task::new(
+        "true".to_string(),
+        PathBuf::from("/tmp"),
+        HashMap::new(),
+        group.to_string(),
+        status,
+        dependencies,
+        0,
+        None,
+    ))
+}
+
+fn queued() -> TaskStatus {
+    TaskStatus::Queued {
+        enqueued_at: Local::now(),
+    }
+}
+
+#[test]
+fn test_plan_move_skips_unmovable_tasks() {
+    let mut state = State::new();
+    let running = add(
+        &mut state,
+        "default",
+        TaskStatus::Running {
+            enqueued_at: Local::now(),
+            start: Local::now(),
+        },
+        vec![],
+    );
+    let stashed = add(
+        &mut state,
+        "default",
+        TaskStatus::Stashed { enqueue_at: None },
+        vec![],
+    );
+    let in_target = add(&mut state, "build", queued(), vec![]);
+
+    let plan = plan_move(&state, &[running, stashed, in_target], "build");
+    assert_eq!(plan.tasks, vec![stashed]);
+    assert_eq!(
+        plan.skipped,
+        vec![
+            (running, SkipReason::NotQueued),
+            (in_target, SkipReason::SameGroup)
+        ]
+    );
+}
+
+#[test]
+fn test_plan_move_keeps_dependency_links() {
+    let mut state = State::new();
+    let base = add(&mut state, "default", queued(), vec![]);
+    let dependant = add(&mut state, "default", queued(), vec![base]);
+
+    // Moving both keeps the link by remapping it
+    let plan = plan_move(&state, &[dependant, base], "build");
+    assert_eq!(plan.tasks, vec![base, dependant]);
+    let new_ids = HashMap::from([(base, 10)]);
+    assert_eq!(
+        remap_dependencies(&state.tasks[&dependant].dependencies, &new_ids),
+        vec![10]
+    );
+
+    // Moving only the base would orphan the dependant
+    let plan = plan_move(&state, &[base], "build");
+    assert!(plan.tasks.is_empty());
+    assert_eq!(plan.skipped, vec![(base, SkipReason::HasDependants)]);
+}
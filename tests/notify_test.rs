@@ -0,0 +1,104 @@
+use chrono::Local;
+use lazypueue::notify::{finished_tasks, osc, substitute, Notifier, NotifyConfig, ResultFilter};
+use pueue_lib::state::State;
+use pueue_lib::task::{Task, TaskResult, TaskStatus};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn task(command: &str, group: &str, status: TaskStatus) -> Task {
+    Task::new(
+        command.to_string(),
+        PathBuf::from("/tmp"),
+        HashMap::new(),
+        group.to_string(),
+        status,
+        vec![],
+        0,
+        None,
+    )
+}
+
+fn running() -> TaskStatus {
+    TaskStatus::Running {
+        enqueued_at: Local::now(),
+        start: Local::now(),
+    }
+}
+
+fn done(result: TaskResult) -> TaskStatus {
+    TaskStatus::Done {
+        enqueued_at: Local::now(),
+        start: Local::now(),
+        end: Local::now(),
+        result,
+    }
+}
+
+#[test]
+fn test_finished_tasks_detects_transitions() {
+    let mut previous = State::new();
+    previous.add_task(task("sleep 1", "default", running()));
+    previous.add_task(task("true", "default", done(TaskResult::Success)));
+    previous.add_task(task("sleep 2", "default", running()));
+
+    let mut new = previous.clone();
+    new.tasks.get_mut(&0).unwrap().status = done(TaskResult::Failed(3));
+    // Added and finished between two polls
+    new.add_task(task("echo hi", "build", done(TaskResult::Success)));
+
+    let finished = finished_tasks(&previous, &new);
+    let ids: Vec<usize> = finished.iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![0, 3]);
+    assert_eq!(finished[0].result, TaskResult::Failed(3));
+    assert_eq!(finished[0].exit_code(), "3");
+    assert_eq!(finished[1].group, "build");
+}
+
+#[test]
+fn test_notifier_filters() {
+    let mut state = State::new();
+    state.add_task(task("make", "build", done(TaskResult::Killed)));
+    let finished = finished_tasks(&State::new(), &state).remove(0);
+
+    let notifier = Notifier::new(NotifyConfig {
+        groups: vec!["build".to_string()],
+        result: ResultFilter::Failure,
+        ..Default::default()
+    });
+    assert!(notifier.matches(&finished));
+
+    let notifier = Notifier::new(NotifyConfig {
+        result: ResultFilter::Success,
+        ..Default::default()
+    });
+    assert!(!notifier.matches(&finished));
+
+    let notifier = Notifier::new(NotifyConfig {
+        labels: vec!["nightly".to_string()],
+        ..Default::default()
+    });
+    assert!(!notifier.matches(&finished));
+}
+
+#[test]
+fn test_substitute_quotes_values() {
+    let mut state = State::new();
+    state.add_task(task("echo 'hi'", "default", done(TaskResult::Failed(1))));
+    let finished = finished_tasks(&State::new(), &state).remove(0);
+
+    assert_eq!(
+        substitute("notify-send {result} {command} #{id} {unknown}", &finished),
+        r"notify-send failed 'echo '\''hi'\''' #0 {unknown}"
+    );
+    assert_eq!(substitute("exit {exit_code} {", &finished), "exit 1 {");
+}
+
+#[test]
+fn test_osc_strips_control_characters() {
+    std::env::remove_var("TMUX");
+    // A BEL would end the notification and let the rest reach the terminal
+    assert_eq!(
+        osc("9;done\x07\x1b]0;title\x1b\\\n"),
+        "\x1b]9;done  ]0;title \\ \x07"
+    );
+}
@@ -0,0 +1,65 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use lazypueue::app::{Action, App, Overlay};
+use lazypueue::events::handle_key_event;
+use lazypueue::fuzzy::fuzzy_match;
+use lazypueue::keymap::Mode;
+use lazypueue::palette::{filter_entries, palette_entries};
+use pueue_lib::message::request::Signal;
+
+fn key(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+}
+
+#[test]
+fn test_fuzzy_match_prefers_word_starts() {
+    assert!(fuzzy_match("xyz", "Kill the selected task").is_none());
+
+    let m = fuzzy_match("kst", "Kill the selected task").unwrap();
+    assert_eq!(m.positions, vec![0, 9, 18]);
+
+    let word_starts = fuzzy_match("rt", "Restart task").unwrap();
+    let inside = fuzzy_match("rt", "Resort").unwrap();
+    assert!(word_starts.score > inside.score);
+}
+
+#[test]
+fn test_palette_entries_show_keys_and_parameters() {
+    let app = App::new();
+    let entries = palette_entries(&app, "");
+    let kill = entries
+        .iter()
+        .find(|e| e.action == Some(Action::KillTask))
+        .unwrap();
+    assert_eq!(kill.keys.as_deref(), Some("K"));
+    // Dialog-only actions aren't commands
+    assert!(!entries
+        .iter()
+        .any(|e| e.action == Some(Action::ScrollLogUp)));
+    assert!(entries
+        .iter()
+        .any(|e| e.action == Some(Action::KillWithSignal(Signal::SigInt))));
+
+    let query = "parallel 4";
+    let matches = filter_entries(palette_entries(&app, query), query);
+    assert_eq!(matches[0].entry.action, Some(Action::SetParallel(4)));
+}
+
+#[test]
+fn test_palette_keys_type_into_query() {
+    let mut app = App::new();
+    assert_eq!(
+        handle_key_event(&mut app, key(':')),
+        Some(Action::OpenPalette)
+    );
+
+    app.open(Overlay::Palette);
+    assert_eq!(app.mode(), Mode::Palette);
+    assert_eq!(
+        handle_key_event(&mut app, key('q')),
+        Some(Action::InputChar('q'))
+    );
+    assert_eq!(
+        handle_key_event(&mut app, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)),
+        Some(Action::ExecutePalette)
+    );
+}
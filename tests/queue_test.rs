@@ -0,0 +1,77 @@
+use chrono::Local;
+use lazypueue::queue::{plan_switches, queue_order, QueueError, QueueMove};
+use pueue_lib::state::State;
+use pueue_lib::task::{Task, TaskStatus};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn add(state: &mut State, group: &str, status: TaskStatus, priority: i32) -> usize {
+    state.add_task(Task::new(
+        "true".to_string(),
+        PathBuf::from("/tmp"),
+        HashMap::new(),
+        group.to_string(),
+        status,
+        vec![],
+        priority,
+        None,
+    ))
+}
+
+fn queued() -> TaskStatus {
+    TaskStatus::Queued {
+        enqueued_at: Local::now(),
+    }
+}
+
+#[test]
+fn test_reordering_stays_within_group_and_priority() {
+    let mut state = State::new();
+    let running = add(
+        &mut state,
+        "default",
+        TaskStatus::Running {
+            enqueued_at: Local::now(),
+            start: Local::now(),
+        },
+        0,
+    );
+    let urgent = add(&mut state, "default", queued(), 5);
+    let other_group = add(&mut state, "build", queued(), 0);
+    let a = add(&mut state, "default", queued(), 0);
+    let b = add(
+        &mut state,
+        "default",
+        TaskStatus::Stashed { enqueue_at: None },
+        0,
+    );
+    let c = add(&mut state, "default", queued(), 0);
+
+    assert_eq!(queue_order(&state, "default"), vec![urgent, a, b, c]);
+
+    assert_eq!(plan_switches(&state, c, QueueMove::Up), Ok(vec![(c, b)]));
+    assert_eq!(
+        plan_switches(&state, c, QueueMove::Top),
+        Ok(vec![(c, b), (b, a)])
+    );
+    assert_eq!(
+        plan_switches(&state, a, QueueMove::Up),
+        Err(QueueError::HigherPriority(urgent))
+    );
+    assert_eq!(
+        plan_switches(&state, c, QueueMove::Bottom),
+        Err(QueueError::AtBottom)
+    );
+    assert_eq!(
+        plan_switches(&state, urgent, QueueMove::Top),
+        Err(QueueError::AtTop)
+    );
+    assert_eq!(
+        plan_switches(&state, running, QueueMove::Down),
+        Err(QueueError::NotQueued)
+    );
+    assert_eq!(
+        plan_switches(&state, other_group, QueueMove::Down),
+        Err(QueueError::AtBottom)
+    );
+}
@@ -0,0 +1,61 @@
+use chrono::Local;
+use lazypueue::app::{App, TreeSelection};
+use lazypueue::ui::{self, scroll_offset};
+use pueue_lib::state::{Group, GroupStatus, State};
+use pueue_lib::task::{Task, TaskStatus};
+use ratatui::{backend::TestBackend, Terminal};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[test]
+fn test_scroll_offset_keeps_selection_visible() {
+    // Selection below the view scrolls down just enough
+    assert_eq!(scroll_offset(0, Some(25), 100, 10), 16);
+    // Selection above the view scrolls up to it
+    assert_eq!(scroll_offset(50, Some(20), 100, 10), 20);
+    // A visible selection keeps the offset
+    assert_eq!(scroll_offset(15, Some(20), 100, 10), 15);
+    // Never scroll past the end, e.g. after the terminal grew
+    assert_eq!(scroll_offset(95, Some(99), 100, 10), 90);
+    assert_eq!(scroll_offset(5, None, 3, 10), 0);
+}
+
+#[test]
+fn test_large_task_list_renders_selection() {
+    let mut state = State::new();
+    state.groups.insert(
+        "default".to_string(),
+        Group {
+            status: GroupStatus::Running,
+            parallel_tasks: 1,
+        },
+    );
+    for i in 0..20_000 {
+        state.add_task(Task::new(
+            format!("echo {}", i),
+            PathBuf::from("/tmp"),
+            HashMap::new(),
+            "default".to_string(),
+            TaskStatus::Queued {
+                enqueued_at: Local::now(),
+            },
+            vec![],
+            0,
+            None,
+        ));
+    }
+
+    let mut app = App::new();
+    app.state = Some(state);
+    app.selection = TreeSelection::Task("default".to_string(), 19_999);
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|f| ui::render(f, &app)).unwrap();
+
+    // 20k tasks plus the group header, 16 visible rows
+    assert_eq!(app.task_list_height.get(), 16);
+    assert_eq!(app.task_list_offset.get(), 20_001 - 16);
+    let buffer = terminal.backend().buffer();
+    let content: String = buffer.content().iter().map(|c| c.symbol()).collect();
+    assert!(content.contains("#19999"));
+}
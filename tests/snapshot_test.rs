@@ -0,0 +1,83 @@
+use chrono::Local;
+use lazypueue::app::{App, TreeSelection};
+use lazypueue::snapshot::{render_frame, to_ansi, to_html, to_svg, to_text};
+use pueue_lib::state::{Group, GroupStatus, State};
+use pueue_lib::task::{Task, TaskStatus};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn buffer() -> Buffer {
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 10, 2));
+    buffer.set_string(0, 0, "ok", Style::default().fg(Color::Green));
+    buffer.set_string(3, 0, "<a&b>", Style::default().add_modifier(Modifier::BOLD));
+    buffer.set_string(0, 1, "日本", Style::default().bg(Color::Indexed(196)));
+    buffer
+}
+
+#[test]
+fn test_text_skips_wide_char_continuations() {
+    assert_eq!(to_text(&buffer()), "ok <a&b>\n日本\n");
+}
+
+#[test]
+fn test_ansi_codes() {
+    let ansi = to_ansi(&buffer());
+    let rows: Vec<&str> = ansi.lines().collect();
+    assert_eq!(
+        rows[0],
+        "\x1b[0;32mok\x1b[0m \x1b[0;1m<a&b>\x1b[0m  \x1b[0m"
+    );
+    assert!(rows[1].starts_with("\x1b[0;48;5;196m日本\x1b[0m"));
+}
+
+#[test]
+fn test_svg_and_html_escape_and_color() {
+    let svg = to_svg(&buffer());
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"84\" height=\"34\""));
+    assert!(svg.contains(">&lt;a&amp;b&gt;</text>"));
+    assert!(svg.contains("fill=\"#00cd00\""));
+    // Indexed 196 is pure red in the 256 color cube
+    assert!(svg.contains("<rect x=\"0\" y=\"17\" width=\"33.6\" height=\"17\" fill=\"#ff0000\"/>"));
+
+    let html = to_html(&buffer());
+    assert!(html.contains("<span style=\"color:#d0d0d0;font-weight:bold;\">&lt;a&amp;b&gt;</span>"));
+    assert!(html.contains("background:#ff0000;\">日本</span>"));
+    assert!(html.trim_end().ends_with("</html>"));
+}
+
+#[test]
+fn test_render_frame_of_app() {
+    let mut state = State::new();
+    state.groups.insert(
+        "default".to_string(),
+        Group {
+            status: GroupStatus::Running,
+            parallel_tasks: 1,
+        },
+    );
+    state.add_task(Task::new(
+        "sleep 60".to_string(),
+        PathBuf::from("/tmp"),
+        HashMap::new(),
+        "default".to_string(),
+        TaskStatus::Queued {
+            enqueued_at: Local::now(),
+        },
+        vec![],
+        0,
+        None,
+    ));
+    let mut app = App::new();
+    app.state = Some(state);
+    app.selection = TreeSelection::Task("default".to_string(), 0);
+
+    let buffer = render_frame(&app, 100, 30).unwrap();
+    assert_eq!(buffer.area, Rect::new(0, 0, 100, 30));
+    let text = to_text(&buffer);
+    assert_eq!(text.lines().count(), 30);
+    assert!(text.contains("#0"), "{}", text);
+    assert!(text.contains("sleep 60"), "{}", text);
+}
@@ -0,0 +1,100 @@
+// The tunnel tests run shell scripts standing in for ssh
+#![cfg(unix)]
+
+use lazypueue::config::Config;
+use lazypueue::ssh::{SshTarget, Tunnel};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Stand-in for ssh: creates the local end of `-L` and runs the remote
+/// command locally
+const FAKE_SSH: &str = r#"#!/bin/sh
+while [ $# -gt 1 ]; do
+    case "$1" in
+        -L) touch "${2%%:*}"; shift 2 ;;
+        *) shift ;;
+    esac
+done
+exec sh -c "$1"
+"#;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("lazypueue-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn script(dir: &Path, name: &str, content: &str) -> Vec<String> {
+    let path = dir.join(name);
+    std::fs::write(&path, content).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    vec![path.to_string_lossy().into_owned()]
+}
+
+#[test]
+fn test_parse_ssh_urls() {
+    assert_eq!(
+        SshTarget::parse("ssh://me@build1").unwrap(),
+        SshTarget {
+            destination: "me@build1".to_string(),
+            socket: None,
+        }
+    );
+    assert_eq!(
+        SshTarget::parse("ssh://build1:/run/user/1000/pueue_me.socket").unwrap(),
+        SshTarget {
+            destination: "build1".to_string(),
+            socket: Some("/run/user/1000/pueue_me.socket".to_string()),
+        }
+    );
+    assert!(SshTarget::parse("tcp://build1").is_err());
+    assert!(SshTarget::parse("ssh://me@").is_err());
+    assert!(SshTarget::parse("ssh://build1:pueue.socket").is_err());
+
+    let error = Config::parse(
+        "[daemons.build]\nurl = \"ssh://\"\n",
+        Path::new("config.toml"),
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("daemons.build.url"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_tunnel_reads_the_secret_and_cleans_up() {
+    let dir = temp_dir("ssh-tunnel");
+    // The secret next to the remote socket, with a trailing newline
+    std::fs::write(dir.join("shared_secret"), "s3cret\n").unwrap();
+    let target = SshTarget {
+        destination: "me@build1".to_string(),
+        socket: Some(dir.join("pueue.socket").to_string_lossy().into_owned()),
+    };
+    let command = script(&dir, "ssh", FAKE_SSH);
+
+    let tunnel = Tunnel::open(&target, &command).await.unwrap();
+    assert_eq!(tunnel.secret, b"s3cret\n");
+    assert!(tunnel.socket.exists());
+
+    let socket = tunnel.socket.clone();
+    drop(tunnel);
+    assert!(!socket.parent().unwrap().exists());
+}
+
+#[tokio::test]
+async fn test_tunnel_reports_ssh_errors() {
+    let dir = temp_dir("ssh-error");
+    let target = SshTarget {
+        destination: "me@build1".to_string(),
+        socket: Some("/run/pueue.socket".to_string()),
+    };
+    let command = script(
+        &dir,
+        "ssh",
+        "#!/bin/sh\necho 'me@build1: Permission denied (publickey).' >&2\nexit 255\n",
+    );
+
+    let error = Tunnel::open(&target, &command).await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "ssh to me@build1 failed: me@build1: Permission denied (publickey)."
+    );
+}
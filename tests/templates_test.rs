@@ -0,0 +1,101 @@
+use lazypueue::app::{Action, App, InputMode};
+use lazypueue::config::Config;
+use lazypueue::keymap::Mode;
+use lazypueue::palette::palette_matches;
+use lazypueue::templates::{append_to_config, fill, placeholders, Template};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+fn deploy() -> Template {
+    Template {
+        command: "deploy {env} --tag {tag} && echo ${HOME} {env}".to_string(),
+        group: Some("deploys".to_string()),
+        path: Some(PathBuf::from("~/src/app")),
+        label: Some("deploy".to_string()),
+        priority: Some(2),
+        env: BTreeMap::from([("RUST_LOG".to_string(), "info".to_string())]),
+    }
+}
+
+#[test]
+fn test_placeholders_are_distinct_and_skip_shell_syntax() {
+    let command = &deploy().command;
+    assert_eq!(placeholders(command), vec!["env", "tag"]);
+    assert!(placeholders("for f in *; do { echo $f; }; done").is_empty());
+
+    let values = HashMap::from([("env".to_string(), "prod".to_string())]);
+    assert_eq!(
+        fill(command, &values),
+        "deploy prod --tag {tag} && echo ${HOME} prod"
+    );
+}
+
+#[test]
+fn test_saved_template_round_trips_through_config() {
+    let dir = std::env::temp_dir().join(format!("lazypueue-templates-{}", std::process::id()));
+    let path = dir.join("config.toml");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&path, "# my settings\n[refresh]\ninterval_ms = 1000\n").unwrap();
+
+    append_to_config(&path, "deploy", &deploy()).unwrap();
+    append_to_config(
+        &path,
+        "build",
+        &Template {
+            command: "make".to_string(),
+            ..Template::default()
+        },
+    )
+    .unwrap();
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(content.starts_with("# my settings\n"));
+
+    let config = Config::parse(&content, &path).unwrap();
+    assert_eq!(config.refresh.interval_ms, 1000);
+    assert_eq!(config.templates["deploy"], deploy());
+    assert_eq!(config.templates["build"].command, "make");
+}
+
+#[test]
+fn test_template_prompts_for_each_placeholder() {
+    let mut config = Config::default();
+    config.templates.insert("deploy".to_string(), deploy());
+    let mut app = App::with_config(config);
+
+    app.handle_action(Action::StartAddTask);
+    let labels: Vec<String> = palette_matches(&app)
+        .into_iter()
+        .map(|m| m.entry.label)
+        .collect();
+    assert_eq!(labels[0], "New command");
+    assert!(labels[1].starts_with("deploy: deploy {env}"));
+
+    app.handle_action(Action::PaletteNext);
+    app.handle_action(Action::ExecutePalette);
+    for value in ["prod", "v1.2"] {
+        assert!(matches!(
+            app.input_mode(),
+            Some(InputMode::TemplateValue(_))
+        ));
+        assert!(app.take_jobs().is_empty());
+        for c in value.chars() {
+            app.handle_action(Action::InputChar(c));
+        }
+        app.handle_action(Action::SubmitInput);
+    }
+
+    assert!(app.input_mode().is_none());
+    let jobs = app.take_jobs();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].label.as_deref(), Some("Adding task"));
+}
+
+#[test]
+fn test_blank_command_without_templates() {
+    let mut app = App::new();
+    app.handle_action(Action::StartAddTask);
+    assert!(!app.is_open(Mode::Palette));
+    assert!(matches!(app.input_mode(), Some(InputMode::AddTask)));
+}
@@ -0,0 +1,122 @@
+use lazypueue::ui::{render_input_dialog, TextInput};
+use ratatui::backend::{Backend, TestBackend};
+use ratatui::layout::{Position, Rect};
+use ratatui::style::Style;
+use ratatui::Terminal;
+
+#[test]
+fn test_non_ascii_editing_stays_on_char_boundaries() {
+    let mut input = TextInput::new();
+    for c in "héllo 日本 🦀".chars() {
+        input.insert(c);
+    }
+    assert_eq!(input.cursor, input.value.len());
+
+    input.move_left();
+    input.move_left();
+    input.delete_char();
+    assert_eq!(input.value, "héllo 日 🦀");
+    input.delete_forward();
+    assert_eq!(input.value, "héllo 日🦀");
+
+    input.move_start();
+    input.move_right();
+    input.move_right();
+    input.delete_char();
+    assert_eq!(input.value, "hllo 日🦀");
+    input.insert('ü');
+    assert_eq!(input.value, "hüllo 日🦀");
+}
+
+#[test]
+fn test_grapheme_clusters_are_edited_as_one() {
+    // "e" followed by a combining acute accent, and a family emoji made of
+    // several code points joined with zero width joiners
+    let mut input = TextInput::with_value("ae\u{301}👨\u{200d}👩\u{200d}👧".to_string());
+    input.move_left();
+    assert_eq!(&input.value[input.cursor..], "👨\u{200d}👩\u{200d}👧");
+    input.delete_char();
+    assert_eq!(input.value, "a👨\u{200d}👩\u{200d}👧");
+    input.delete_forward();
+    assert_eq!(input.value, "a");
+}
+
+#[test]
+fn test_cursor_position_counts_display_width() {
+    let mut input = TextInput::with_value("日本 ok".to_string());
+    assert_eq!(input.cursor_position(), (0, 7));
+    input.move_start();
+    input.move_right();
+    assert_eq!(input.cursor_position(), (0, 2));
+}
+
+#[test]
+fn test_multiline_editing() {
+    let mut input = TextInput::new();
+    assert!(!input.insert_newline());
+
+    input.multiline = true;
+    for c in "cat <<EOF".chars() {
+        input.insert(c);
+    }
+    assert!(input.insert_newline());
+    input.insert('日');
+    input.insert('x');
+    assert!(input.insert_newline());
+    for c in "EOF".chars() {
+        input.insert(c);
+    }
+    assert_eq!(input.value, "cat <<EOF\n日x\nEOF");
+    assert_eq!(input.line_count(), 3);
+    assert_eq!(input.cursor_position(), (2, 3));
+
+    // Up and down keep the display column where the line allows it
+    assert!(input.move_up());
+    assert_eq!(input.cursor_position(), (1, 3));
+    assert!(input.move_up());
+    assert_eq!(input.cursor_position(), (0, 3));
+    assert!(!input.move_up());
+    input.move_end();
+    assert!(input.move_down());
+    assert_eq!(input.cursor_position(), (1, 3));
+    input.move_start();
+    assert_eq!(input.cursor_position(), (1, 0));
+    assert!(input.move_down());
+    assert!(!input.move_down());
+
+    input.clear();
+    assert!(!input.multiline);
+}
+
+#[test]
+fn test_long_input_scrolls_to_the_cursor() {
+    let mut terminal = Terminal::new(TestBackend::new(20, 3)).unwrap();
+    let input = TextInput::with_value("echo 0123456789abcdefghij".to_string());
+    terminal
+        .draw(|f| render_input_dialog(f, "Add", &input, Style::default(), Rect::new(0, 0, 20, 3)))
+        .unwrap();
+
+    // 18 columns inside the border, the last one holds the cursor
+    let row: String = (1..19)
+        .map(|x| terminal.backend().buffer()[(x, 1)].symbol().to_string())
+        .collect();
+    assert_eq!(row, "3456789abcdefghij ");
+    assert_eq!(
+        terminal.backend_mut().get_cursor_position().unwrap(),
+        Position::new(18, 1)
+    );
+
+    let mut input = input;
+    input.move_start();
+    terminal
+        .draw(|f| render_input_dialog(f, "Add", &input, Style::default(), Rect::new(0, 0, 20, 3)))
+        .unwrap();
+    let row: String = (1..19)
+        .map(|x| terminal.backend().buffer()[(x, 1)].symbol().to_string())
+        .collect();
+    assert_eq!(row, "echo 0123456789abc");
+    assert_eq!(
+        terminal.backend_mut().get_cursor_position().unwrap(),
+        Position::new(1, 1)
+    );
+}
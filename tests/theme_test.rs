@@ -0,0 +1,103 @@
+use chrono::Local;
+use lazypueue::app::{App, TreeSelection};
+use lazypueue::config::Config;
+use lazypueue::snapshot::render_frame;
+use lazypueue::theme::{Theme, ThemeName};
+use pueue_lib::state::{Group, GroupStatus, State};
+use pueue_lib::task::{Task, TaskResult, TaskStatus};
+use ratatui::style::{Color, Modifier};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+fn app_with_task(theme: Theme) -> App {
+    let mut state = State::new();
+    state.groups.insert(
+        "default".to_string(),
+        Group {
+            status: GroupStatus::Running,
+            parallel_tasks: 1,
+        },
+    );
+    state.add_task(Task::new(
+        "make".to_string(),
+        PathBuf::from("/tmp"),
+        HashMap::new(),
+        "default".to_string(),
+        TaskStatus::Queued {
+            enqueued_at: Local::now(),
+        },
+        vec![],
+        0,
+        None,
+    ));
+    let mut app = App::new();
+    app.theme = theme;
+    app.state = Some(state);
+    app.selection = TreeSelection::Task("default".to_string(), 0);
+    app
+}
+
+#[test]
+fn test_theme_from_config() {
+    let config = Config::parse(
+        "[general]\ntheme = \"high-contrast\"\n",
+        Path::new("config.toml"),
+    )
+    .unwrap();
+    assert_eq!(config.general.theme, Some(ThemeName::HighContrast));
+    assert!(Config::parse(
+        "[general]\ntheme = \"solarized\"\n",
+        Path::new("config.toml")
+    )
+    .is_err());
+
+    // A configured theme wins over NO_COLOR
+    std::env::set_var("NO_COLOR", "1");
+    assert_eq!(Theme::from_env(None), Theme::no_color());
+    assert_eq!(Theme::from_env(Some(ThemeName::Light)), Theme::light());
+    std::env::remove_var("NO_COLOR");
+    assert_eq!(Theme::from_env(None), Theme::dark());
+}
+
+#[test]
+fn test_status_styles() {
+    let theme = Theme::dark();
+    assert_eq!(
+        theme.task_result(&TaskResult::Failed(1)).fg,
+        Some(Color::Red)
+    );
+    assert_eq!(theme.task_result(&TaskResult::Killed), theme.killed);
+    assert_eq!(theme.group_status(&GroupStatus::Paused), theme.group_paused);
+
+    // Light backgrounds get no yellow or light gray text
+    let light = Theme::light();
+    for style in [light.queued, light.stashed, light.muted, light.warn] {
+        assert!(
+            !matches!(style.fg, Some(Color::Yellow | Color::Gray | Color::White)),
+            "{:?}",
+            style
+        );
+    }
+}
+
+#[test]
+fn test_themes_style_the_task_list() {
+    // The selected task's row, inside the border of the task list
+    let selected = |theme: Theme| {
+        let buffer = render_frame(&app_with_task(theme), 100, 30).unwrap();
+        let row = (0..30)
+            .find(|&y| (0..40).any(|x| buffer[(x, y)].symbol() == "#"))
+            .unwrap();
+        buffer[(2, row)].style()
+    };
+
+    let dark = selected(Theme::dark());
+    assert_eq!(dark.bg, Some(Color::White));
+    let light = selected(Theme::light());
+    assert_eq!(light.bg, Some(Color::Blue));
+
+    let plain = selected(Theme::no_color());
+    assert_eq!(plain.fg, Some(Color::Reset));
+    assert_eq!(plain.bg, Some(Color::Reset));
+    assert!(plain.add_modifier.contains(Modifier::REVERSED));
+}
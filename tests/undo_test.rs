@@ -0,0 +1,160 @@
+use chrono::Local;
+use lazypueue::app::{Action, App};
+use lazypueue::daemon::Outcome;
+use lazypueue::palette::palette_matches;
+use lazypueue::undo::{restored_task, UndoEntry, UndoOp, UndoStack, UNDO_CAPACITY};
+use pueue_lib::task::{Task, TaskResult, TaskStatus};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn finished_task() -> Task {
+    let mut task = Task::new(
+        "make test".to_string(),
+        PathBuf::from("/src"),
+        HashMap::from([("CI".to_string(), "1".to_string())]),
+        "build".to_string(),
+        TaskStatus::Done {
+            enqueued_at: Local::now(),
+            start: Local::now(),
+            end: Local::now(),
+            result: TaskResult::Success,
+        },
+        vec![],
+        3,
+        Some("nightly".to_string()),
+    );
+    task.id = 7;
+    task
+}
+
+#[test]
+fn test_undo_stack_is_bounded_and_newest_first() {
+    let mut stack = UndoStack::new();
+    for n in 0..UNDO_CAPACITY + 2 {
+        stack.push(UndoEntry::new(
+            format!("Stash task #{}", n),
+            UndoOp::Enqueue(vec![n]),
+        ));
+    }
+    assert_eq!(stack.len(), UNDO_CAPACITY);
+
+    let newest = format!("Stash task #{}", UNDO_CAPACITY + 1);
+    assert_eq!(stack.iter().next().unwrap().description, newest);
+    assert_eq!(stack.take(0).unwrap().description, newest);
+    assert_eq!(
+        stack.take(1).unwrap().description,
+        format!("Stash task #{}", UNDO_CAPACITY - 1)
+    );
+    assert!(stack.take(UNDO_CAPACITY).is_none());
+}
+
+#[test]
+fn test_failed_undo_goes_back_to_its_place() {
+    let mut stack = UndoStack::new();
+    for n in 0..3 {
+        stack.push(UndoEntry::new(
+            format!("Stash task #{}", n),
+            UndoOp::Enqueue(vec![n]),
+        ));
+    }
+    // The middle entry, first from the oldest
+    let position = stack.len() - 2;
+    let entry = stack.take(1).unwrap();
+    stack.push(UndoEntry::new(
+        "Pause group",
+        UndoOp::StartGroup("default".into()),
+    ));
+    stack.insert(position, entry);
+
+    let descriptions: Vec<&str> = stack.iter().map(|e| e.description.as_str()).collect();
+    assert_eq!(
+        descriptions,
+        vec![
+            "Pause group",
+            "Stash task #2",
+            "Stash task #1",
+            "Stash task #0"
+        ]
+    );
+}
+
+#[test]
+fn test_restored_tasks_keep_their_settings() {
+    let task = finished_task();
+
+    let stashed = restored_task(&task, true);
+    assert!(matches!(
+        stashed.status,
+        TaskStatus::Stashed { enqueue_at: None }
+    ));
+    assert_eq!(stashed.original_command, "make test");
+    assert_eq!(stashed.path, PathBuf::from("/src"));
+    assert_eq!(stashed.envs.get("CI").map(String::as_str), Some("1"));
+    assert_eq!(stashed.group, "build");
+    assert_eq!(stashed.priority, 3);
+    assert_eq!(stashed.label.as_deref(), Some("nightly"));
+
+    // Finished tasks would run again if they were queued
+    assert!(matches!(
+        restored_task(&task, false).status,
+        TaskStatus::Stashed { .. }
+    ));
+    let mut task = task;
+    task.status = TaskStatus::Running {
+        enqueued_at: Local::now(),
+        start: Local::now(),
+    };
+    assert!(matches!(
+        restored_task(&task, false).status,
+        TaskStatus::Queued { .. }
+    ));
+}
+
+#[test]
+fn test_undo_reverts_recorded_operations() {
+    let mut app = App::new();
+
+    app.handle_action(Action::Undo);
+    assert!(app.take_jobs().is_empty());
+    assert_eq!(
+        app.messages.history().next().map(|m| m.text.as_str()),
+        Some("Nothing to undo")
+    );
+
+    // Successful operations are recorded when their outcome arrives
+    app.complete(
+        1,
+        Outcome::new().undoable(UndoEntry::new(
+            "Remove task #7",
+            UndoOp::Readd(vec![finished_task()]),
+        )),
+    );
+    app.complete(
+        2,
+        Outcome::new().undoable(UndoEntry::new("Stash task #2", UndoOp::Enqueue(vec![2]))),
+    );
+
+    app.handle_action(Action::ShowUndoHistory);
+    let labels: Vec<String> = palette_matches(&app)
+        .into_iter()
+        .map(|m| m.entry.label)
+        .collect();
+    assert_eq!(labels.len(), 2);
+    assert!(labels[0].ends_with("Stash task #2"));
+    assert!(labels[1].ends_with("Remove task #7"));
+
+    // Undo the older one from the list
+    app.handle_action(Action::PaletteNext);
+    app.handle_action(Action::ExecutePalette);
+    let jobs = app.take_jobs();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].label.as_deref(), Some("Undoing: Remove task #7"));
+    assert_eq!(app.undo.len(), 1);
+
+    app.handle_action(Action::Undo);
+    assert_eq!(
+        app.take_jobs()[0].label.as_deref(),
+        Some("Undoing: Stash task #2")
+    );
+    assert!(app.undo.is_empty());
+}